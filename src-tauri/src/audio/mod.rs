@@ -1,5 +1,6 @@
 // Audio capture module - High-Compatibility Wireless Bridge
 
+use crate::errors::AudioError;
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use std::sync::{Arc, mpsc};
 use std::sync::atomic::{AtomicU64, Ordering};
@@ -15,6 +16,7 @@ pub struct AudioRecorder {
 #[allow(dead_code)]
 enum Cmd {
     Start,
+    StartStream(tokio::sync::mpsc::UnboundedSender<Vec<f32>>),
     Stop(mpsc::Sender<Vec<f32>>),
     Shutdown,
 }
@@ -28,11 +30,13 @@ impl AudioRecorder {
         })
     }
 
-    pub fn open(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+    pub fn open(&mut self) -> Result<(), AudioError> {
         if self.worker_handle.is_some() { return Ok(()); }
 
         let (cmd_tx, cmd_rx) = mpsc::channel::<Cmd>();
         let (sample_tx, sample_rx) = mpsc::channel::<Vec<f32>>();
+        // The worker reports whether the stream came up before entering its loop
+        let (ready_tx, ready_rx) = mpsc::channel::<Result<(), AudioError>>();
 
         let handle = thread::spawn(move || {
             #[cfg(windows)]
@@ -45,6 +49,7 @@ impl AudioRecorder {
             
             let mut buffer = Vec::new();
             let mut recording = false;
+            let mut frame_tap: Option<tokio::sync::mpsc::UnboundedSender<Vec<f32>>> = None;
             let mut last_heartbeat = Instant::now();
 
             let host = cpal::default_host();
@@ -58,6 +63,7 @@ impl AudioRecorder {
                 },
                 None => {
                     log::error!("NO INPUT DEVICE FOUND");
+                    let _ = ready_tx.send(Err(AudioError::NoInputDevice));
                     return;
                 }
             };
@@ -70,6 +76,7 @@ impl AudioRecorder {
                 },
                 Err(e) => {
                     log::error!("FAILED TO GET HW CONFIG: {}", e);
+                    let _ = ready_tx.send(Err(AudioError::DeviceConfig(e.to_string())));
                     return;
                 }
             };
@@ -126,21 +133,31 @@ impl AudioRecorder {
                         None
                     )
                 },
-                _ => {
+                other => {
                     log::error!("UNSUPPORTED SAMPLE FORMAT");
+                    let _ = ready_tx.send(Err(AudioError::UnsupportedFormat(format!("{:?}", other))));
                     return;
                 }
             };
 
-            let stream = stream_result.expect("FAILED TO START STREAM");
+            let stream = match stream_result {
+                Ok(stream) => stream,
+                Err(e) => {
+                    log::error!("FAILED TO START STREAM: {}", e);
+                    let _ = ready_tx.send(Err(AudioError::StreamFailed(e.to_string())));
+                    return;
+                }
+            };
 
             // Give the driver 500ms to settle before playing
             thread::sleep(Duration::from_millis(500));
             
             if let Err(e) = stream.play() {
                 log::error!("FAILED TO START STREAM PLAYBACK: {}", e);
+                let _ = ready_tx.send(Err(AudioError::StreamFailed(e.to_string())));
                 return;
             }
+            let _ = ready_tx.send(Ok(()));
             
             log::info!("--- HARDWARE BRIDGE LIVE (Wireless Optimizations Applied) ---");
             log::info!("Stream started successfully. Waiting for audio callbacks...");
@@ -158,10 +175,19 @@ impl AudioRecorder {
                             log::info!("WORKER: START CAPTURE");
                             buffer.clear();
                             recording = true;
+                            frame_tap = None;
+                        }
+                        Cmd::StartStream(tap) => {
+                            log::info!("WORKER: START CAPTURE (streaming)");
+                            buffer.clear();
+                            recording = true;
+                            frame_tap = Some(tap);
                         }
                         Cmd::Stop(reply_tx) => {
                             log::info!("WORKER: STOP CAPTURE ({} samples)", buffer.len());
                             recording = false;
+                            // Dropping the tap ends the consumer's frame stream
+                            frame_tap = None;
                             let _ = reply_tx.send(std::mem::take(&mut buffer));
                         }
                         Cmd::Shutdown => return,
@@ -176,11 +202,15 @@ impl AudioRecorder {
                     }
                     if recording {
                         // Simple 3:1 downsampler if 48k -> 16k
-                        if native_rate == 48000 {
-                            for chunk in samples.chunks(3) { buffer.push(chunk[0]); }
+                        let frame: Vec<f32> = if native_rate == 48000 {
+                            samples.chunks(3).map(|chunk| chunk[0]).collect()
                         } else {
-                            buffer.extend_from_slice(&samples);
+                            samples
+                        };
+                        if let Some(tap) = &frame_tap {
+                            let _ = tap.send(frame.clone());
                         }
+                        buffer.extend_from_slice(&frame);
                     }
                 }
 
@@ -196,6 +226,16 @@ impl AudioRecorder {
             }
         });
 
+        match ready_rx.recv_timeout(Duration::from_secs(10)) {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => {
+                let _ = handle.join();
+                return Err(e);
+            }
+            // A worker that neither succeeds nor fails in time has hung in the driver
+            Err(_) => return Err(AudioError::StreamFailed("audio driver did not respond".to_string())),
+        }
+
         self.cmd_tx = Some(cmd_tx);
         self.worker_handle = Some(handle);
         self.sample_rate = 16000; 
@@ -203,9 +243,19 @@ impl AudioRecorder {
         Ok(())
     }
 
-    pub fn start_recording(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        if let Some(tx) = &self.cmd_tx { let _ = tx.send(Cmd::Start); }
-        Ok(())
+    fn send(&self, cmd: Cmd) -> Result<(), AudioError> {
+        let tx = self.cmd_tx.as_ref().ok_or(AudioError::RecorderNotOpen)?;
+        tx.send(cmd).map_err(|_| AudioError::RecorderNotOpen)
+    }
+
+    pub fn start_recording(&mut self) -> Result<(), AudioError> {
+        self.send(Cmd::Start)
+    }
+
+    /// Like `start_recording`, but also forwards each captured frame to `tap`
+    /// so streaming providers can consume audio while the user is speaking.
+    pub fn start_streaming(&mut self, tap: tokio::sync::mpsc::UnboundedSender<Vec<f32>>) -> Result<(), AudioError> {
+        self.send(Cmd::StartStream(tap))
     }
 
     pub fn stop_recording(&mut self) -> Vec<f32> {
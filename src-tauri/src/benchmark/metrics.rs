@@ -0,0 +1,86 @@
+// Benchmark metrics - word and character error rates via Levenshtein alignment
+
+/// Lowercases, drops punctuation (keeping apostrophes inside words) and collapses whitespace,
+/// so "Hello, world." and "hello world" score as identical
+pub fn normalize(text: &str) -> String {
+    let cleaned: String = text
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '\'' { c.to_ascii_lowercase() } else { ' ' })
+        .collect();
+    cleaned
+        .split_whitespace()
+        .map(|word| word.trim_matches('\''))
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Minimum substitutions + insertions + deletions turning `reference` into `hypothesis`
+pub fn edit_distance<T: PartialEq>(reference: &[T], hypothesis: &[T]) -> usize {
+    let mut prev: Vec<usize> = (0..=hypothesis.len()).collect();
+    let mut curr = vec![0; hypothesis.len() + 1];
+
+    for (i, r) in reference.iter().enumerate() {
+        curr[0] = i + 1;
+        for (j, h) in hypothesis.iter().enumerate() {
+            let substitution = prev[j] + usize::from(r != h);
+            curr[j + 1] = substitution.min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+    prev[hypothesis.len()]
+}
+
+/// Edit count and reference length; kept separate so corpus totals weight long files properly
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ErrorCount {
+    pub errors: usize,
+    pub reference_len: usize,
+}
+
+impl ErrorCount {
+    pub fn rate(&self) -> f64 {
+        match self.reference_len {
+            0 if self.errors == 0 => 0.0,
+            0 => 1.0,
+            len => self.errors as f64 / len as f64,
+        }
+    }
+
+    pub fn add(&mut self, other: ErrorCount) {
+        self.errors += other.errors;
+        self.reference_len += other.reference_len;
+    }
+}
+
+pub fn word_errors(reference: &str, hypothesis: &str) -> ErrorCount {
+    let (reference, hypothesis) = (normalize(reference), normalize(hypothesis));
+    let reference: Vec<&str> = reference.split_whitespace().collect();
+    let hypothesis: Vec<&str> = hypothesis.split_whitespace().collect();
+    ErrorCount { errors: edit_distance(&reference, &hypothesis), reference_len: reference.len() }
+}
+
+pub fn char_errors(reference: &str, hypothesis: &str) -> ErrorCount {
+    let reference: Vec<char> = normalize(reference).chars().collect();
+    let hypothesis: Vec<char> = normalize(hypothesis).chars().collect();
+    ErrorCount { errors: edit_distance(&reference, &hypothesis), reference_len: reference.len() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_rates() {
+        assert_eq!(normalize("Hello, World! It's  me."), "hello world it's me");
+        assert_eq!(word_errors("Hello, world.", "hello world").rate(), 0.0);
+
+        // One substitution and one deletion against four reference words
+        let wer = word_errors("the cat sat down", "the bat sat");
+        assert_eq!((wer.errors, wer.reference_len), (2, 4));
+
+        let cer = char_errors("cat", "cut");
+        assert_eq!((cer.errors, cer.reference_len), (1, 3));
+        assert_eq!(word_errors("", "anything").rate(), 1.0);
+    }
+}
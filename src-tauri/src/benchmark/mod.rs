@@ -0,0 +1,245 @@
+// Benchmark module - offline accuracy and speed runs over a local corpus
+//
+// A corpus is a directory of WAV files, each with a reference transcript next to it
+// (`clip.wav` + `clip.txt`). Every file goes through one provider, optionally followed by
+// the FormattingEngine, and is scored for WER, CER and real-time factor.
+
+pub mod metrics;
+pub mod tune;
+
+use crate::formatting::FormattingEngine;
+use crate::transcription::{read_wav_file, AudioBuffer, TranscriptionProvider};
+use metrics::ErrorCount;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+pub struct CorpusItem {
+    pub name: String,
+    pub audio_path: PathBuf,
+    pub reference: String,
+}
+
+/// Finds every `*.wav` in `dir` that has a matching `.txt` reference, sorted by name
+pub fn load_corpus(dir: &Path) -> Result<Vec<CorpusItem>, Box<dyn std::error::Error + Send + Sync>> {
+    let mut items = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let audio_path = entry?.path();
+        if !audio_path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("wav")) {
+            continue;
+        }
+        let reference_path = audio_path.with_extension("txt");
+        let Ok(reference) = std::fs::read_to_string(&reference_path) else {
+            log::warn!("Skipping {}: no reference transcript at {}", audio_path.display(), reference_path.display());
+            continue;
+        };
+        let name = audio_path.file_name().unwrap_or_default().to_string_lossy().to_string();
+        items.push(CorpusItem { name, audio_path, reference: reference.trim().to_string() });
+    }
+    items.sort_by(|a, b| a.name.cmp(&b.name));
+
+    if items.is_empty() {
+        return Err(format!("No .wav files with .txt references found in {}", dir.display()).into());
+    }
+    Ok(items)
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FileResult {
+    pub file: String,
+    pub reference: String,
+    pub hypothesis: String,
+    pub audio_secs: f64,
+    pub elapsed_secs: f64,
+    pub rtf: f64,
+    pub wer: f64,
+    pub cer: f64,
+    pub word_errors: usize,
+    pub reference_words: usize,
+    pub char_errors: usize,
+    pub reference_chars: usize,
+    /// Set when the provider failed; the file then scores as all deletions
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Aggregate {
+    pub files: usize,
+    pub failed: usize,
+    /// Corpus-level rates: total edits over total reference length
+    pub wer: f64,
+    pub cer: f64,
+    pub audio_secs: f64,
+    pub elapsed_secs: f64,
+    pub rtf: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchmarkReport {
+    pub provider: String,
+    pub formatted: bool,
+    pub files: Vec<FileResult>,
+    pub aggregate: Aggregate,
+}
+
+/// Transcribes every corpus item in order; one file failing doesn't stop the run
+pub async fn run(
+    provider: &dyn TranscriptionProvider,
+    corpus: &[CorpusItem],
+    formatter: Option<&FormattingEngine>,
+) -> BenchmarkReport {
+    let mut files = Vec::with_capacity(corpus.len());
+    for item in corpus {
+        let result = match read_wav_file(&item.audio_path) {
+            Ok((audio, sample_rate)) => run_file(provider, item, audio, sample_rate, formatter).await,
+            Err(e) => score(item, String::new(), 0.0, 0.0, Some(format!("Could not read audio: {}", e))),
+        };
+        log::info!("Benchmark {}: WER {:.3}, CER {:.3}, RTF {:.3}", result.file, result.wer, result.cer, result.rtf);
+        files.push(result);
+    }
+
+    let mut words = ErrorCount::default();
+    let mut chars = ErrorCount::default();
+    let (mut audio_secs, mut elapsed_secs) = (0.0, 0.0);
+    for file in &files {
+        words.add(ErrorCount { errors: file.word_errors, reference_len: file.reference_words });
+        chars.add(ErrorCount { errors: file.char_errors, reference_len: file.reference_chars });
+        audio_secs += file.audio_secs;
+        elapsed_secs += file.elapsed_secs;
+    }
+
+    let aggregate = Aggregate {
+        files: files.len(),
+        failed: files.iter().filter(|f| f.error.is_some()).count(),
+        wer: words.rate(),
+        cer: chars.rate(),
+        audio_secs,
+        elapsed_secs,
+        rtf: if audio_secs > 0.0 { elapsed_secs / audio_secs } else { 0.0 },
+    };
+
+    BenchmarkReport { provider: provider.name().to_string(), formatted: formatter.is_some(), files, aggregate }
+}
+
+async fn run_file(
+    provider: &dyn TranscriptionProvider,
+    item: &CorpusItem,
+    audio: AudioBuffer,
+    sample_rate: u32,
+    formatter: Option<&FormattingEngine>,
+) -> FileResult {
+    let audio_secs = audio.len() as f64 / sample_rate.max(1) as f64;
+    let start = Instant::now();
+    let result = provider.transcribe(audio, sample_rate).await;
+    let (hypothesis, error) = match result {
+        Ok(text) => (formatter.map_or(text.clone(), |engine| engine.format(&text)), None),
+        Err(e) => (String::new(), Some(e.to_string())),
+    };
+    score(item, hypothesis, audio_secs, start.elapsed().as_secs_f64(), error)
+}
+
+fn score(item: &CorpusItem, hypothesis: String, audio_secs: f64, elapsed_secs: f64, error: Option<String>) -> FileResult {
+    let words = metrics::word_errors(&item.reference, &hypothesis);
+    let chars = metrics::char_errors(&item.reference, &hypothesis);
+    FileResult {
+        file: item.name.clone(),
+        reference: item.reference.clone(),
+        hypothesis,
+        audio_secs,
+        elapsed_secs,
+        rtf: if audio_secs > 0.0 { elapsed_secs / audio_secs } else { 0.0 },
+        wer: words.rate(),
+        cer: chars.rate(),
+        word_errors: words.errors,
+        reference_words: words.reference_len,
+        char_errors: chars.errors,
+        reference_chars: chars.reference_len,
+        error,
+    }
+}
+
+impl BenchmarkReport {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut md = format!(
+            "# Benchmark: {}{}\n\n",
+            self.provider,
+            if self.formatted { " (formatted)" } else { "" }
+        );
+        let agg = &self.aggregate;
+        md.push_str("| Files | Failed | WER | CER | Audio (s) | Elapsed (s) | RTF |\n");
+        md.push_str("|---|---|---|---|---|---|---|\n");
+        md.push_str(&format!(
+            "| {} | {} | {:.2}% | {:.2}% | {:.1} | {:.1} | {:.3} |\n\n",
+            agg.files, agg.failed, agg.wer * 100.0, agg.cer * 100.0, agg.audio_secs, agg.elapsed_secs, agg.rtf
+        ));
+
+        md.push_str("| File | WER | CER | RTF | Hypothesis |\n");
+        md.push_str("|---|---|---|---|---|\n");
+        for file in &self.files {
+            let hypothesis = match &file.error {
+                Some(error) => format!("**error:** {}", error),
+                None => file.hypothesis.clone(),
+            };
+            md.push_str(&format!(
+                "| {} | {:.2}% | {:.2}% | {:.3} | {} |\n",
+                file.file, file.wer * 100.0, file.cer * 100.0, file.rtf, hypothesis.replace('|', "\\|")
+            ));
+        }
+        md
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transcription::write_wav_file;
+    use async_trait::async_trait;
+
+    /// Echoes a fixed transcript per clip length, so results are deterministic
+    struct LengthProvider;
+
+    #[async_trait]
+    impl TranscriptionProvider for LengthProvider {
+        async fn transcribe(&self, audio: AudioBuffer, _sample_rate: u32) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+            match audio.len() {
+                16000 => Ok("Hello world.".to_string()),
+                _ => Err("engine crashed".into()),
+            }
+        }
+
+        fn name(&self) -> &'static str {
+            "Length"
+        }
+
+        fn supports_streaming(&self) -> bool {
+            false
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_scores_each_file_and_aggregates() {
+        let dir = std::env::temp_dir().join(format!("vanta_bench_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        write_wav_file(&vec![0.1; 16000], 16000, &dir.join("a.wav")).unwrap();
+        std::fs::write(dir.join("a.txt"), "hello world\n").unwrap();
+        write_wav_file(&vec![0.1; 8000], 16000, &dir.join("b.wav")).unwrap();
+        std::fs::write(dir.join("b.txt"), "good morning").unwrap();
+        write_wav_file(&vec![0.1; 8000], 16000, &dir.join("unreferenced.wav")).unwrap();
+
+        let corpus = load_corpus(&dir).unwrap();
+        let report = run(&LengthProvider, &corpus, None).await;
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(report.files.len(), 2);
+        assert_eq!(report.files[0].wer, 0.0);
+        assert_eq!(report.files[1].error.as_deref(), Some("engine crashed"));
+        // Two of four reference words missing across the corpus
+        assert_eq!(report.aggregate.wer, 0.5);
+        assert_eq!(report.aggregate.failed, 1);
+        assert!(report.to_markdown().contains("| a.wav | 0.00% |"));
+    }
+}
@@ -0,0 +1,354 @@
+// Hardware tuning - which installed model and thread count this machine can run fast enough
+//
+// A short bundled clip goes through every installed Whisper model at a few thread counts.
+// Each run is timed and the child's peak memory is sampled; the recommendation is the most
+// accurate model family whose expected latency for a typical dictation stays under the
+// target, at its fastest thread count. The first run of each model only warms the OS file
+// cache and is not scored.
+
+use super::{run, CorpusItem};
+use crate::config::AppConfig;
+use crate::errors::TuningError;
+use crate::models::{self, ModelDescriptor, ModelProvider};
+use crate::transcription::{chain::create_provider, memory, read_wav_file, TranscriptionProvider};
+use serde::Serialize;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Latency targets are for a dictation of this length
+pub const TYPICAL_DICTATION_SECS: f64 = 5.0;
+
+/// File name of the clip in the app's resources, with an optional `.txt` reference
+pub const CLIP_NAME: &str = "benchmark.wav";
+
+/// Larger models are skipped once a smaller one is this far over the target
+const GIVE_UP_FACTOR: f64 = 2.0;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Trial {
+    pub model: String,
+    pub threads: usize,
+    pub elapsed_secs: f64,
+    pub rtf: f64,
+    /// Estimated for a `TYPICAL_DICTATION_SECS` dictation
+    pub expected_latency_ms: u64,
+    pub peak_memory_mb: Option<u64>,
+    /// Only when the clip has a reference transcript
+    pub wer: Option<f64>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Recommendation {
+    pub model: String,
+    pub threads: usize,
+    pub expected_latency_ms: u64,
+    /// False when nothing met the target and this is just the fastest run
+    pub meets_target: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TuneReport {
+    pub clip: String,
+    pub audio_secs: f64,
+    pub logical_cores: usize,
+    pub latency_target_ms: u64,
+    pub trials: Vec<Trial>,
+    /// Models not tried because a smaller one was already far too slow
+    pub skipped: Vec<String>,
+    pub recommendation: Option<Recommendation>,
+}
+
+/// Where the clip is bundled; mirrors the whisper executable lookup
+pub fn bundled_clip() -> Option<PathBuf> {
+    let mut exe_dir = std::env::current_exe().unwrap_or_else(|_| PathBuf::from("."));
+    exe_dir.pop();
+    [
+        exe_dir.join("resources").join(CLIP_NAME),  // Installed app
+        exe_dir.join("..").join("resources").join(CLIP_NAME),  // Dev mode
+        exe_dir.join(CLIP_NAME),  // Same directory
+    ]
+    .into_iter()
+    .find(|path| path.is_file())
+}
+
+/// Half the cores, the default (all but one, at most 8) and all of them
+pub fn thread_counts(logical_cores: usize) -> Vec<usize> {
+    let cores = logical_cores.max(1);
+    let mut counts = vec![(cores / 2).max(1), cores.saturating_sub(1).clamp(1, 8), cores];
+    counts.sort_unstable();
+    counts.dedup();
+    counts
+}
+
+/// Installed Whisper models worth trying for `language`, smallest first
+pub fn candidates(installed: &[ModelDescriptor], language: &str) -> Vec<ModelDescriptor> {
+    let english = language.to_lowercase().starts_with("en");
+    let mut models: Vec<ModelDescriptor> = installed.iter()
+        .filter(|model| model.provider == ModelProvider::Whisper)
+        .filter(|model| english || model.languages != ["en"])
+        .cloned()
+        .collect();
+    models.sort_by_key(|model| model.size_mb);
+    models
+}
+
+/// Accuracy proxy: size of the family's unquantized model, so quantized variants
+/// rank with the model they were made from
+fn family_rank(model: &str, catalog: &[ModelDescriptor]) -> u64 {
+    let Some(family) = catalog.iter().find(|m| m.id == model).map(|m| &m.family) else {
+        return 0;
+    };
+    catalog.iter().filter(|m| &m.family == family).map(|m| m.size_mb).max().unwrap_or(0)
+}
+
+pub fn recommend(trials: &[Trial], catalog: &[ModelDescriptor], latency_target_ms: u64) -> Option<Recommendation> {
+    let ok: Vec<&Trial> = trials.iter().filter(|trial| trial.error.is_none()).collect();
+    let within: Vec<&Trial> = ok.iter().copied().filter(|trial| trial.expected_latency_ms <= latency_target_ms).collect();
+
+    let best = if within.is_empty() {
+        ok.iter().copied().min_by_key(|trial| trial.expected_latency_ms)
+    } else {
+        let top = within.iter().map(|trial| family_rank(&trial.model, catalog)).max()?;
+        within.iter().copied()
+            .filter(|trial| family_rank(&trial.model, catalog) == top)
+            .min_by_key(|trial| trial.expected_latency_ms)
+    }?;
+
+    Some(Recommendation {
+        model: best.model.clone(),
+        threads: best.threads,
+        expected_latency_ms: best.expected_latency_ms,
+        meets_target: !within.is_empty(),
+    })
+}
+
+/// Runs every model in `models` (smallest first) at every thread count and ranks them by
+/// their family in `catalog`. `provider_for(model, threads)` builds the provider;
+/// `on_trial` sees each result as it lands.
+pub async fn tune<F, P>(
+    clip: &CorpusItem,
+    models: &[ModelDescriptor],
+    catalog: &[ModelDescriptor],
+    threads: &[usize],
+    latency_target_ms: u64,
+    provider_for: F,
+    mut on_trial: P,
+) -> TuneReport
+where
+    F: Fn(&str, usize) -> Option<Arc<dyn TranscriptionProvider>>,
+    P: FnMut(&Trial),
+{
+    let mut trials = Vec::new();
+    let mut skipped = Vec::new();
+    let mut audio_secs = 0.0;
+
+    for (index, model) in models.iter().enumerate() {
+        // Warm-up: loads the model file into the OS cache like everyday use does
+        if let Some(provider) = threads.last().and_then(|&n| provider_for(&model.id, n)) {
+            let _ = run(provider.as_ref(), std::slice::from_ref(clip), None).await;
+        }
+
+        for &count in threads {
+            let Some(provider) = provider_for(&model.id, count) else {
+                continue;
+            };
+            let (report, peak_bytes) = memory::with_probe(run(provider.as_ref(), std::slice::from_ref(clip), None)).await;
+            let Some(file) = report.files.into_iter().next() else {
+                continue;
+            };
+            audio_secs = file.audio_secs;
+            let trial = Trial {
+                model: model.id.clone(),
+                threads: count,
+                elapsed_secs: file.elapsed_secs,
+                rtf: file.rtf,
+                expected_latency_ms: (file.rtf * TYPICAL_DICTATION_SECS * 1000.0) as u64,
+                peak_memory_mb: peak_bytes.map(|bytes| bytes / 1_000_000),
+                wer: (!clip.reference.is_empty() && file.error.is_none()).then_some(file.wer),
+                error: file.error,
+            };
+            log::info!("Tuning {} x{} threads: RTF {:.3}, ~{}ms per dictation, {:?}MB",
+                trial.model, trial.threads, trial.rtf, trial.expected_latency_ms, trial.peak_memory_mb);
+            on_trial(&trial);
+            trials.push(trial);
+        }
+
+        let fastest = trials.iter()
+            .filter(|trial| trial.model == model.id && trial.error.is_none())
+            .map(|trial| trial.expected_latency_ms)
+            .min();
+        if fastest.is_some_and(|ms| ms as f64 > latency_target_ms as f64 * GIVE_UP_FACTOR) {
+            skipped.extend(models[index + 1..].iter().map(|model| model.id.clone()));
+            log::info!("{} is far over the {}ms target, skipping larger models", model.id, latency_target_ms);
+            break;
+        }
+    }
+
+    let recommendation = recommend(&trials, catalog, latency_target_ms);
+    log::info!("Tuning recommendation: {:?}", recommendation);
+    TuneReport {
+        clip: clip.name.clone(),
+        audio_secs,
+        logical_cores: std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1),
+        latency_target_ms,
+        trials,
+        skipped,
+        recommendation,
+    }
+}
+
+/// Tunes the installed models for `config`'s language with the configured Whisper
+/// settings, using `clip` or the bundled clip
+pub async fn tune_installed<P: FnMut(&Trial)>(config: &AppConfig, clip: Option<PathBuf>, latency_target_ms: u64, on_trial: P) -> Result<TuneReport, TuningError> {
+    let audio_path = clip.or_else(bundled_clip).ok_or_else(|| TuningError::ClipMissing(CLIP_NAME.to_string()))?;
+    if let Err(e) = read_wav_file(&audio_path) {
+        return Err(TuningError::ClipUnreadable { path: audio_path, details: e.to_string() });
+    }
+    let reference = std::fs::read_to_string(audio_path.with_extension("txt")).unwrap_or_default();
+    let name = audio_path.file_name().unwrap_or_default().to_string_lossy().to_string();
+    let clip = CorpusItem { name, audio_path, reference: reference.trim().to_string() };
+
+    let catalog = models::catalog();
+    let models_dir = models::models_dir();
+    let installed: Vec<ModelDescriptor> = catalog.iter().filter(|model| model.is_installed(&models_dir)).cloned().collect();
+    let models = candidates(&installed, &config.language);
+    if models.is_empty() {
+        return Err(TuningError::NoModels { language: config.language.clone() });
+    }
+
+    let threads = thread_counts(std::thread::available_parallelism().map(|n| n.get()).unwrap_or(4));
+    let provider_for = |model: &str, threads: usize| {
+        let mut config = config.clone();
+        config.whisper_model = model.to_string();
+        config.whisper.threads = threads;
+        create_provider("Whisper", &config)
+    };
+    Ok(tune(&clip, &models, &catalog, &threads, latency_target_ms, provider_for, on_trial).await)
+}
+
+impl TuneReport {
+    /// Writes the recommendation into `config` if it meets the target
+    pub fn apply_to(&self, config: &mut AppConfig) -> bool {
+        let Some(best) = self.recommendation.as_ref().filter(|best| best.meets_target) else {
+            return false;
+        };
+        config.whisper_model = best.model.clone();
+        config.whisper.threads = best.threads;
+        config.latency_target_ms = self.latency_target_ms;
+        true
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut md = format!(
+            "# Tuning: {} ({:.1}s), {} cores, target {}ms per {}s dictation\n\n",
+            self.clip, self.audio_secs, self.logical_cores, self.latency_target_ms, TYPICAL_DICTATION_SECS
+        );
+        md.push_str("| Model | Threads | RTF | Latency (ms) | Peak memory (MB) | WER |\n");
+        md.push_str("|---|---|---|---|---|---|\n");
+        for trial in &self.trials {
+            let memory = trial.peak_memory_mb.map_or("-".to_string(), |mb| mb.to_string());
+            let wer = trial.wer.map_or("-".to_string(), |wer| format!("{:.2}%", wer * 100.0));
+            match &trial.error {
+                Some(error) => md.push_str(&format!("| {} | {} | **error:** {} | | | |\n", trial.model, trial.threads, error.replace('|', "\\|"))),
+                None => md.push_str(&format!("| {} | {} | {:.3} | {} | {} | {} |\n", trial.model, trial.threads, trial.rtf, trial.expected_latency_ms, memory, wer)),
+            }
+        }
+        if !self.skipped.is_empty() {
+            md.push_str(&format!("\nSkipped (a smaller model was already too slow): {}\n", self.skipped.join(", ")));
+        }
+        match &self.recommendation {
+            Some(best) if best.meets_target => md.push_str(&format!("\n**Recommended:** {} with {} threads (~{}ms)\n", best.model, best.threads, best.expected_latency_ms)),
+            Some(best) => md.push_str(&format!("\n**Nothing meets the target.** Fastest: {} with {} threads (~{}ms)\n", best.model, best.threads, best.expected_latency_ms)),
+            None => md.push_str("\nNo run succeeded.\n"),
+        }
+        md
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ModelFile;
+    use crate::transcription::{write_wav_file, AudioBuffer};
+    use async_trait::async_trait;
+    use std::time::Duration;
+
+    /// Takes `work_ms / threads` to "transcribe", like a CPU-bound model
+    struct TimedProvider {
+        work_ms: u64,
+        threads: usize,
+    }
+
+    #[async_trait]
+    impl TranscriptionProvider for TimedProvider {
+        async fn transcribe(&self, _audio: AudioBuffer, _sample_rate: u32) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+            tokio::time::sleep(Duration::from_millis(self.work_ms / self.threads as u64)).await;
+            Ok("ask not what your country can do for you".to_string())
+        }
+
+        fn name(&self) -> &'static str {
+            "Timed"
+        }
+
+        fn supports_streaming(&self) -> bool {
+            false
+        }
+    }
+
+    fn model(id: &str, family: &str, size_mb: u64, languages: &[&str]) -> ModelDescriptor {
+        ModelDescriptor {
+            id: id.to_string(),
+            display_name: id.to_string(),
+            provider: ModelProvider::Whisper,
+            family: family.to_string(),
+            languages: languages.iter().map(|l| l.to_string()).collect(),
+            quantization: None,
+            size_mb,
+            ram_mb: size_mb * 2,
+            files: vec![ModelFile { path: format!("ggml-{}.bin", id), url: String::new(), size: None, sha256: None }],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_picks_largest_family_under_target_at_fastest_threads() {
+        let dir = std::env::temp_dir().join(format!("vanta_tune_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let audio_path = dir.join(CLIP_NAME);
+        // 1s clip: latency for a 5s dictation is 5x the elapsed time
+        write_wav_file(&vec![0.1; 16000], 16000, &audio_path).unwrap();
+        let clip = CorpusItem { name: CLIP_NAME.to_string(), audio_path, reference: "ask not what your country can do for you".to_string() };
+
+        let installed = vec![
+            model("medium", "medium", 1500, &[]),
+            model("tiny", "tiny", 75, &[]),
+            model("small-q8_0", "small", 252, &[]),
+            model("small.en", "small", 466, &["en"]),
+            model("large", "large", 2900, &[]),
+        ];
+        let models = candidates(&installed, "de-DE");
+        assert_eq!(models.iter().map(|m| m.id.as_str()).collect::<Vec<_>>(), ["tiny", "small-q8_0", "medium", "large"]);
+
+        // tiny: 20ms of work, small: 40ms, medium: 400ms (1s per dictation even on 2 threads)
+        let work = |id: &str| match id { "tiny" => 20, "small-q8_0" => 40, _ => 400 };
+        let mut seen = 0;
+        let report = tune(&clip, &models, &installed, &[1, 2], 300, |id, threads| {
+            Some(Arc::new(TimedProvider { work_ms: work(id), threads }) as Arc<dyn TranscriptionProvider>)
+        }, |_| seen += 1).await;
+        let _ = std::fs::remove_dir_all(dir);
+
+        assert_eq!(seen, 6);
+        assert_eq!(report.skipped, ["large"]);
+        assert_eq!(report.trials[0].wer, Some(0.0));
+        let recommendation = report.recommendation.clone().unwrap();
+        assert_eq!((recommendation.model.as_str(), recommendation.threads, recommendation.meets_target), ("small-q8_0", 2, true));
+        let mut config = AppConfig::default();
+        assert!(report.apply_to(&mut config));
+        assert_eq!((config.whisper_model.as_str(), config.whisper.threads), ("small-q8_0", 2));
+        assert!(report.to_markdown().contains("**Recommended:** small-q8_0 with 2 threads"));
+
+        // Nothing fast enough: fall back to the fastest run
+        let fallback = recommend(&report.trials, &models, 1).unwrap();
+        assert_eq!((fallback.model.as_str(), fallback.threads, fallback.meets_target), ("tiny", 2, false));
+    }
+}
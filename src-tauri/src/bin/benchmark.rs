@@ -0,0 +1,114 @@
+// Offline accuracy benchmark
+//
+//   vanta-benchmark <corpus-dir> [--provider Whisper] [--model base] [--format] [--out report]
+//   vanta-benchmark --tune [--clip clip.wav] [--target 1500] [--apply] [--out report]
+//
+// Writes <out>.json and <out>.md when --out is given, otherwise prints Markdown to stdout.
+// --tune times the installed Whisper models instead; --apply saves the recommendation.
+
+use std::path::PathBuf;
+use vanta_dictate_lib::benchmark::{self, tune};
+use vanta_dictate_lib::config::{AppConfig, ConfigManager};
+use vanta_dictate_lib::formatting::{FormattingEngine, TranscriptionMode};
+use vanta_dictate_lib::transcription::chain::create_provider;
+
+const USAGE: &str = "usage: vanta-benchmark <corpus-dir> [--provider NAME] [--model NAME] [--format] [--out PATH]\n       vanta-benchmark --tune [--clip PATH] [--target MS] [--apply] [--out PATH]";
+
+fn main() {
+    if let Err(e) = run() {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+}
+
+fn run() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let _ = simplelog::TermLogger::init(
+        simplelog::LevelFilter::Warn,
+        simplelog::Config::default(),
+        simplelog::TerminalMode::Stderr,
+        simplelog::ColorChoice::Auto,
+    );
+
+    let mut config = ConfigManager::new().load_config();
+    vanta_dictate_lib::paths::set_models_dir(&config.custom_models_dir);
+    let mut corpus_dir = None;
+    let mut provider_name = config.provider.clone();
+    let mut format = false;
+    let mut out = None;
+    let mut tune = false;
+    let mut clip = None;
+    let mut target = config.latency_target_ms;
+    let mut apply = false;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--provider" => provider_name = args.next().ok_or(USAGE)?,
+            "--model" => config.whisper_model = args.next().ok_or(USAGE)?,
+            "--format" => format = true,
+            "--out" => out = Some(std::path::PathBuf::from(args.next().ok_or(USAGE)?)),
+            "--tune" => tune = true,
+            "--clip" => clip = Some(std::path::PathBuf::from(args.next().ok_or(USAGE)?)),
+            "--target" => target = args.next().ok_or(USAGE)?.parse()?,
+            "--apply" => apply = true,
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return Ok(());
+            }
+            _ if corpus_dir.is_none() && !arg.starts_with("--") => corpus_dir = Some(std::path::PathBuf::from(arg)),
+            _ => return Err(format!("unexpected argument '{}'\n{}", arg, USAGE).into()),
+        }
+    }
+
+    if tune {
+        return run_tuning(config, clip, target, apply, out);
+    }
+
+    let corpus_dir = corpus_dir.ok_or(USAGE)?;
+    let corpus = benchmark::load_corpus(&corpus_dir)?;
+    let provider = create_provider(&provider_name, &config).ok_or_else(|| format!("unknown provider '{}'", provider_name))?;
+    let formatter = format.then(|| FormattingEngine::new(TranscriptionMode::Formatted));
+
+    eprintln!("Benchmarking {} on {} files...", provider.name(), corpus.len());
+    let runtime = tokio::runtime::Runtime::new()?;
+    let report = runtime.block_on(benchmark::run(provider.as_ref(), &corpus, formatter.as_ref()));
+
+    match out {
+        Some(out) => {
+            std::fs::write(out.with_extension("json"), report.to_json())?;
+            std::fs::write(out.with_extension("md"), report.to_markdown())?;
+            eprintln!("Wrote {} and {}", out.with_extension("json").display(), out.with_extension("md").display());
+        }
+        None => print!("{}", report.to_markdown()),
+    }
+    Ok(())
+}
+
+fn run_tuning(
+    mut config: AppConfig,
+    clip: Option<PathBuf>,
+    target: u64,
+    apply: bool,
+    out: Option<PathBuf>,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    eprintln!("Tuning installed models for a {}ms target...", target);
+    let runtime = tokio::runtime::Runtime::new()?;
+    let report = runtime.block_on(tune::tune_installed(&config, clip, target, |trial| {
+        eprintln!("  {} x{} threads: ~{}ms", trial.model, trial.threads, trial.expected_latency_ms);
+    }))?;
+
+    match out {
+        Some(out) => {
+            std::fs::write(out.with_extension("json"), serde_json::to_string_pretty(&report)?)?;
+            std::fs::write(out.with_extension("md"), report.to_markdown())?;
+            eprintln!("Wrote {} and {}", out.with_extension("json").display(), out.with_extension("md").display());
+        }
+        None => print!("{}", report.to_markdown()),
+    }
+
+    if apply && report.apply_to(&mut config) {
+        ConfigManager::new().save_config(&config)?;
+        eprintln!("Saved {} with {} threads to the config", config.whisper_model, config.whisper.threads);
+    }
+    Ok(())
+}
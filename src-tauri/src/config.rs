@@ -1,7 +1,13 @@
 use serde::{Deserialize, Serialize};
 use std::fs::{self, File};
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use crate::transcription::command::OutputParser;
+use crate::errors::ConfigError;
+use crate::pipeline::BackpressurePolicy;
+use crate::transcription::routing::RoutingRule;
+use crate::transcription::WhisperParams;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct AppConfig {
@@ -25,6 +31,108 @@ pub struct AppConfig {
     
     #[serde(default = "default_model")]
     pub whisper_model: String,
+    
+    // whisper.cpp decoding options (threads, beam search, fallback, ...)
+    #[serde(default)]
+    pub whisper: WhisperParams,
+    
+    // ONNX provider model (see models::catalog)
+    #[serde(default = "default_onnx_model")]
+    pub onnx_model: String,
+    
+    // Streaming cloud provider (WebSocket)
+    #[serde(default = "default_streaming_endpoint")]
+    pub streaming_endpoint: String,
+    
+    // Wyoming protocol provider (self-hosted speech servers)
+    #[serde(default = "default_wyoming_host")]
+    pub wyoming_host: String,
+    
+    #[serde(default = "default_wyoming_port")]
+    pub wyoming_port: u16,
+    
+    // External command provider, e.g. "my-stt --in {wav} --lang {lang}"
+    #[serde(default)]
+    pub command_template: String,
+    
+    #[serde(default)]
+    pub command_output: OutputParser,
+    
+    // Ordered fallback chain; empty means just `provider`
+    #[serde(default)]
+    pub provider_chain: Vec<ChainEntry>,
+    
+    // Routing policy: first matching rule picks provider/model per utterance
+    #[serde(default)]
+    pub routing_rules: Vec<RoutingRule>,
+    
+    // host:port that rules with a network condition connect to; unset = never probe
+    #[serde(default)]
+    pub network_probe_address: Option<String>,
+    
+    // Two-pass: inject a fast draft, then replace it with the refined transcript
+    #[serde(default)]
+    pub two_pass_enabled: bool,
+    
+    #[serde(default = "default_draft_model")]
+    pub two_pass_draft_model: String,
+    
+    #[serde(default = "default_two_pass_min_secs")]
+    pub two_pass_min_secs: f32,
+    
+    // Shadow mode: also transcribe with this provider and log the comparison; empty = off
+    #[serde(default)]
+    pub shadow_provider: String,
+    
+    // Whisper model for the shadow provider; empty = same as `whisper_model`
+    #[serde(default)]
+    pub shadow_whisper_model: String,
+    
+    // Context: pass the last few transcripts sent to the same window to the decoder
+    #[serde(default)]
+    pub context_enabled: bool,
+    
+    #[serde(default = "default_context_max_dictations")]
+    pub context_max_dictations: usize,
+    
+    // Forget the context after this long without a dictation
+    #[serde(default = "default_context_idle_reset_secs")]
+    pub context_idle_reset_secs: u64,
+    
+    // Keep cloud dictations that fail while offline and retry them in the background
+    #[serde(default = "default_true")]
+    pub offline_queue_enabled: bool,
+    
+    // Where to fetch a newer model catalog from; empty = bundled catalog only
+    #[serde(default)]
+    pub model_catalog_url: String,
+    
+    // Hardware tuning: longest acceptable wait after a typical 5-second dictation
+    #[serde(default = "default_latency_target_ms")]
+    pub latency_target_ms: u64,
+    
+    // whisper.cpp program (or the folder holding it); empty = bundled copy, then PATH
+    #[serde(default)]
+    pub whisper_binary_path: String,
+    
+    // Keep models here instead of the app data folder; empty = default
+    #[serde(default)]
+    pub custom_models_dir: String,
+    
+    // Job queue: what to do with new dictations while earlier ones are still processing
+    #[serde(default)]
+    pub queue_policy: BackpressurePolicy,
+    
+    #[serde(default = "default_max_queued_jobs")]
+    pub max_queued_jobs: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ChainEntry {
+    pub provider: String,
+    
+    #[serde(default = "default_provider_timeout")]
+    pub timeout_ms: u64,
 }
 
 fn default_false() -> bool { false }
@@ -32,6 +140,17 @@ fn default_true() -> bool { true }
 fn default_focus_delay() -> u64 { 100 }
 fn default_provider() -> String { "Whisper".to_string() }
 fn default_model() -> String { "base".to_string() }
+fn default_onnx_model() -> String { "moonshine-tiny".to_string() }
+fn default_provider_timeout() -> u64 { 60_000 }
+fn default_draft_model() -> String { "tiny".to_string() }
+fn default_two_pass_min_secs() -> f32 { 6.0 }
+fn default_max_queued_jobs() -> usize { 3 }
+fn default_latency_target_ms() -> u64 { 1500 }
+fn default_context_max_dictations() -> usize { 3 }
+fn default_context_idle_reset_secs() -> u64 { 120 }
+fn default_streaming_endpoint() -> String { "ws://127.0.0.1:8765/v1/stream".to_string() }
+fn default_wyoming_host() -> String { "127.0.0.1".to_string() }
+fn default_wyoming_port() -> u16 { 10300 }
 
 impl Default for AppConfig {
     fn default() -> Self {
@@ -44,8 +163,68 @@ impl Default for AppConfig {
             enable_typing_fallback: true,
             provider: "Whisper".to_string(),
             whisper_model: "base".to_string(),
+            whisper: WhisperParams::default(),
+            onnx_model: default_onnx_model(),
+            streaming_endpoint: default_streaming_endpoint(),
+            wyoming_host: default_wyoming_host(),
+            wyoming_port: default_wyoming_port(),
+            command_template: String::new(),
+            command_output: OutputParser::Stdout,
+            provider_chain: Vec::new(),
+            routing_rules: Vec::new(),
+            network_probe_address: None,
+            two_pass_enabled: false,
+            two_pass_draft_model: default_draft_model(),
+            two_pass_min_secs: default_two_pass_min_secs(),
+            shadow_provider: String::new(),
+            shadow_whisper_model: String::new(),
+            context_enabled: false,
+            context_max_dictations: default_context_max_dictations(),
+            context_idle_reset_secs: default_context_idle_reset_secs(),
+            offline_queue_enabled: true,
+            model_catalog_url: String::new(),
+            latency_target_ms: default_latency_target_ms(),
+            whisper_binary_path: String::new(),
+            custom_models_dir: String::new(),
+            queue_policy: BackpressurePolicy::default(),
+            max_queued_jobs: default_max_queued_jobs(),
+        }
+    }
+}
+
+impl AppConfig {
+    /// The chain the pipeline actually runs, falling back to the single `provider` setting
+    pub fn effective_provider_chain(&self) -> Vec<ChainEntry> {
+        if self.provider_chain.is_empty() {
+            vec![ChainEntry {
+                provider: self.provider.clone(),
+                timeout_ms: default_provider_timeout(),
+            }]
+        } else {
+            self.provider_chain.clone()
         }
     }
+
+    /// Model ids some setting would load: the chain's models, routing overrides, the
+    /// two-pass draft model (when two-pass is on) and the shadow model
+    pub fn referenced_models(&self) -> Vec<&str> {
+        let chain = self.effective_provider_chain();
+        let mut ids = Vec::new();
+        if chain.iter().any(|entry| entry.provider == "Whisper") {
+            ids.push(self.whisper_model.as_str());
+        }
+        if chain.iter().any(|entry| entry.provider == "Onnx") {
+            ids.push(self.onnx_model.as_str());
+        }
+        ids.extend(self.routing_rules.iter().filter_map(|rule| rule.model.as_deref()));
+        if self.two_pass_enabled {
+            ids.push(self.two_pass_draft_model.as_str());
+        }
+        if !self.shadow_whisper_model.is_empty() {
+            ids.push(self.shadow_whisper_model.as_str());
+        }
+        ids
+    }
 }
 
 pub struct ConfigManager {
@@ -54,14 +233,20 @@ pub struct ConfigManager {
 
 impl ConfigManager {
     pub fn new() -> Self {
-        let mut path = dirs::config_dir().unwrap_or_else(|| PathBuf::from("."));
-        path.push("VantaDictate");
-        if !path.exists() {
-            let _ = fs::create_dir_all(&path);
+        let dir = &crate::paths::storage().config_dir;
+        if !dir.exists() {
+            let _ = fs::create_dir_all(dir);
         }
-        path.push("config.json");
         
-        Self { config_path: path }
+        Self { config_path: dir.join("config.json") }
+    }
+
+    pub fn with_path(config_path: PathBuf) -> Self {
+        Self { config_path }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.config_path
     }
 
     pub fn load_config(&self) -> AppConfig {
@@ -73,10 +258,102 @@ impl ConfigManager {
         AppConfig::default()
     }
 
-    pub fn save_config(&self, config: &AppConfig) -> Result<(), Box<dyn std::error::Error>> {
+    /// Like `load_config`, but a file that exists and doesn't parse is an error rather
+    /// than the defaults, so a half-written or hand-broken file never resets settings
+    pub fn try_load_config(&self) -> Result<AppConfig, ConfigError> {
+        let json = match fs::read_to_string(&self.config_path) {
+            Ok(json) => json,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(AppConfig::default()),
+            Err(source) => return Err(ConfigError::Io { path: self.config_path.clone(), source }),
+        };
+        serde_json::from_str(&json).map_err(|source| ConfigError::Parse { path: self.config_path.clone(), source })
+    }
+
+    fn modified(&self) -> Option<SystemTime> {
+        fs::metadata(&self.config_path).and_then(|meta| meta.modified()).ok()
+    }
+
+    /// Polls the config file every `interval` and calls `on_change` with each new version
+    /// that parses. Edits made by hand or by another tool are picked up this way; saves from
+    /// the settings window are applied directly and arrive here as no-op reloads.
+    pub async fn watch<F: Fn(AppConfig)>(self, interval: Duration, on_change: F) {
+        log::info!("Watching {} for changes", self.config_path.display());
+        let mut last_seen = self.modified();
+        loop {
+            tokio::time::sleep(interval).await;
+            let modified = self.modified();
+            if modified == last_seen {
+                continue;
+            }
+            last_seen = modified;
+            match self.try_load_config() {
+                Ok(config) => {
+                    log::info!("Config file changed on disk, reloading");
+                    on_change(config);
+                }
+                Err(e) => log::warn!("Ignoring config change: {}", e),
+            }
+        }
+    }
+
+    pub fn save_config(&self, config: &AppConfig) -> Result<(), ConfigError> {
         let json = serde_json::to_string_pretty(config)?;
-        let mut file = File::create(&self.config_path)?;
-        file.write_all(json.as_bytes())?;
+        let io_error = |source| ConfigError::Io { path: self.config_path.clone(), source };
+        let mut file = File::create(&self.config_path).map_err(io_error)?;
+        file.write_all(json.as_bytes()).map_err(io_error)?;
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_watch_reloads_valid_changes_only() {
+        let dir = std::env::temp_dir().join(format!("vanta_config_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let manager = ConfigManager::with_path(dir.join("config.json"));
+        manager.save_config(&AppConfig::default()).unwrap();
+
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let watcher = tokio::spawn(ConfigManager::with_path(manager.path().to_path_buf())
+            .watch(Duration::from_millis(10), move |config| tx.send(config).unwrap()));
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        // A broken file keeps the current settings instead of resetting them
+        fs::write(manager.path(), "{ \"autostart\": ").unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(rx.try_recv().is_err());
+        assert!(matches!(manager.try_load_config(), Err(ConfigError::Parse { .. })));
+
+        let edited = AppConfig { whisper_model: "small".to_string(), ..AppConfig::default() };
+        manager.save_config(&edited).unwrap();
+        let reloaded = tokio::time::timeout(Duration::from_secs(2), rx.recv()).await.unwrap().unwrap();
+        assert_eq!(reloaded.whisper_model, "small");
+
+        watcher.abort();
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_referenced_models_cover_every_model_setting() {
+        let config = AppConfig {
+            provider: "Whisper".to_string(),
+            whisper_model: "base".to_string(),
+            onnx_model: "moonshine-tiny".to_string(),
+            routing_rules: vec![serde_json::from_str(r#"{"name": "long", "provider": "Whisper", "model": "medium"}"#).unwrap()],
+            two_pass_enabled: true,
+            two_pass_draft_model: "tiny".to_string(),
+            shadow_whisper_model: "small".to_string(),
+            ..AppConfig::default()
+        };
+        // The ONNX model isn't loaded unless ONNX is in the chain
+        assert_eq!(config.referenced_models(), ["base", "medium", "tiny", "small"]);
+
+        // Nor is the draft model while two-pass is off, as it is by default
+        let config = AppConfig { two_pass_enabled: false, ..config };
+        assert_eq!(config.referenced_models(), ["base", "medium", "small"]);
+        assert!(!AppConfig::default().referenced_models().contains(&"tiny"));
+    }
+}
@@ -0,0 +1,386 @@
+// Error types - typed errors per pipeline stage with stable codes and remediation hints
+//
+// Codes are part of the frontend contract: the UI may key translations or help links
+// off them, so never rename an existing code. Add a new variant instead.
+
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// What the frontend receives for any pipeline failure
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ErrorPayload {
+    pub code: &'static str,
+    pub category: &'static str,
+    pub message: String,
+    pub hint: String,
+}
+
+/// Implemented by every error enum below
+pub trait UserFacingError: std::error::Error {
+    fn category(&self) -> &'static str;
+    fn code(&self) -> &'static str;
+    fn hint(&self) -> String;
+
+    fn payload(&self) -> ErrorPayload {
+        ErrorPayload {
+            code: self.code(),
+            category: self.category(),
+            message: self.to_string(),
+            hint: self.hint(),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum AudioError {
+    #[error("No microphone was found")]
+    NoInputDevice,
+    #[error("Could not read the microphone configuration: {0}")]
+    DeviceConfig(String),
+    #[error("Unsupported microphone sample format: {0}")]
+    UnsupportedFormat(String),
+    #[error("Could not start the audio stream: {0}")]
+    StreamFailed(String),
+    #[error("The audio recorder is not running")]
+    RecorderNotOpen,
+}
+
+impl UserFacingError for AudioError {
+    fn category(&self) -> &'static str {
+        "audio"
+    }
+
+    fn code(&self) -> &'static str {
+        match self {
+            AudioError::NoInputDevice => "AUDIO_NO_INPUT_DEVICE",
+            AudioError::DeviceConfig(_) => "AUDIO_DEVICE_CONFIG",
+            AudioError::UnsupportedFormat(_) => "AUDIO_UNSUPPORTED_FORMAT",
+            AudioError::StreamFailed(_) => "AUDIO_STREAM_FAILED",
+            AudioError::RecorderNotOpen => "AUDIO_RECORDER_NOT_OPEN",
+        }
+    }
+
+    fn hint(&self) -> String {
+        match self {
+            AudioError::NoInputDevice => "Connect a microphone and set it as the default recording device, then restart Vanta Dictate.".into(),
+            AudioError::DeviceConfig(_) | AudioError::StreamFailed(_) => "Check that no other app has exclusive control of the microphone, or pick another default recording device.".into(),
+            AudioError::UnsupportedFormat(_) => "Set the microphone to a 16-bit or 32-bit float format in the system sound settings.".into(),
+            AudioError::RecorderNotOpen => "Restart Vanta Dictate. If this keeps happening, check vanta.log for audio errors at startup.".into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum TranscriptionError {
+    #[error("No transcription provider is configured")]
+    NoProvider,
+    #[error("Model not found at {}", .path.display())]
+    ModelNotFound { path: PathBuf },
+    #[error("Could not run {program}: {details}")]
+    EngineNotFound { program: String, details: String },
+    #[error("{program} cannot be used: {details}")]
+    EngineIncompatible { program: String, details: String },
+    #[error("{engine} failed: {details}")]
+    EngineFailed { engine: String, details: String },
+    #[error("{provider} returned an empty transcript")]
+    EmptyResult { provider: String },
+    #[error("No speech detected ({reason})")]
+    NoSpeech { reason: String },
+    #[error("{provider} timed out after {secs:.0}s")]
+    Timeout { provider: String, secs: f32 },
+    #[error("Failed to connect to {endpoint}: {details}")]
+    Connection { endpoint: String, details: String },
+    #[error("{provider} is not configured: {missing}")]
+    NotConfigured { provider: String, missing: String },
+    #[error("Audio saved for later: {}", .path.display())]
+    SavedForLater { path: PathBuf },
+    #[error("Dictation dropped: {depth} still processing")]
+    QueueFull { depth: usize },
+    #[error("Offline: dictation queued for {provider} ({pending} waiting)")]
+    QueuedOffline { provider: String, pending: usize },
+    #[error("All transcription providers failed ({attempts})")]
+    AllProvidersFailed { attempts: String, primary: Box<TranscriptionError> },
+    #[error("{0}")]
+    Other(String),
+}
+
+impl TranscriptionError {
+    /// Recovers the typed error from a provider result, wrapping untyped ones in `Other`
+    pub fn from_boxed(err: Box<dyn std::error::Error + Send + Sync>) -> Self {
+        match err.downcast::<TranscriptionError>() {
+            Ok(typed) => *typed,
+            Err(other) => TranscriptionError::Other(other.to_string()),
+        }
+    }
+
+    /// Failures that retrying later could fix: the service was unreachable or too slow
+    pub fn is_network(&self) -> bool {
+        match self {
+            TranscriptionError::Connection { .. } | TranscriptionError::Timeout { .. } => true,
+            TranscriptionError::AllProvidersFailed { primary, .. } => primary.is_network(),
+            _ => false,
+        }
+    }
+}
+
+impl UserFacingError for TranscriptionError {
+    fn category(&self) -> &'static str {
+        "transcription"
+    }
+
+    fn code(&self) -> &'static str {
+        match self {
+            TranscriptionError::NoProvider => "TRANSCRIPTION_NO_PROVIDER",
+            TranscriptionError::ModelNotFound { .. } => "TRANSCRIPTION_MODEL_NOT_FOUND",
+            TranscriptionError::EngineNotFound { .. } => "TRANSCRIPTION_ENGINE_NOT_FOUND",
+            TranscriptionError::EngineIncompatible { .. } => "TRANSCRIPTION_ENGINE_INCOMPATIBLE",
+            TranscriptionError::EngineFailed { .. } => "TRANSCRIPTION_ENGINE_FAILED",
+            TranscriptionError::EmptyResult { .. } => "TRANSCRIPTION_EMPTY_RESULT",
+            TranscriptionError::NoSpeech { .. } => "TRANSCRIPTION_NO_SPEECH",
+            TranscriptionError::Timeout { .. } => "TRANSCRIPTION_TIMEOUT",
+            TranscriptionError::Connection { .. } => "TRANSCRIPTION_CONNECTION_FAILED",
+            TranscriptionError::NotConfigured { .. } => "TRANSCRIPTION_NOT_CONFIGURED",
+            TranscriptionError::SavedForLater { .. } => "TRANSCRIPTION_SAVED_FOR_LATER",
+            TranscriptionError::QueueFull { .. } => "TRANSCRIPTION_QUEUE_FULL",
+            TranscriptionError::QueuedOffline { .. } => "TRANSCRIPTION_QUEUED_OFFLINE",
+            TranscriptionError::AllProvidersFailed { .. } => "TRANSCRIPTION_ALL_PROVIDERS_FAILED",
+            TranscriptionError::Other(_) => "TRANSCRIPTION_FAILED",
+        }
+    }
+
+    fn hint(&self) -> String {
+        match self {
+            TranscriptionError::NoProvider => "Choose a transcription engine in Settings > Transcription.".into(),
+            TranscriptionError::ModelNotFound { .. } => "Download the model from Settings > Transcription.".into(),
+            TranscriptionError::EngineNotFound { .. } => "Reinstall Vanta Dictate, or set the path to whisper.cpp in Settings.".into(),
+            TranscriptionError::EngineIncompatible { .. } => "Install whisper.cpp 1.6 or newer and point Settings > Transcription at its whisper-cli program.".into(),
+            TranscriptionError::EngineFailed { .. } => "Try a smaller model, or re-download the current one in case the file is damaged.".into(),
+            TranscriptionError::EmptyResult { .. } | TranscriptionError::NoSpeech { .. } => "Speak a little louder or closer to the microphone, and hold the hotkey until you finish.".into(),
+            TranscriptionError::Timeout { .. } => "Use a smaller model, or raise the provider timeout in the fallback chain settings.".into(),
+            TranscriptionError::Connection { .. } => "Check that the server is running and reachable, or add a local provider to the fallback chain.".into(),
+            TranscriptionError::NotConfigured { .. } => "Finish setting up this provider in Settings > Transcription.".into(),
+            TranscriptionError::SavedForLater { .. } => "The recording was kept on disk; it can be transcribed once a provider is available.".into(),
+            TranscriptionError::QueueFull { .. } => "Wait for earlier dictations to finish, or set the queue policy to Queue or Merge in Settings.".into(),
+            TranscriptionError::QueuedOffline { .. } => "It will be transcribed automatically once the connection is back, and copied to your clipboard.".into(),
+            TranscriptionError::AllProvidersFailed { primary, .. } => primary.hint(),
+            TranscriptionError::Other(_) => "Check vanta.log for details.".into(),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum FormattingError {
+    #[error("Formatting removed all text from the transcript")]
+    EmptyOutput,
+}
+
+impl UserFacingError for FormattingError {
+    fn category(&self) -> &'static str {
+        "formatting"
+    }
+
+    fn code(&self) -> &'static str {
+        match self {
+            FormattingError::EmptyOutput => "FORMATTING_EMPTY_OUTPUT",
+        }
+    }
+
+    fn hint(&self) -> String {
+        match self {
+            FormattingError::EmptyOutput => "Switch Output Style to Lightning to insert the raw transcript.".into(),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum InjectionError {
+    #[error("No target window was captured")]
+    NoTarget,
+    #[error("Clipboard error: {0}")]
+    Clipboard(String),
+    #[error("Only {sent} of {expected} key events were delivered")]
+    SendInput { sent: u32, expected: u32 },
+    #[error("Windows API error: {0}")]
+    Win32(String),
+}
+
+#[cfg(windows)]
+impl From<windows::core::Error> for InjectionError {
+    fn from(err: windows::core::Error) -> Self {
+        InjectionError::Win32(err.to_string())
+    }
+}
+
+impl UserFacingError for InjectionError {
+    fn category(&self) -> &'static str {
+        "injection"
+    }
+
+    fn code(&self) -> &'static str {
+        match self {
+            InjectionError::NoTarget => "INJECTION_NO_TARGET",
+            InjectionError::Clipboard(_) => "INJECTION_CLIPBOARD",
+            InjectionError::SendInput { .. } => "INJECTION_SEND_INPUT",
+            InjectionError::Win32(_) => "INJECTION_WIN32",
+        }
+    }
+
+    fn hint(&self) -> String {
+        match self {
+            InjectionError::NoTarget => "Click into the text field you want to dictate into before pressing the hotkey.".into(),
+            InjectionError::Clipboard(_) => "Another app may be holding the clipboard open. Enable the typing fallback in Settings > Advanced.".into(),
+            InjectionError::SendInput { .. } | InjectionError::Win32(_) => "Apps running as administrator block simulated input. Run Vanta Dictate at the same privilege level.".into(),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+    #[error("Could not write {}: {source}", .path.display())]
+    Io { path: PathBuf, source: std::io::Error },
+    #[error("Could not serialize settings: {0}")]
+    Serialize(#[from] serde_json::Error),
+    #[error("Could not parse {}: {source}", .path.display())]
+    Parse { path: PathBuf, source: serde_json::Error },
+}
+
+impl UserFacingError for ConfigError {
+    fn category(&self) -> &'static str {
+        "config"
+    }
+
+    fn code(&self) -> &'static str {
+        match self {
+            ConfigError::Io { .. } => "CONFIG_IO",
+            ConfigError::Serialize(_) => "CONFIG_SERIALIZE",
+            ConfigError::Parse { .. } => "CONFIG_PARSE",
+        }
+    }
+
+    fn hint(&self) -> String {
+        match self {
+            ConfigError::Io { path, .. } => format!("Check that {} is writable and the disk isn't full.", path.display()),
+            ConfigError::Serialize(_) => "Reset the setting you just changed; the value could not be saved.".into(),
+            ConfigError::Parse { path, .. } => format!("Fix the JSON in {} or delete it to restore defaults. The previous settings stay in effect.", path.display()),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ModelError {
+    #[error("Unknown model: {0}")]
+    Unknown(String),
+    #[error("Download of {url} failed: {details}")]
+    Network { url: String, details: String },
+    #[error("Server returned {status} for {url}")]
+    Http { url: String, status: u16 },
+    #[error("{} is corrupt: expected SHA-256 {expected}, got {actual}", .path.display())]
+    ChecksumMismatch { path: PathBuf, expected: String, actual: String },
+    #[error("Download cancelled")]
+    Cancelled,
+    #[error("Model catalog is invalid: {0}")]
+    Catalog(String),
+    #[error("{} can't be imported: {reason}", .path.display())]
+    InvalidFile { path: PathBuf, reason: String },
+    #[error("Model {0} is not installed")]
+    NotInstalled(String),
+    #[error("Model {0} is in use")]
+    InUse(String),
+    #[error("Could not write {}: {source}", .path.display())]
+    Io { path: PathBuf, source: std::io::Error },
+}
+
+impl UserFacingError for ModelError {
+    fn category(&self) -> &'static str {
+        "model"
+    }
+
+    fn code(&self) -> &'static str {
+        match self {
+            ModelError::Unknown(_) => "MODEL_UNKNOWN",
+            ModelError::Network { .. } => "MODEL_DOWNLOAD_NETWORK",
+            ModelError::Http { .. } => "MODEL_DOWNLOAD_HTTP",
+            ModelError::ChecksumMismatch { .. } => "MODEL_CHECKSUM_MISMATCH",
+            ModelError::Cancelled => "MODEL_DOWNLOAD_CANCELLED",
+            ModelError::Catalog(_) => "MODEL_CATALOG_INVALID",
+            ModelError::InvalidFile { .. } => "MODEL_INVALID_FILE",
+            ModelError::NotInstalled(_) => "MODEL_NOT_INSTALLED",
+            ModelError::InUse(_) => "MODEL_IN_USE",
+            ModelError::Io { .. } => "MODEL_IO",
+        }
+    }
+
+    fn hint(&self) -> String {
+        match self {
+            ModelError::Unknown(_) => "Pick a model from the list in Settings > Transcription.".into(),
+            ModelError::Network { .. } | ModelError::Http { .. } => "Check your connection and press Download again; it continues where it stopped.".into(),
+            ModelError::ChecksumMismatch { .. } => "The damaged file was deleted. Press Download again to fetch a fresh copy.".into(),
+            ModelError::Cancelled => "Press Download again to continue where it stopped.".into(),
+            ModelError::Catalog(_) => "The built-in model list is still used. Check the catalog URL in Settings > Advanced.".into(),
+            ModelError::InvalidFile { .. } => "Import the ggml-*.bin file downloaded from the whisper.cpp model page, not a renamed or partial copy.".into(),
+            ModelError::NotInstalled(_) => "Download or import the model first.".into(),
+            ModelError::InUse(_) => "Switch to another model in Settings > Transcription before deleting this one.".into(),
+            ModelError::Io { path, .. } => format!("Check that {} is writable and the disk has enough free space.", path.display()),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum TuningError {
+    #[error("The benchmark clip ({0}) is missing")]
+    ClipMissing(String),
+    #[error("Could not read the benchmark clip {}: {details}", .path.display())]
+    ClipUnreadable { path: PathBuf, details: String },
+    #[error("No Whisper models for {language} are installed")]
+    NoModels { language: String },
+}
+
+impl UserFacingError for TuningError {
+    fn category(&self) -> &'static str {
+        "tuning"
+    }
+
+    fn code(&self) -> &'static str {
+        match self {
+            TuningError::ClipMissing(_) => "TUNING_CLIP_MISSING",
+            TuningError::ClipUnreadable { .. } => "TUNING_CLIP_UNREADABLE",
+            TuningError::NoModels { .. } => "TUNING_NO_MODELS",
+        }
+    }
+
+    fn hint(&self) -> String {
+        match self {
+            TuningError::ClipMissing(name) => format!("Reinstall the app, or copy whisper.cpp's samples/jfk.wav into the resources folder as {}.", name),
+            TuningError::ClipUnreadable { .. } => "Replace it with a 16-bit PCM WAV file.".into(),
+            TuningError::NoModels { .. } => "Download at least one Whisper model in Settings > Transcription, then run the benchmark again.".into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_typed_error_survives_boxing() {
+        let boxed: Box<dyn std::error::Error + Send + Sync> = TranscriptionError::ModelNotFound { path: PathBuf::from("ggml-base.bin") }.into();
+        let typed = TranscriptionError::from_boxed(boxed);
+        assert_eq!(typed.code(), "TRANSCRIPTION_MODEL_NOT_FOUND");
+
+        let untyped: Box<dyn std::error::Error + Send + Sync> = "socket closed".into();
+        let payload = TranscriptionError::from_boxed(untyped).payload();
+        assert_eq!((payload.code, payload.message.as_str()), ("TRANSCRIPTION_FAILED", "socket closed"));
+    }
+
+    #[test]
+    fn test_payload_shape() {
+        let err = TranscriptionError::AllProvidersFailed {
+            attempts: "Whisper: timed out".into(),
+            primary: Box::new(TranscriptionError::Timeout { provider: "Whisper".into(), secs: 60.0 }),
+        };
+        let json = serde_json::to_value(err.payload()).unwrap();
+        assert_eq!(json["code"], "TRANSCRIPTION_ALL_PROVIDERS_FAILED");
+        assert_eq!(json["category"], "transcription");
+        assert_eq!(json["hint"], TranscriptionError::Timeout { provider: String::new(), secs: 0.0 }.hint());
+    }
+}
@@ -0,0 +1,215 @@
+// Evaluation store - append-only JSON lines log of shadow-provider comparisons
+//
+// Once the file passes `MAX_BYTES` it is rewritten with only the newest `KEEP_RECORDS`,
+// so shadow mode can stay on indefinitely without the log growing without bound.
+
+use crate::benchmark::metrics;
+use crate::transcription::two_pass::{word_diff, DiffOp};
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+
+const MAX_BYTES: u64 = 4 * 1024 * 1024;
+const KEEP_RECORDS: usize = 1000;
+
+/// One real dictation transcribed by both the active and the shadow provider
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct EvalRecord {
+    pub id: String,
+    pub timestamp: String,
+    pub audio_secs: f32,
+    pub primary_provider: String,
+    pub primary_text: String,
+    pub primary_latency_ms: u64,
+    pub shadow_provider: String,
+    pub shadow_text: String,
+    pub shadow_latency_ms: u64,
+    /// Set when the shadow provider failed; such records count as disagreements
+    pub shadow_error: Option<String>,
+    /// Both transcripts match after normalizing case and punctuation
+    pub agree: bool,
+    /// Word error rate of the shadow text measured against the primary text
+    pub wer: f64,
+    pub diff: Vec<DiffOp>,
+}
+
+impl EvalRecord {
+    pub fn new(
+        audio_secs: f32,
+        (primary_provider, primary_text, primary_latency_ms): (String, String, u64),
+        (shadow_provider, shadow_result, shadow_latency_ms): (String, Result<String, String>, u64),
+    ) -> Self {
+        let (shadow_text, shadow_error) = match shadow_result {
+            Ok(text) => (text, None),
+            Err(e) => (String::new(), Some(e)),
+        };
+        let agree = shadow_error.is_none() && metrics::normalize(&primary_text) == metrics::normalize(&shadow_text);
+        let wer = metrics::word_errors(&primary_text, &shadow_text).rate();
+        let diff = word_diff(&primary_text, &shadow_text);
+
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            timestamp: chrono::Local::now().to_rfc3339(),
+            audio_secs,
+            primary_provider,
+            primary_text,
+            primary_latency_ms,
+            shadow_provider,
+            shadow_text,
+            shadow_latency_ms,
+            shadow_error,
+            agree,
+            wer,
+            diff,
+        }
+    }
+}
+
+/// Agreement summary over the stored comparisons
+#[derive(Debug, Serialize, Clone, PartialEq, Default)]
+pub struct ShadowReport {
+    pub samples: usize,
+    pub agreements: usize,
+    pub shadow_failures: usize,
+    pub agreement_rate: f64,
+    pub mean_wer: f64,
+    pub mean_primary_latency_ms: f64,
+    pub mean_shadow_latency_ms: f64,
+    /// Most recent disagreements, newest first
+    pub recent_disagreements: Vec<EvalRecord>,
+}
+
+impl ShadowReport {
+    /// `records` newest first, as returned by `EvalStore::load`
+    pub fn from_records(records: &[EvalRecord], max_disagreements: usize) -> Self {
+        if records.is_empty() {
+            return Self::default();
+        }
+
+        let samples = records.len();
+        let mean = |value: fn(&EvalRecord) -> f64| records.iter().map(value).sum::<f64>() / samples as f64;
+        let agreements = records.iter().filter(|r| r.agree).count();
+
+        Self {
+            samples,
+            agreements,
+            shadow_failures: records.iter().filter(|r| r.shadow_error.is_some()).count(),
+            agreement_rate: agreements as f64 / samples as f64,
+            mean_wer: mean(|r| r.wer),
+            mean_primary_latency_ms: mean(|r| r.primary_latency_ms as f64),
+            mean_shadow_latency_ms: mean(|r| r.shadow_latency_ms as f64),
+            recent_disagreements: records.iter().filter(|r| !r.agree).take(max_disagreements).cloned().collect(),
+        }
+    }
+}
+
+pub struct EvalStore {
+    eval_path: PathBuf,
+    max_bytes: u64,
+}
+
+impl Default for EvalStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EvalStore {
+    pub fn new() -> Self {
+        Self::with_path(crate::paths::data_file("shadow_eval.jsonl"))
+    }
+
+    pub fn with_path(eval_path: PathBuf) -> Self {
+        Self { eval_path, max_bytes: MAX_BYTES }
+    }
+
+    pub fn append(&self, record: &EvalRecord) -> Result<(), Box<dyn std::error::Error>> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.eval_path)?;
+        writeln!(file, "{}", serde_json::to_string(record)?)?;
+
+        if file.metadata()?.len() > self.max_bytes {
+            drop(file);
+            self.trim(KEEP_RECORDS)?;
+        }
+        Ok(())
+    }
+
+    /// Rewrites the log with only the newest `keep` records
+    fn trim(&self, keep: usize) -> Result<(), Box<dyn std::error::Error>> {
+        let mut records = self.load(keep);
+        records.reverse();
+        let temp_path = self.eval_path.with_extension("jsonl.tmp");
+        let mut temp = File::create(&temp_path)?;
+        for record in &records {
+            writeln!(temp, "{}", serde_json::to_string(record)?)?;
+        }
+        temp.sync_all()?;
+        std::fs::rename(&temp_path, &self.eval_path)?;
+        log::info!("Trimmed shadow evaluations to the newest {}", records.len());
+        Ok(())
+    }
+
+    /// Most recent records first
+    pub fn load(&self, limit: usize) -> Vec<EvalRecord> {
+        let Ok(file) = File::open(&self.eval_path) else {
+            return Vec::new();
+        };
+
+        let mut records: Vec<EvalRecord> = BufReader::new(file)
+            .lines()
+            .map_while(Result::ok)
+            .filter_map(|line| serde_json::from_str(&line).ok())
+            .collect();
+        records.reverse();
+        records.truncate(limit);
+        records
+    }
+
+    pub fn report(&self, limit: usize) -> ShadowReport {
+        ShadowReport::from_records(&self.load(limit), 10)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_counts_agreement() {
+        let path = std::env::temp_dir().join(format!("vanta-eval-{}.jsonl", uuid::Uuid::new_v4()));
+        let store = EvalStore::with_path(path.clone());
+
+        let primary = |text: &str| ("Whisper".to_string(), text.to_string(), 400);
+        let shadow = |result: Result<&str, &str>| ("Onnx".to_string(), result.map(str::to_string).map_err(str::to_string), 200);
+        store.append(&EvalRecord::new(2.0, primary("Hello, world."), shadow(Ok("hello world")))).unwrap();
+        store.append(&EvalRecord::new(2.0, primary("Send the report."), shadow(Ok("Send the rapport.")))).unwrap();
+        store.append(&EvalRecord::new(2.0, primary("Thanks."), shadow(Err("timed out")))).unwrap();
+
+        let report = store.report(100);
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!((report.samples, report.agreements, report.shadow_failures), (3, 1, 1));
+        assert_eq!(report.mean_shadow_latency_ms, 200.0);
+        assert_eq!(report.recent_disagreements[0].primary_text, "Thanks.");
+        assert_eq!(report.recent_disagreements[1].diff[1], DiffOp::Delete("report.".into()));
+    }
+
+    #[test]
+    fn test_log_is_trimmed_to_newest_records() {
+        let path = std::env::temp_dir().join(format!("vanta-eval-{}.jsonl", uuid::Uuid::new_v4()));
+        let store = EvalStore { max_bytes: 1, ..EvalStore::with_path(path.clone()) };
+
+        for text in ["one", "two", "three"] {
+            store.append(&EvalRecord::new(1.0, ("A".into(), text.into(), 1), ("B".into(), Ok(text.into()), 1))).unwrap();
+        }
+        store.trim(2).unwrap();
+        let records = store.load(10);
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(records.iter().map(|r| r.primary_text.as_str()).collect::<Vec<_>>(), vec!["three", "two"]);
+    }
+}
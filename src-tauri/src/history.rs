@@ -0,0 +1,97 @@
+// Dictation history - append-only JSON lines log of finished transcriptions
+
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct HistoryEntry {
+    pub id: String,
+    pub timestamp: String,
+    pub text: String,
+    /// Provider that produced the text (e.g. "Whisper", or the chain step that succeeded)
+    pub provider: String,
+    pub audio_secs: f32,
+    pub latency_ms: u64,
+}
+
+impl HistoryEntry {
+    pub fn new(text: String, provider: String, audio_secs: f32, latency_ms: u64) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            timestamp: chrono::Local::now().to_rfc3339(),
+            text,
+            provider,
+            audio_secs,
+            latency_ms,
+        }
+    }
+}
+
+pub struct HistoryStore {
+    history_path: PathBuf,
+}
+
+impl Default for HistoryStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HistoryStore {
+    pub fn new() -> Self {
+        Self { history_path: crate::paths::data_file("history.jsonl") }
+    }
+
+    pub fn with_path(history_path: PathBuf) -> Self {
+        Self { history_path }
+    }
+
+    pub fn append(&self, entry: &HistoryEntry) -> Result<(), Box<dyn std::error::Error>> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.history_path)?;
+        writeln!(file, "{}", serde_json::to_string(entry)?)?;
+        Ok(())
+    }
+
+    /// Most recent entries first
+    pub fn load(&self, limit: usize) -> Vec<HistoryEntry> {
+        let Ok(file) = File::open(&self.history_path) else {
+            return Vec::new();
+        };
+
+        let mut entries: Vec<HistoryEntry> = BufReader::new(file)
+            .lines()
+            .map_while(Result::ok)
+            .filter_map(|line| serde_json::from_str(&line).ok())
+            .collect();
+        entries.reverse();
+        entries.truncate(limit);
+        entries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_append_and_load_newest_first() {
+        let path = std::env::temp_dir().join(format!("vanta-history-{}.jsonl", uuid::Uuid::new_v4()));
+        let store = HistoryStore::with_path(path.clone());
+
+        store.append(&HistoryEntry::new("First.".into(), "Whisper".into(), 1.0, 300)).unwrap();
+        store.append(&HistoryEntry::new("Second.".into(), "Cloud".into(), 2.0, 500)).unwrap();
+
+        let entries = store.load(10);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].text, "Second.");
+        assert_eq!(entries[0].provider, "Cloud");
+        assert_eq!(store.load(1).len(), 1);
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -2,40 +2,104 @@
 
 use global_hotkey::{GlobalHotKeyManager, HotKeyState, hotkey::{HotKey, Code, Modifiers}};
 use std::sync::mpsc::{channel, Receiver, Sender};
+use tokio::sync::watch;
 
 pub enum HotkeyEvent {
     Pressed,
     Released,
+    /// The cancel hotkey: discard the current recording or transcription
+    Cancel,
+    /// Escape, pressed in any app; only discards a recording
+    Escape,
 }
 
 pub struct HotkeyManager {
     _manager: GlobalHotKeyManager,
     hotkey: HotKey,
+    cancel_hotkey: HotKey,
     event_tx: Sender<HotkeyEvent>,
 }
 
+/// Set by the logic thread on cancel so the processing worker can observe it
+/// while a transcription is awaiting
+#[derive(Clone)]
+pub struct CancelFlag(watch::Sender<bool>);
+
+impl Default for CancelFlag {
+    fn default() -> Self {
+        Self(watch::channel(false).0)
+    }
+}
+
+impl CancelFlag {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.send_replace(true);
+    }
+
+    pub fn reset(&self) {
+        self.0.send_replace(false);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        *self.0.borrow()
+    }
+
+    /// Resolves once `cancel()` has been called
+    pub async fn cancelled(&self) {
+        let _ = self.0.subscribe().wait_for(|cancelled| *cancelled).await;
+    }
+}
+
+/// Escape is polled rather than registered so other apps keep receiving it
+#[cfg(windows)]
+fn escape_down() -> bool {
+    use windows::Win32::UI::Input::KeyboardAndMouse::{GetAsyncKeyState, VK_ESCAPE};
+    unsafe { (GetAsyncKeyState(VK_ESCAPE.0 as i32) as u16 & 0x8000) != 0 }
+}
+
+#[cfg(not(windows))]
+fn escape_down() -> bool {
+    false
+}
+
 impl HotkeyManager {
-    pub fn new() -> Result<(Self, Receiver<HotkeyEvent>), Box<dyn std::error::Error>> {
+    /// Registers `hotkey` (e.g. "Ctrl+Shift+Space") once; changing it takes a restart
+    pub fn new(hotkey: &str) -> Result<(Self, Receiver<HotkeyEvent>), Box<dyn std::error::Error>> {
         let manager = GlobalHotKeyManager::new()?;
         let (event_tx, event_rx) = channel();
         
         // Default hotkey: Ctrl+Shift+Space
-        let hotkey = HotKey::new(Some(Modifiers::CONTROL | Modifiers::SHIFT), Code::Space);
+        let default_hotkey = HotKey::new(Some(Modifiers::CONTROL | Modifiers::SHIFT), Code::Space);
+        let hotkey = hotkey.parse::<HotKey>().unwrap_or_else(|e| {
+            log::warn!("Invalid hotkey '{}' ({}), using Ctrl+Shift+Space", hotkey, e);
+            default_hotkey
+        });
+        // Cancel hotkey: Ctrl+Shift+Backspace
+        let cancel_hotkey = HotKey::new(Some(Modifiers::CONTROL | Modifiers::SHIFT), Code::Backspace);
         
         manager.register(hotkey)?;
+        if let Err(e) = manager.register(cancel_hotkey) {
+            log::warn!("Failed to register cancel hotkey (Escape still cancels recordings): {}", e);
+        }
         
         Ok((Self {
             _manager: manager,
             hotkey,
+            cancel_hotkey,
             event_tx,
         }, event_rx))
     }
     
     // Separate function to run the listener loop
-    pub fn start_listening(event_tx: Sender<HotkeyEvent>, hotkey_id: u32) {
+    pub fn start_listening(event_tx: Sender<HotkeyEvent>, hotkey_id: u32, cancel_id: u32) {
         use global_hotkey::GlobalHotKeyEvent;
         
-        log::info!("GlobalHotKey listener loop started for ID: {}", hotkey_id);
+        log::info!("GlobalHotKey listener loop started for ID: {} (cancel ID: {})", hotkey_id, cancel_id);
+        let mut escape_was_down = false;
 
         loop {
             // Using try_recv and a small sleep prevents potential locking issues
@@ -52,8 +116,20 @@ impl HotkeyManager {
                             let _ = event_tx.send(HotkeyEvent::Released);
                         }
                     }
+                } else if event.id == cancel_id && event.state == HotKeyState::Pressed {
+                    log::debug!("Raw Event: Cancel hotkey (ID: {})", event.id);
+                    let _ = event_tx.send(HotkeyEvent::Cancel);
                 }
             }
+            
+            // Only the key-down edge counts, holding Escape cancels once
+            let escape_is_down = escape_down();
+            if escape_is_down && !escape_was_down {
+                log::debug!("Raw Event: Escape");
+                let _ = event_tx.send(HotkeyEvent::Escape);
+            }
+            escape_was_down = escape_is_down;
+
             std::thread::sleep(std::time::Duration::from_millis(5));
         }
     }
@@ -65,4 +141,8 @@ impl HotkeyManager {
     pub fn get_hotkey_id(&self) -> u32 {
         self.hotkey.id()
     }
+
+    pub fn get_cancel_hotkey_id(&self) -> u32 {
+        self.cancel_hotkey.id()
+    }
 }
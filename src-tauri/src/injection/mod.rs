@@ -1,20 +1,46 @@
 // Text injection module - Clipboard + Ctrl+V with typing fallback
 
+use crate::errors::InjectionError;
 use std::sync::{Arc, Mutex};
 use windows::Win32::UI::WindowsAndMessaging::{GetForegroundWindow, SetForegroundWindow};
 use windows::Win32::Foundation::{HWND, HANDLE};
-use windows::Win32::System::DataExchange::{OpenClipboard, CloseClipboard, EmptyClipboard, SetClipboardData};
-use windows::Win32::System::Memory::{GlobalAlloc, GlobalLock, GlobalUnlock, GMEM_MOVEABLE};
-use windows::Win32::UI::Input::KeyboardAndMouse::{SendInput, INPUT, INPUT_KEYBOARD, KEYEVENTF_KEYUP, KEYEVENTF_UNICODE, VK_CONTROL, VK_V};
+use windows::Win32::System::DataExchange::{OpenClipboard, CloseClipboard, EmptyClipboard, GetClipboardData, SetClipboardData};
+use windows::Win32::System::Memory::{GlobalAlloc, GlobalLock, GlobalUnlock, GMEM_MOVEABLE, HGLOBAL};
+use windows::Win32::UI::Input::KeyboardAndMouse::{SendInput, INPUT, INPUT_KEYBOARD, KEYEVENTF_KEYUP, KEYEVENTF_UNICODE, VIRTUAL_KEY, VK_C, VK_CONTROL, VK_LEFT, VK_RIGHT, VK_SHIFT, VK_V};
 use std::ffi::OsStr;
 use std::os::windows::ffi::OsStrExt;
 
 const CF_UNICODETEXT: u32 = 13;
 
+/// Shift+Left presses needed to select `text`: edit controls step in UTF-16 code units
+/// and treat a line break as one position, so `text` must use `\n` line endings
+fn selection_length(text: &str) -> usize {
+    text.encode_utf16().count()
+}
+
+/// Result of trying to swap recently injected text for a new version
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReplaceOutcome {
+    Replaced,
+    /// The text before the caret no longer matches what we injected
+    Edited { current: String },
+    /// The user has moved to another window since the injection
+    FocusChanged,
+}
+
+/// Window that had focus when a dictation started; queued jobs inject into their own target
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FocusTarget(HWND);
+
+unsafe impl Send for FocusTarget {}
+unsafe impl Sync for FocusTarget {}
+
 pub struct TextInjector {
     target_hwnd: Arc<Mutex<Option<HWND>>>,
     focus_delay_ms: Arc<Mutex<u64>>,
     enable_fallback: Arc<Mutex<bool>>,
+    // Serializes synthetic input between the logic thread and background refinements
+    input_lock: Mutex<()>,
 }
 
 unsafe impl Send for TextInjector {}
@@ -26,6 +52,7 @@ impl TextInjector {
             target_hwnd: Arc::new(Mutex::new(None)),
             focus_delay_ms: Arc::new(Mutex::new(100)),
             enable_fallback: Arc::new(Mutex::new(true)),
+            input_lock: Mutex::new(()),
         }
     }
     
@@ -41,17 +68,37 @@ impl TextInjector {
         }
     }
 
-    pub fn capture_focus(&self) {
+    pub fn capture_focus(&self) -> FocusTarget {
         unsafe {
             let hwnd = GetForegroundWindow();
             log::info!("Captured HWND: {:?}", hwnd);
             if let Ok(mut lock) = self.target_hwnd.lock() {
                 *lock = Some(hwnd);
             }
+            FocusTarget(hwnd)
+        }
+    }
+    
+    /// Points the next injection at a previously captured window
+    pub fn set_target(&self, target: FocusTarget) {
+        if let Ok(mut lock) = self.target_hwnd.lock() {
+            *lock = Some(target.0);
         }
     }
 
-    pub fn inject(&self, text: &str) -> Result<(), Box<dyn std::error::Error>> {
+    pub fn inject(&self, text: &str) -> Result<(), InjectionError> {
+        let _input = self.input_lock.lock().unwrap_or_else(|e| e.into_inner());
+        self.inject_locked(text)
+    }
+    
+    /// Injects into `target` without racing other jobs changing the target
+    pub fn inject_into(&self, target: FocusTarget, text: &str) -> Result<(), InjectionError> {
+        let _input = self.input_lock.lock().unwrap_or_else(|e| e.into_inner());
+        self.set_target(target);
+        self.inject_locked(text)
+    }
+
+    fn inject_locked(&self, text: &str) -> Result<(), InjectionError> {
         log::info!("Injecting text: '{}' (len: {})", text, text.len());
 
         // Try primary method: Clipboard + Ctrl+V
@@ -75,7 +122,65 @@ impl TextInjector {
         }
     }
     
-    fn inject_via_clipboard(&self, text: &str) -> Result<(), Box<dyn std::error::Error>> {
+    /// Replaces `previous`, which must sit directly before the caret in `target`,
+    /// with `replacement`. The text is selected with Shift+Left and copied first,
+    /// so nothing is overwritten if the user has edited it or moved elsewhere.
+    pub fn replace_recent(&self, target: FocusTarget, previous: &str, replacement: &str) -> Result<ReplaceOutcome, InjectionError> {
+        let _input = self.input_lock.lock().unwrap_or_else(|e| e.into_inner());
+
+        let foreground = unsafe { GetForegroundWindow() };
+        if target.0 != foreground {
+            log::info!("Foreground window changed since injection, not replacing text");
+            return Ok(ReplaceOutcome::FocusChanged);
+        }
+
+        // 1. Select exactly the injected text
+        let previous = previous.replace("\r\n", "\n");
+        let length = selection_length(&previous);
+        for _ in 0..length {
+            self.send_key(Some(VK_SHIFT), VK_LEFT)?;
+        }
+
+        // 2-3. Copying and pasting go through the clipboard, so the user's contents are put back after
+        let saved_clipboard = self.get_clipboard_text().ok();
+        let outcome = self.copy_and_replace(target, &previous, length, replacement);
+        if matches!(outcome, Ok(ReplaceOutcome::Replaced)) {
+            // The target reads the clipboard when it handles Ctrl+V, not when it is sent
+            std::thread::sleep(std::time::Duration::from_millis(100));
+        }
+        match saved_clipboard {
+            Some(text) => {
+                if let Err(e) = self.set_clipboard_text(&text) {
+                    log::warn!("Failed to restore the clipboard after replacing text: {}", e);
+                }
+            }
+            None => log::debug!("Clipboard held no text before replacing, leaving it as is"),
+        }
+        outcome
+    }
+
+    fn copy_and_replace(&self, target: FocusTarget, previous: &str, length: usize, replacement: &str) -> Result<ReplaceOutcome, InjectionError> {
+        // 2. Copy the selection and compare
+        self.set_clipboard_text("")?;
+        self.send_key(Some(VK_CONTROL), VK_C)?;
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        let current = self.get_clipboard_text().unwrap_or_default().replace("\r\n", "\n");
+
+        if current != previous {
+            // Collapse the selection back to where the caret was
+            self.send_key(None, VK_RIGHT)?;
+            log::info!("Injected text was edited ('{}' != '{}'), not replacing", current, previous);
+            return Ok(ReplaceOutcome::Edited { current });
+        }
+
+        // 3. Typing over the selection replaces it
+        log::info!("Replacing {} UTF-16 units with refined text", length);
+        self.set_target(target);
+        self.inject_locked(replacement)?;
+        Ok(ReplaceOutcome::Replaced)
+    }
+
+    fn inject_via_clipboard(&self, text: &str) -> Result<(), InjectionError> {
         // 1. Set clipboard
         self.set_clipboard_text(text)?;
         log::debug!("Clipboard set successfully");
@@ -90,7 +195,7 @@ impl TextInjector {
         Ok(())
     }
     
-    fn inject_via_typing(&self, text: &str) -> Result<(), Box<dyn std::error::Error>> {
+    fn inject_via_typing(&self, text: &str) -> Result<(), InjectionError> {
         log::info!("Using typing fallback for {} characters", text.len());
         
         // Restore focus first
@@ -106,7 +211,7 @@ impl TextInjector {
         Ok(())
     }
     
-    fn send_unicode_char(&self, ch: char) -> Result<(), Box<dyn std::error::Error>> {
+    fn send_unicode_char(&self, ch: char) -> Result<(), InjectionError> {
         unsafe {
             let mut inputs: [INPUT; 2] = std::mem::zeroed();
             let code = ch as u16;
@@ -131,7 +236,74 @@ impl TextInjector {
         }
     }
 
-    fn set_clipboard_text(&self, text: &str) -> Result<(), Box<dyn std::error::Error>> {
+    fn send_key(&self, modifier: Option<VIRTUAL_KEY>, key: VIRTUAL_KEY) -> Result<(), InjectionError> {
+        unsafe {
+            let mut inputs: Vec<INPUT> = Vec::with_capacity(4);
+            let mut push = |vk: VIRTUAL_KEY, up: bool| {
+                let mut input: INPUT = std::mem::zeroed();
+                input.r#type = INPUT_KEYBOARD;
+                input.Anonymous.ki.wVk = vk;
+                if up {
+                    input.Anonymous.ki.dwFlags = KEYEVENTF_KEYUP;
+                }
+                inputs.push(input);
+            };
+
+            if let Some(modifier) = modifier {
+                push(modifier, false);
+            }
+            push(key, false);
+            push(key, true);
+            if let Some(modifier) = modifier {
+                push(modifier, true);
+            }
+
+            let sent = SendInput(&inputs, std::mem::size_of::<INPUT>() as i32);
+            if sent as usize != inputs.len() {
+                return Err(InjectionError::SendInput { sent, expected: inputs.len() as u32 });
+            }
+            Ok(())
+        }
+    }
+
+    /// Puts `text` on the clipboard without pasting it anywhere
+    pub fn copy_to_clipboard(&self, text: &str) -> Result<(), InjectionError> {
+        let _input = self.input_lock.lock().unwrap_or_else(|e| e.into_inner());
+        self.set_clipboard_text(text)
+    }
+
+    fn get_clipboard_text(&self) -> Result<String, InjectionError> {
+        unsafe {
+            OpenClipboard(None)?;
+
+            let handle = match GetClipboardData(CF_UNICODETEXT) {
+                Ok(handle) => handle,
+                Err(e) => {
+                    let _ = CloseClipboard();
+                    return Err(InjectionError::Clipboard(e.to_string()));
+                }
+            };
+
+            let hmem = HGLOBAL(handle.0 as _);
+            let ptr = GlobalLock(hmem) as *const u16;
+            if ptr.is_null() {
+                let _ = CloseClipboard();
+                return Err(InjectionError::Clipboard("failed to lock clipboard memory".to_string()));
+            }
+
+            let mut len = 0;
+            while *ptr.add(len) != 0 {
+                len += 1;
+            }
+            let text = String::from_utf16_lossy(std::slice::from_raw_parts(ptr, len));
+
+            let _ = GlobalUnlock(hmem);
+            let _ = CloseClipboard();
+            Ok(text)
+        }
+    }
+
+    fn set_clipboard_text(&self, text: &str) -> Result<(), InjectionError> {
         unsafe {
             // Open clipboard
             OpenClipboard(None)?;
@@ -150,14 +322,14 @@ impl TextInjector {
             let hmem = GlobalAlloc(GMEM_MOVEABLE, size)?;
             if hmem.0.is_null() {
                 let _ = CloseClipboard();
-                return Err("Failed to allocate global memory".into());
+                return Err(InjectionError::Clipboard("failed to allocate global memory".to_string()));
             }
 
             // Lock and copy
             let ptr = GlobalLock(hmem);
             if ptr.is_null() {
                 let _ = CloseClipboard();
-                return Err("Failed to lock global memory".into());
+                return Err(InjectionError::Clipboard("failed to lock global memory".to_string()));
             }
 
             std::ptr::copy_nonoverlapping(wide.as_ptr(), ptr as *mut u16, wide.len());
@@ -168,17 +340,17 @@ impl TextInjector {
             let _ = CloseClipboard();
 
             if result.is_err() {
-                return Err("Failed to set clipboard data".into());
+                return Err(InjectionError::Clipboard("failed to set clipboard data".to_string()));
             }
 
             Ok(())
         }
     }
 
-    fn restore_focus(&self) -> Result<(), Box<dyn std::error::Error>> {
+    fn restore_focus(&self) -> Result<(), InjectionError> {
         let hwnd = {
             let lock = self.target_hwnd.lock().unwrap();
-            lock.ok_or("No target HWND captured")?
+            lock.ok_or(InjectionError::NoTarget)?
         };
 
         unsafe {
@@ -191,7 +363,7 @@ impl TextInjector {
         Ok(())
     }
 
-    fn send_ctrl_v(&self) -> Result<(), Box<dyn std::error::Error>> {
+    fn send_ctrl_v(&self) -> Result<(), InjectionError> {
         unsafe {
             // Small delay to ensure window is ready
             std::thread::sleep(std::time::Duration::from_millis(50));
@@ -221,7 +393,7 @@ impl TextInjector {
             log::debug!("SendInput sent {} of 4 events", sent);
             
             if sent != 4 {
-                return Err(format!("SendInput only sent {} of 4 events", sent).into());
+                return Err(InjectionError::SendInput { sent, expected: 4 });
             }
 
             Ok(())
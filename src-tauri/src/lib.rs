@@ -4,18 +4,31 @@ pub mod audio;
 pub mod transcription;
 pub mod formatting;
 pub mod injection;
+pub mod models;
+pub mod history;
+pub mod pipeline;
+pub mod errors;
+pub mod benchmark;
+pub mod eval;
+pub mod offline;
+pub mod paths;
 
+use std::sync::Arc;
 use std::thread;
 use tauri::{
     menu::{Menu, MenuItem},
     tray::{MouseButton, TrayIconBuilder, TrayIconEvent},
     Emitter, Manager,
 };
-use hotkey::{HotkeyManager, HotkeyEvent};
+use hotkey::{CancelFlag, HotkeyManager, HotkeyEvent};
 use audio::AudioRecorder;
-use transcription::{TranscriptionProvider, MockProvider, WhisperProvider, CloudSTTProvider};
+use transcription::{ProviderChain, ProviderReport, TranscriptEvent};
 use formatting::{FormattingEngine, TranscriptionMode};
 use injection::TextInjector;
+use history::{HistoryEntry, HistoryStore};
+use errors::{ErrorPayload, ModelError, TranscriptionError, UserFacingError};
+use pipeline::{deliver_offline_result, emit_error, emit_queue_depth, Components, DictationJob, JobQueue, LiveConfig, Pipeline, PushOutcome, StreamingJob};
+use offline::OfflineQueue;
 #[cfg(windows)]
 use windows::Win32::System::Com::{CoInitializeEx, COINIT_MULTITHREADED};
 
@@ -25,10 +38,52 @@ fn greet(name: &str) -> String {
     format!("Hello, {}! You've been greeted from Rust!", name)
 }
 
-mod config;
+pub mod config;
 use config::ConfigManager;
 
 // Config commands
+#[tauri::command]
+fn get_history(limit: Option<usize>) -> Vec<HistoryEntry> {
+    HistoryStore::new().load(limit.unwrap_or(100))
+}
+
+/// Agreement between the active and shadow providers over the last `limit` dictations
+#[tauri::command]
+fn get_shadow_report(limit: Option<usize>) -> eval::ShadowReport {
+    eval::EvalStore::new().report(limit.unwrap_or(1000))
+}
+
+/// Last health/warm-up results for the fallback chain, filled in shortly after launch
+#[derive(Default)]
+struct ProviderStatus(std::sync::Mutex<Vec<ProviderReport>>);
+
+#[tauri::command]
+fn get_provider_status(status: tauri::State<'_, ProviderStatus>) -> Vec<ProviderReport> {
+    status.0.lock().map(|reports| reports.clone()).unwrap_or_default()
+}
+
+/// Re-checks the chain as currently saved, without warming anything up
+#[tauri::command]
+async fn check_providers(app: tauri::AppHandle) -> Vec<ProviderReport> {
+    let chain = ProviderChain::from_config(&ConfigManager::new().load_config());
+    let reports = transcription::lifecycle::check_chain(chain.steps(), false).await;
+    store_provider_status(&app, reports.clone());
+    reports
+}
+
+fn store_provider_status(app: &tauri::AppHandle, reports: Vec<ProviderReport>) {
+    if let Ok(mut status) = app.state::<ProviderStatus>().0.lock() {
+        *status = reports.clone();
+    }
+    let _ = app.emit("provider-status", reports);
+}
+
+/// Cloud dictations waiting for the network to come back, oldest first
+#[tauri::command]
+fn get_offline_queue() -> Vec<offline::OfflineJob> {
+    OfflineQueue::new().pending()
+}
+
 #[tauri::command]
 fn get_config() -> config::AppConfig {
     let manager = ConfigManager::new();
@@ -36,44 +91,154 @@ fn get_config() -> config::AppConfig {
 }
 
 #[tauri::command]
-fn save_config(app: tauri::AppHandle, config: config::AppConfig) -> Result<(), String> {
-    use tauri_plugin_autostart::ManagerExt;
-    
+fn save_config(app: tauri::AppHandle, config: config::AppConfig) -> Result<(), ErrorPayload> {
     let manager = ConfigManager::new();
-    manager.save_config(&config).map_err(|e| e.to_string())?;
-    
-    // Apply autostart
+    manager.save_config(&config).map_err(|e| e.payload())?;
+    apply_config(&app, config);
+    Ok(())
+}
+
+/// Applies settings to the running app: autostart, injector, queue and, between
+/// dictations, providers and models. Rebuilt providers are checked and warmed up, and
+/// settings that only apply after a restart are reported to the UI.
+fn apply_config(app: &tauri::AppHandle, config: config::AppConfig) {
+    use tauri_plugin_autostart::ManagerExt;
+
+    paths::set_models_dir(&config.custom_models_dir);
+
     let autostart_manager = app.autolaunch();
     if config.autostart {
         let _ = autostart_manager.enable();
     } else {
         let _ = autostart_manager.disable();
     }
-    
-    Ok(())
+
+    // Registered once the logic thread has built the pipeline, which reads the saved file
+    let Some(live) = app.try_state::<Arc<LiveConfig>>() else {
+        return;
+    };
+    let applied = live.apply(config);
+    if !applied.restart_required.is_empty() {
+        let _ = app.emit("restart-required", &applied.restart_required);
+    }
+    if let Some(router) = applied.router {
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            let reports = transcription::lifecycle::check_chain(router.chain().steps(), true).await;
+            store_provider_status(&app, reports);
+        });
+    }
 }
 
 // Phase 2: Model management commands
 #[tauri::command]
 fn get_model_path(model_name: String) -> String {
-    let mut model_path = dirs::data_dir().unwrap_or_else(|| std::path::PathBuf::from("."));
-    model_path.push("VantaDictate");
-    model_path.push("models");
-    model_path.push(format!("ggml-{}.bin", model_name));
-    model_path.to_string_lossy().to_string()
+    match models::find(&model_name) {
+        Some(model) => model.path(&models::models_dir()).to_string_lossy().to_string(),
+        None => models::models_dir().join(format!("ggml-{}.bin", model_name)).to_string_lossy().to_string(),
+    }
+}
+
+#[tauri::command]
+fn get_model_catalog() -> Vec<models::CatalogEntry> {
+    models::CatalogEntry::list(models::catalog())
+}
+
+/// Fetches the catalog from `model_catalog_url`, keeping the current one if it is not newer
+#[tauri::command]
+async fn refresh_model_catalog() -> Result<Vec<models::CatalogEntry>, ErrorPayload> {
+    let url = ConfigManager::new().load_config().model_catalog_url;
+    if url.is_empty() {
+        return Err(ModelError::Catalog("no catalog URL is configured".to_string()).payload());
+    }
+    let manifest = models::catalog::refresh(&url).await.map_err(|e| e.payload())?;
+    Ok(models::CatalogEntry::list(manifest.models))
+}
+
+/// Times the bundled clip on each installed Whisper model at a few thread counts and
+/// recommends the most accurate one under the latency target; `apply` also saves it
+#[tauri::command]
+async fn tune_models(app: tauri::AppHandle, latency_target_ms: Option<u64>, apply: bool) -> Result<benchmark::tune::TuneReport, ErrorPayload> {
+    let mut config = ConfigManager::new().load_config();
+    let target = latency_target_ms.unwrap_or(config.latency_target_ms);
+    let progress = app.clone();
+    let report = benchmark::tune::tune_installed(&config, None, target, |trial| {
+        let _ = progress.emit("tuning-trial", trial);
+    }).await.map_err(|e| e.payload())?;
+
+    if apply && report.apply_to(&mut config) {
+        ConfigManager::new().save_config(&config).map_err(|e| e.payload())?;
+        let _ = app.emit("config-changed", &config);
+        apply_config(&app, config);
+    }
+    Ok(report)
+}
+
+/// Locates and validates whisper.cpp for `path` (empty = automatic) without saving it
+#[tauri::command]
+async fn check_whisper_binary(path: String) -> Result<transcription::WhisperBinary, ErrorPayload> {
+    transcription::whisper_binary::resolve(&path).await.map_err(|e| e.payload())
+}
+
+#[tauri::command]
+fn list_installed_models() -> models::manage::ModelInventory {
+    models::manage::inventory(&models::models_dir(), &models::catalog())
+}
+
+/// Full SHA-256 check of an installed model; slow for large models
+#[tauri::command]
+async fn verify_model(model_name: String) -> Result<models::manage::Verification, ErrorPayload> {
+    let model = models::find(&model_name).ok_or_else(|| ModelError::Unknown(model_name.clone()).payload())?;
+    tokio::task::spawn_blocking(move || models::manage::verify(&models::models_dir(), &model))
+        .await
+        .map_err(|e| ModelError::Io { path: models::models_dir(), source: std::io::Error::other(e) }.payload())?
+        .map_err(|e| e.payload())
+}
+
+/// Deletes a model unless the current configuration uses it; returns the bytes freed
+#[tauri::command]
+fn delete_model(model_name: String) -> Result<u64, ErrorPayload> {
+    let model = models::find(&model_name).ok_or_else(|| ModelError::Unknown(model_name.clone()).payload())?;
+    let config = ConfigManager::new().load_config();
+    if config.referenced_models().contains(&model.id.as_str()) {
+        return Err(ModelError::InUse(model.display_name).payload());
+    }
+    models::manage::delete(&models::models_dir(), &model).map_err(|e| e.payload())
+}
+
+/// Copies a ggml file (e.g. from a USB stick) into the models directory after checking it.
+/// Without `model_name` the file is matched against the catalog by checksum.
+#[tauri::command]
+async fn import_model(path: String, model_name: Option<String>) -> Result<models::ModelDescriptor, ErrorPayload> {
+    let catalog = models::catalog();
+    let model = match model_name {
+        Some(name) => Some(catalog.iter().find(|model| model.id == name).cloned().ok_or_else(|| ModelError::Unknown(name).payload())?),
+        None => None,
+    };
+    tokio::task::spawn_blocking(move || {
+        models::manage::import(&models::models_dir(), std::path::Path::new(&path), model.as_ref(), &catalog)
+    })
+    .await
+    .map_err(|e| ModelError::Io { path: models::models_dir(), source: std::io::Error::other(e) }.payload())?
+    .map_err(|e| e.payload())
 }
 
 #[tauri::command]
 fn check_model_exists(model_name: String) -> bool {
-    let model_path = get_model_path(model_name);
-    std::path::Path::new(&model_path).exists()
+    match models::find(&model_name) {
+        Some(model) => model.is_installed(&models::models_dir()),
+        None => false,
+    }
+}
+
+#[tauri::command]
+fn get_storage_paths() -> paths::StoragePaths {
+    paths::storage().clone()
 }
 
 #[tauri::command]
 fn get_models_dir() -> String {
-    let mut models_dir = dirs::data_dir().unwrap_or_else(|| std::path::PathBuf::from("."));
-    models_dir.push("VantaDictate");
-    models_dir.push("models");
+    let models_dir = models::models_dir();
     
     // Create directory if it doesn't exist
     let _ = std::fs::create_dir_all(&models_dir);
@@ -81,70 +246,90 @@ fn get_models_dir() -> String {
     models_dir.to_string_lossy().to_string()
 }
 
+/// Cancel flags for downloads in progress, by model id
+#[derive(Default)]
+struct ActiveDownloads(std::sync::Mutex<std::collections::HashMap<String, Arc<std::sync::atomic::AtomicBool>>>);
+
 #[tauri::command]
 async fn download_model(
     app: tauri::AppHandle,
+    downloads: tauri::State<'_, ActiveDownloads>,
     model_name: String,
-) -> Result<String, String> {
-    use std::io::Write;
-    
+) -> Result<String, ErrorPayload> {
     log::info!("Starting download for model: {}", model_name);
     
-    // Model URLs from HuggingFace
-    let model_url = match model_name.as_str() {
-        "tiny" => "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-tiny.bin",
-        "base" => "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-base.bin",
-        "small" => "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-small.bin",
-        "medium" => "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-medium.bin",
-        _ => return Err(format!("Unknown model: {}", model_name)),
-    };
-    
-    let model_path = get_model_path(model_name.clone());
-    let model_path_buf = std::path::PathBuf::from(&model_path);
+    let model = models::find(&model_name).ok_or_else(|| ModelError::Unknown(model_name.clone()).payload())?;
+    let models_dir = models::models_dir();
+    let file_count = model.files.len() as u64;
     
-    // Create parent directory
-    if let Some(parent) = model_path_buf.parent() {
-        std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {}", e))?;
+    let cancel = Arc::new(std::sync::atomic::AtomicBool::new(false));
+    if let Ok(mut active) = downloads.0.lock() {
+        active.insert(model_name.clone(), cancel.clone());
     }
     
-    // Download with progress
     let client = reqwest::Client::new();
-    let response = client.get(model_url)
-        .send()
-        .await
-        .map_err(|e| format!("Download failed: {}", e))?;
-    
-    if !response.status().is_success() {
-        return Err(format!("Download failed with status: {}", response.status()));
-    }
-    
-    let total_size = response.content_length().unwrap_or(0);
-    log::info!("Downloading {} bytes to {}", total_size, model_path);
+    let mut result = Ok(());
     
-    let mut file = std::fs::File::create(&model_path)
-        .map_err(|e| format!("Failed to create file: {}", e))?;
-    
-    let mut downloaded: u64 = 0;
-    let mut stream = response.bytes_stream();
-    
-    use futures_util::StreamExt;
-    while let Some(chunk) = stream.next().await {
-        let chunk = chunk.map_err(|e| format!("Download error: {}", e))?;
-        file.write_all(&chunk).map_err(|e| format!("Write error: {}", e))?;
-        
-        downloaded += chunk.len() as u64;
+    // Multi-file models (ONNX) report overall progress across their files
+    for (index, model_file) in model.files.iter().enumerate() {
+        let file_path = models_dir.join(&model_file.path);
+        match models::download::reuse_existing(&file_path, model_file) {
+            Ok(true) => {
+                log::info!("Already present, skipping: {}", file_path.display());
+                continue;
+            }
+            Ok(false) => {}
+            Err(e) => {
+                result = Err(e);
+                break;
+            }
+        }
         
-        // Emit progress event
-        if total_size > 0 {
-            let progress = (downloaded as f64 / total_size as f64 * 100.0) as u32;
-            let _ = app.emit("download-progress", progress);
+        result = models::download::download_file(
+            &client,
+            &model_file.url,
+            &file_path,
+            model_file.sha256.as_deref(),
+            &cancel,
+            |downloaded, total| {
+                if let Some(total) = total.or(model_file.size).filter(|&total| total > 0) {
+                    let file_progress = downloaded as f64 / total as f64;
+                    let progress = ((index as f64 + file_progress) / file_count as f64 * 100.0) as u32;
+                    let _ = app.emit("download-progress", progress);
+                }
+            },
+        ).await;
+        if result.is_err() {
+            break;
         }
     }
     
+    if let Ok(mut active) = downloads.0.lock() {
+        active.remove(&model_name);
+    }
+    if let Err(e) = result {
+        log::error!("Download of {} failed: {}", model_name, e);
+        return Err(e.payload());
+    }
+    
+    let model_path = model.path(&models_dir).to_string_lossy().to_string();
     log::info!("Download complete: {}", model_path);
     Ok(model_path)
 }
 
+/// Stops a running download; the partial file is kept so the next download resumes it
+#[tauri::command]
+fn cancel_download(downloads: tauri::State<'_, ActiveDownloads>, model_name: String) -> bool {
+    match downloads.0.lock().ok().and_then(|active| active.get(&model_name).cloned()) {
+        Some(cancel) => {
+            log::info!("Cancelling download of {}", model_name);
+            cancel.store(true, std::sync::atomic::Ordering::SeqCst);
+            true
+        }
+        None => false,
+    }
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     // Initialize logging
@@ -157,21 +342,33 @@ pub fn run() {
         .set_time_level(LevelFilter::Debug)
         .build();
 
+    let log_path = paths::log_file();
+    if let Some(dir) = log_path.parent() {
+        let _ = std::fs::create_dir_all(dir);
+    }
     let _ = CombinedLogger::init(
         vec![
             TermLogger::new(LevelFilter::Debug, log_config.clone(), TerminalMode::Mixed, ColorChoice::Auto),
-            WriteLogger::new(LevelFilter::Debug, log_config, File::create("vanta.log").unwrap_or_else(|_| File::create("vanta_fallback.log").unwrap())),
+            WriteLogger::new(LevelFilter::Debug, log_config, File::create(&log_path).unwrap_or_else(|_| File::create("vanta_fallback.log").unwrap())),
         ]
     );
     
     log::info!("Vanta Dictate starting up...");
+    let storage = paths::storage();
+    log::info!("Storage ({}): config {}, data {}, logs {}",
+        if storage.portable { "portable" } else { "installed" },
+        storage.config_dir.display(), storage.data_dir.display(), storage.log_dir.display());
+    paths::set_models_dir(&ConfigManager::new().load_config().custom_models_dir);
 
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_autostart::init(MacosLauncher::LaunchAgent, Some(vec![])))
         .plugin(tauri_plugin_positioner::init()) // Init positioner
         .plugin(tauri_plugin_updater::Builder::new().build())
-        .invoke_handler(tauri::generate_handler![greet, get_config, save_config, get_model_path, check_model_exists, get_models_dir, download_model])
+        .plugin(tauri_plugin_notification::init())
+        .manage(ProviderStatus::default())
+        .manage(ActiveDownloads::default())
+        .invoke_handler(tauri::generate_handler![greet, get_config, save_config, get_model_path, check_model_exists, get_models_dir, get_storage_paths, download_model, cancel_download, get_model_catalog, refresh_model_catalog, list_installed_models, verify_model, delete_model, import_model, tune_models, check_whisper_binary, get_history, get_shadow_report, get_provider_status, check_providers, get_offline_queue])
         .setup(|app| {
             log::info!("Tauri setup hook running");
             
@@ -226,52 +423,24 @@ pub fn run() {
             }
             
             // Initialize managers
-            let (hotkey_manager, hotkey_rx) = HotkeyManager::new().expect("Failed to init hotkey manager");
+            let (hotkey_manager, hotkey_rx) = HotkeyManager::new(&config.hotkey).expect("Failed to init hotkey manager");
             let register_result = "Success"; // Logging placeholder
             log::info!("Hotkey registration result: {}", register_result);
 
             // Extract values needed for the thread BEFORE moving manager
             let event_tx = hotkey_manager.get_event_sender();
             let hotkey_id = hotkey_manager.get_hotkey_id();
+            let cancel_id = hotkey_manager.get_cancel_hotkey_id();
+            let cancel_flag = CancelFlag::new();
 
             // CRITICAL: Keep hotkey_manager alive by leaking it (destructor never called)
             // This avoids Send/Sync requirements for GlobalHotKeyManager in Tauri State
             Box::leak(Box::new(hotkey_manager));
             
-            // Phase 2: Initialize transcription provider based on config
-            let transcription_provider: Box<dyn TranscriptionProvider> = match config.provider.as_str() {
-                "Whisper" => {
-                    // Get model path from app data directory
-                    let mut model_path = dirs::data_dir().unwrap_or_else(|| std::path::PathBuf::from("."));
-                    model_path.push("VantaDictate");
-                    model_path.push("models");
-                    model_path.push(format!("ggml-{}.bin", config.whisper_model));
-                    
-                    log::info!("Using Whisper provider with model: {}", model_path.display());
-                    
-                    // Check if model exists at startup
-                    if !model_path.exists() {
-                        log::warn!("Whisper model not found at: {}. User must download it.", model_path.display());
-                    }
-                    
-                    Box::new(WhisperProvider::new(model_path.to_string_lossy().to_string()))
-                }
-                "Cloud" => {
-                    log::info!("Using Cloud STT provider");
-                    Box::new(CloudSTTProvider::new(None))
-                }
-                "Mock" => {
-                    log::info!("Using Mock provider");
-                    Box::new(MockProvider::new())
-                }
-                _ => {
-                    log::warn!("Unknown provider '{}', falling back to Mock", config.provider);
-                    Box::new(MockProvider::new())
-                }
-            };
+            let history_store = Arc::new(HistoryStore::new());
             
             let formatting_engine = FormattingEngine::new(TranscriptionMode::Formatted);
-            let text_injector = TextInjector::new();
+            let text_injector = Arc::new(TextInjector::new());
             
             // Apply config to text injector
             text_injector.set_focus_delay(config.focus_delay_ms);
@@ -282,7 +451,7 @@ pub fn run() {
 
             thread::spawn(move || {
                 log::info!("Starting hotkey listener thread");
-                HotkeyManager::start_listening(event_tx, hotkey_id);
+                HotkeyManager::start_listening(event_tx, hotkey_id, cancel_id);
             });
 
             // Spawn main logic thread to handle hotkey events
@@ -302,6 +471,7 @@ pub fn run() {
                 let mut audio_recorder = AudioRecorder::new().expect("Failed to init audio recorder");
                 if let Err(e) = audio_recorder.open() {
                     log::error!("Failed to open audio recorder: {}", e);
+                    emit_error(&app_handle, &e);
                 }
                 log::info!("AudioRecorder initialized and opened on logic thread");
                 
@@ -324,10 +494,64 @@ pub fn run() {
                 
                 log::info!("Audio device ready for recording");
                 
+                // Processing runs on its own worker so a new recording never waits for it.
+                // Phase 2: transcription providers (routing policy + fallback chain) come from config
+                let offline_queue = Arc::new(OfflineQueue::new());
+                let job_queue = Arc::new(JobQueue::new(config_for_thread.queue_policy, config_for_thread.max_queued_jobs));
+                let pipeline = Arc::new(Pipeline::new(
+                    app_handle.clone(),
+                    Components::from_config(&config_for_thread),
+                    offline_queue.clone(),
+                    formatting_engine,
+                    text_injector.clone(),
+                    history_store.clone(),
+                    cancel_flag.clone(),
+                ));
+                rt.spawn(pipeline.clone().run(job_queue.clone()));
+
+                // Settings saved from the UI or edited on disk apply without a restart
+                app_handle.manage(Arc::new(LiveConfig::new(pipeline.clone(), job_queue.clone(), config_for_thread.clone())));
+                {
+                    let app_handle = app_handle.clone();
+                    rt.spawn(ConfigManager::new().watch(std::time::Duration::from_secs(2), move |config| {
+                        let _ = app_handle.emit("config-changed", &config);
+                        apply_config(&app_handle, config);
+                    }));
+                }
+
+                // Retries cloud dictations queued while offline, with the provider as currently configured.
+                // Runs even with queueing turned off so dictations queued earlier are still delivered.
+                {
+                    let (app_handle, text_injector, history_store) = (app_handle.clone(), text_injector.clone(), history_store.clone());
+                    rt.spawn(offline_queue.run(
+                        std::time::Duration::from_secs(120),
+                        |provider| transcription::chain::create_provider(provider, &ConfigManager::new().load_config()),
+                        move |result| deliver_offline_result(app_handle.clone(), text_injector.clone(), history_store.clone(), result),
+                    ));
+                }
+
+                // Surface missing models/binaries/servers now, and load models before the first dictation
+                {
+                    let app_handle = app_handle.clone();
+                    let router = pipeline.components().router.clone();
+                    rt.spawn(async move {
+                        let reports = transcription::lifecycle::check_chain(router.chain().steps(), true).await;
+                        store_provider_status(&app_handle, reports);
+                    });
+                }
+                
+                // In-flight streaming transcription, started on press and awaited on release
+                let mut streaming_job: Option<StreamingJob> = None;
+                // Cleared when the hotkey is released or the recording is cancelled
+                let mut recording = false;
+                let mut focus_target = None;
+                
                 while let Ok(event) = hotkey_rx.recv() {
                     match event {
                         HotkeyEvent::Pressed => {
                             log::info!("Hotkey Pressed: Starting recording sequence");
+                            recording = true;
+                            pipeline.set_recording(true);
                             
                             // 1. Show HUD overlay (click-through)
                             if let Some(hud) = app_handle.get_webview_window("hud") {
@@ -348,26 +572,99 @@ pub fn run() {
                                 log::error!("HUD window not found!");
                             }
 
-                            // 2. Capture focus
-                            text_injector.capture_focus();
+                            // 2. Capture focus (each job injects into its own window)
+                            focus_target = Some(text_injector.capture_focus());
                             
                             // 3. Audio feedback: Start
                             text_injector.play_beep(true);
                             
                             // 4. Start Recording (Tell the worker to start collecting)
-                            if let Err(e) = audio_recorder.start_recording() {
+                            let components = pipeline.components();
+                            let streaming_primary = components.router.chain().primary()
+                                .filter(|step| components.router.streaming_enabled() && step.provider.supports_streaming())
+                                .map(|step| step.provider.clone());
+                            if let Some(provider) = streaming_primary.filter(|_| !components.injection_test_mode) {
+                                // Streaming providers consume frames while the user is still speaking
+                                let (frame_tx, frame_rx) = tokio::sync::mpsc::unbounded_channel();
+                                let (event_tx, mut event_rx) = tokio::sync::mpsc::unbounded_channel();
+                                
+                                if let Err(e) = audio_recorder.start_streaming(frame_tx) {
+                                    log::error!("Failed to start streaming collection: {}", e);
+                                    emit_error(&app_handle, &e);
+                                } else {
+                                    log::debug!("Streaming collection started successfully");
+                                }
+                                
+                                streaming_job = Some(rt.spawn(async move {
+                                    provider.transcribe_stream(frame_rx, 16000, event_tx).await
+                                }));
+                                
+                                let hud_handle = app_handle.clone();
+                                rt.spawn(async move {
+                                    while let Some(event) = event_rx.recv().await {
+                                        if let Some(hud) = hud_handle.get_webview_window("hud") {
+                                            let _ = match event {
+                                                TranscriptEvent::Interim(text) => hud.emit("transcription-interim", text),
+                                                TranscriptEvent::Final(text) => hud.emit("transcription-final-segment", text),
+                                            };
+                                        }
+                                    }
+                                });
+                            } else if let Err(e) = audio_recorder.start_recording() {
                                 log::error!("Failed to start recording collection: {}", e);
+                                emit_error(&app_handle, &e);
                             } else {
                                 log::debug!("Recording collection started successfully");
                             }
                         }
+                        // Escape is seen system-wide, so it only ever discards a recording;
+                        // a transcription in flight is cancelled with the cancel hotkey
+                        HotkeyEvent::Escape if !recording => {
+                            log::debug!("Escape ignored while not recording");
+                        }
+                        HotkeyEvent::Escape | HotkeyEvent::Cancel => {
+                            if !recording {
+                                // Not recording: cancel the job the worker is processing, if any
+                                if job_queue.is_busy() {
+                                    log::info!("Cancelling in-flight transcription");
+                                    cancel_flag.cancel();
+                                } else {
+                                    log::debug!("Cancel received while idle");
+                                }
+                                continue;
+                            }
+                            log::info!("Recording cancelled: discarding audio");
+                            recording = false;
+                            pipeline.set_recording(false);
+                            focus_target = None;
+                            
+                            let discarded = audio_recorder.stop_recording();
+                            if let Some(job) = streaming_job.take() {
+                                job.abort();
+                            }
+                            log::info!("Discarded {} samples", discarded.len());
+                            
+                            if let Some(hud) = app_handle.get_webview_window("hud") {
+                                // Earlier dictations may still be showing progress
+                                if !job_queue.is_busy() {
+                                    let _ = hud.hide();
+                                }
+                                let _ = hud.emit("recording-stop", ());
+                                let _ = hud.emit("transcription-cancelled", ());
+                            }
+                        }
                         HotkeyEvent::Released => {
+                            if !recording {
+                                log::info!("Hotkey Released after cancel, nothing to transcribe");
+                                continue;
+                            }
+                            recording = false;
+                            pipeline.set_recording(false);
                             log::info!("Hotkey Released: Stopping recording sequence");
                             let release_time = std::time::Instant::now();
                             
-                            // 1. Hide HUD
+                            // 1. HUD switches to the processing indicator; the worker hides it once idle
                             if let Some(hud) = app_handle.get_webview_window("hud") {
-                                let _ = hud.hide();
                                 let _ = hud.emit("recording-stop", ());
                             }
                             
@@ -389,58 +686,29 @@ pub fn run() {
                                 log::warn!("Audio buffer empty, but continuing with transcription for testing.");
                             }
                             
-                            // 4. Transcribe and Inject
-                            rt.block_on(async {
-                                // PHASE 1: Injection Test Mode
-                                let transcribe_start = std::time::Instant::now();
-                                let text = if config_for_thread.injection_test_mode {
-                                    log::info!("INJECTION TEST MODE: Skipping transcription");
-                                    "Test transcription successful".to_string()
-                                } else {
-                                    log::info!("Starting transcription...");
-                                    match transcription_provider.transcribe(audio_data, sample_rate).await {
-                                        Ok(t) => {
-                                            let transcribe_duration = transcribe_start.elapsed();
-                                            if t.is_empty() {
-                                                log::error!("Transcription returned empty text [took: {:?}]", transcribe_duration);
-                                                // Show error to user via HUD
-                                                if let Some(hud) = app_handle.get_webview_window("hud") {
-                                                    let _ = hud.emit("transcription-error", "Transcription returned empty text");
-                                                }
-                                                return;
-                                            }
-                                            log::info!("Transcription success: '{}' (len: {}) [took: {:?}]", t, t.len(), transcribe_duration);
-                                            t
-                                        }
-                                        Err(e) => {
-                                            let transcribe_duration = transcribe_start.elapsed();
-                                            log::error!("Transcription failed: {} [took: {:?}]", e, transcribe_duration);
-                                            // Show error to user via HUD
-                                            if let Some(hud) = app_handle.get_webview_window("hud") {
-                                                let _ = hud.emit("transcription-error", format!("Transcription failed: {}", e));
-                                            }
-                                            return;
-                                        }
-                                    }
-                                };
-                                
-                                let format_start = std::time::Instant::now();
-                                let formatted = formatting_engine.format(&text);
-                                let format_duration = format_start.elapsed();
-                                log::debug!("Formatted text for injection: '{}' [took: {:?}]", formatted, format_duration);
-                                
-                                // inject() handles restore_focus internally and has fallback
-                                let inject_start = std::time::Instant::now();
-                                if let Err(e) = text_injector.inject(&formatted) {
-                                    log::error!("Injection pipeline failed: {}", e);
-                                } else {
-                                    let inject_duration = inject_start.elapsed();
-                                    let total_duration = release_time.elapsed();
-                                    log::info!("Injection pipeline completed successfully! [inject took: {:?}]", inject_duration);
-                                    log::info!("⏱️  TOTAL PIPELINE: {:?} (stop: {:?}, transcribe: {:?}, format: {:?}, inject: {:?})", 
-                                        total_duration, stop_duration, transcribe_start.elapsed(), format_duration, inject_duration);
+                            // 4. Hand off to the processing worker; capture is free again immediately
+                            let job = DictationJob {
+                                audio: audio_data,
+                                sample_rate,
+                                streaming_job: streaming_job.take(),
+                                target: focus_target.take().unwrap_or_else(|| text_injector.capture_focus()),
+                                released_at: release_time,
+                                stop_duration,
+                            };
+                            match job_queue.push(job) {
+                                PushOutcome::Queued { depth } => {
+                                    log::info!("Dictation queued (depth: {})", depth);
+                                    emit_queue_depth(&app_handle, depth);
+                                }
+                                PushOutcome::Merged { depth } => {
+                                    log::info!("Dictation merged into the last queued job (depth: {})", depth);
+                                    emit_queue_depth(&app_handle, depth);
                                 }
-                            });
+                                PushOutcome::Dropped { depth } => {
+                                    log::warn!("Dictation dropped: {} job(s) still processing", depth);
+                                    emit_error(&app_handle, &TranscriptionError::QueueFull { depth });
+                                }
+                            }
                         }
                     }
                 }
@@ -0,0 +1,154 @@
+// Model catalog - versioned manifest of downloadable models
+//
+// The manifest bundled with the app (catalog.json) is always available. `refresh`
+// fetches a newer revision from the configured URL and caches it in the data dir; the
+// cache wins only while its revision is newer than the bundled one, so an app update
+// with a newer bundled catalog is never shadowed by a stale download.
+
+use super::ModelDescriptor;
+use crate::errors::ModelError;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+
+const BUNDLED: &str = include_str!("catalog.json");
+
+/// Manifest layout this build understands; bumped on incompatible changes
+pub const SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Manifest {
+    pub schema: u32,
+    /// Increases with every published change to the model list
+    pub revision: u32,
+    pub models: Vec<ModelDescriptor>,
+}
+
+impl Manifest {
+    pub fn parse(json: &str) -> Result<Self, ModelError> {
+        let manifest: Manifest = serde_json::from_str(json).map_err(|e| ModelError::Catalog(e.to_string()))?;
+        if manifest.schema != SCHEMA_VERSION {
+            return Err(ModelError::Catalog(format!("unsupported schema {} (expected {})", manifest.schema, SCHEMA_VERSION)));
+        }
+        let mut ids = HashSet::new();
+        for model in &manifest.models {
+            if !ids.insert(model.id.as_str()) {
+                return Err(ModelError::Catalog(format!("duplicate model id '{}'", model.id)));
+            }
+            if model.files.is_empty() {
+                return Err(ModelError::Catalog(format!("model '{}' has no files", model.id)));
+            }
+        }
+        Ok(manifest)
+    }
+
+    pub fn bundled() -> Self {
+        Self::parse(BUNDLED).expect("bundled model catalog is valid")
+    }
+}
+
+fn cache_path() -> PathBuf {
+    crate::paths::storage().data_dir.join("model_catalog.json")
+}
+
+/// The newer of the bundled manifest and the cached refresh, if any
+pub fn load() -> Manifest {
+    let cached = fs::read_to_string(cache_path()).ok().and_then(|json| match Manifest::parse(&json) {
+        Ok(manifest) => Some(manifest),
+        Err(e) => {
+            log::warn!("Ignoring cached model catalog: {}", e);
+            None
+        }
+    });
+    newest(Manifest::bundled(), cached)
+}
+
+fn newest(bundled: Manifest, cached: Option<Manifest>) -> Manifest {
+    match cached {
+        Some(cached) if cached.revision > bundled.revision => cached,
+        _ => bundled,
+    }
+}
+
+/// Fetches the manifest at `url` and caches it if it is newer than what we have
+pub async fn refresh(url: &str) -> Result<Manifest, ModelError> {
+    let current = load();
+    let response = reqwest::get(url).await
+        .map_err(|e| ModelError::Network { url: url.to_string(), details: e.to_string() })?;
+    if !response.status().is_success() {
+        return Err(ModelError::Http { url: url.to_string(), status: response.status().as_u16() });
+    }
+    let json = response.text().await
+        .map_err(|e| ModelError::Network { url: url.to_string(), details: e.to_string() })?;
+    let fetched = Manifest::parse(&json)?;
+
+    if fetched.revision <= current.revision {
+        log::info!("Model catalog is up to date (revision {})", current.revision);
+        return Ok(current);
+    }
+
+    // Write-then-rename so a crash never leaves a half-written cache
+    let path = cache_path();
+    let tmp = path.with_extension("json.tmp");
+    let io_err = |source| ModelError::Io { path: path.clone(), source };
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(io_err)?;
+    }
+    fs::write(&tmp, &json).map_err(io_err)?;
+    fs::rename(&tmp, &path).map_err(io_err)?;
+    log::info!("Model catalog updated from revision {} to {} ({} models)", current.revision, fetched.revision, fetched.models.len());
+    Ok(fetched)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ModelProvider;
+
+    #[test]
+    fn test_bundled_catalog_is_consistent() {
+        let manifest = Manifest::bundled();
+        for model in &manifest.models {
+            assert_eq!(model.id.contains(".en"), model.languages == ["en"] && model.provider == ModelProvider::Whisper, "{}", model.id);
+            if let Some(quantization) = &model.quantization {
+                assert!(model.id.ends_with(quantization.as_str()), "{}", model.id);
+            }
+            assert!(model.ram_mb > model.size_mb, "{}", model.id);
+        }
+        assert!(manifest.models.iter().any(|model| model.id == "large-v3-turbo-q5_0"));
+    }
+
+    #[test]
+    fn test_bundled_checksums_are_well_formed() {
+        // Files without a published checksum are listed as unverified instead of being left out
+        let manifest = Manifest::bundled();
+        for model in &manifest.models {
+            for file in &model.files {
+                if let Some(sha) = &file.sha256 {
+                    assert!(sha.len() == 64 && sha.chars().all(|c| c.is_ascii_hexdigit()), "{} {}", model.id, file.path);
+                    assert!(file.size.is_some_and(|size| size > 0), "{} {}", model.id, file.path);
+                }
+            }
+        }
+        let checksummed = |id: &str| manifest.models.iter().any(|model| model.id == id && model.files.iter().all(|file| file.sha256.is_some()));
+        assert!(["tiny", "base", "small", "medium"].into_iter().all(checksummed));
+    }
+    #[test]
+    fn test_newer_revision_wins_and_bad_manifests_are_rejected() {
+        let mut cached = Manifest::bundled();
+        cached.revision += 1;
+        cached.models.truncate(1);
+        assert_eq!(newest(Manifest::bundled(), Some(cached.clone())).models.len(), 1);
+
+        cached.revision = 0;
+        assert_eq!(newest(Manifest::bundled(), Some(cached)).models.len(), Manifest::bundled().models.len());
+
+        assert!(Manifest::parse(r#"{"schema": 99, "revision": 5, "models": []}"#).is_err());
+        let duplicate = r#"{"schema": 1, "revision": 5, "models": [
+            {"id": "x", "display_name": "X", "provider": "Whisper", "family": "x", "size_mb": 1, "ram_mb": 2, "files": [{"path": "a", "url": "u"}]},
+            {"id": "x", "display_name": "X", "provider": "Whisper", "family": "x", "size_mb": 1, "ram_mb": 2, "files": [{"path": "a", "url": "u"}]}
+        ]}"#;
+        assert!(matches!(Manifest::parse(duplicate), Err(ModelError::Catalog(e)) if e.contains("duplicate")));
+    }
+}
@@ -0,0 +1,259 @@
+// Model downloads - resumable, checksum-verified and atomic
+//
+// Files are downloaded to `<name>.part` next to their final path. An interrupted
+// download is resumed with an HTTP Range request, the finished file is checked against
+// the catalog's SHA-256, and only then renamed into place, so a model file that exists
+// is always complete. Files left by older versions, which wrote straight to the final
+// path, are checked against the catalog before being reused.
+
+use super::manage;
+use super::ModelFile;
+use crate::errors::ModelError;
+use futures_util::StreamExt;
+use sha2::{Digest, Sha256};
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Where an in-progress download of `dest` is kept
+pub fn part_path(dest: &Path) -> PathBuf {
+    let mut name = dest.file_name().unwrap_or_default().to_os_string();
+    name.push(".part");
+    dest.with_file_name(name)
+}
+
+/// Lowercase hex SHA-256 of a file
+pub fn sha256_file(path: &Path) -> Result<String, ModelError> {
+    let io_err = |source| ModelError::Io { path: path.to_path_buf(), source };
+    let mut file = File::open(path).map_err(io_err)?;
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; 1 << 20];
+    loop {
+        let n = file.read(&mut buf).map_err(io_err)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// Whether `dest` already holds `file` with the catalog's size and, when known, checksum.
+/// A shorter file is moved to the part path so the download resumes it; anything else
+/// that doesn't match is removed so it is downloaded again.
+pub fn reuse_existing(dest: &Path, file: &ModelFile) -> Result<bool, ModelError> {
+    let Ok(meta) = fs::metadata(dest) else {
+        return Ok(false);
+    };
+    let io_err = |source| ModelError::Io { path: dest.to_path_buf(), source };
+
+    if file.size.is_none_or(|size| size == meta.len()) {
+        let Some(expected) = &file.sha256 else {
+            return Ok(true);
+        };
+        if sha256_file(dest)?.eq_ignore_ascii_case(expected) {
+            manage::mark_verified(dest, expected);
+            return Ok(true);
+        }
+        log::warn!("{} does not match its checksum, downloading it again", dest.display());
+        fs::remove_file(dest).map_err(io_err)?;
+        return Ok(false);
+    }
+
+    let part = part_path(dest);
+    if file.size.is_some_and(|size| meta.len() < size) && !part.exists() {
+        log::info!("{} is incomplete ({} bytes), resuming it", dest.display(), meta.len());
+        fs::rename(dest, &part).map_err(io_err)?;
+    } else {
+        log::warn!("{} has the wrong size ({} bytes), downloading it again", dest.display(), meta.len());
+        fs::remove_file(dest).map_err(io_err)?;
+    }
+    Ok(false)
+}
+
+/// Downloads `url` to `dest`, resuming a previous partial download if there is one.
+///
+/// `on_progress(downloaded, total)` counts resumed bytes too. Setting `cancel` stops
+/// the download and keeps the partial file for the next attempt.
+pub async fn download_file<P: FnMut(u64, Option<u64>)>(
+    client: &reqwest::Client,
+    url: &str,
+    dest: &Path,
+    expected_sha256: Option<&str>,
+    cancel: &AtomicBool,
+    mut on_progress: P,
+) -> Result<(), ModelError> {
+    let part = part_path(dest);
+    let io_err = |source| ModelError::Io { path: part.clone(), source };
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent).map_err(|source| ModelError::Io { path: parent.to_path_buf(), source })?;
+    }
+
+    let mut offset = fs::metadata(&part).map(|meta| meta.len()).unwrap_or(0);
+    let mut request = client.get(url);
+    if offset > 0 {
+        log::info!("Resuming {} at byte {}", dest.display(), offset);
+        request = request.header(reqwest::header::RANGE, format!("bytes={}-", offset));
+    }
+    let response = request.send().await
+        .map_err(|e| ModelError::Network { url: url.to_string(), details: e.to_string() })?;
+
+    let status = response.status();
+    let total = match status {
+        reqwest::StatusCode::PARTIAL_CONTENT => response.content_length().map(|len| len + offset),
+        // The part file already holds everything the server has
+        reqwest::StatusCode::RANGE_NOT_SATISFIABLE if offset > 0 => None,
+        s if s.is_success() => {
+            if offset > 0 {
+                log::warn!("Server ignored the range request, restarting {}", dest.display());
+                offset = 0;
+            }
+            response.content_length()
+        }
+        s => return Err(ModelError::Http { url: url.to_string(), status: s.as_u16() }),
+    };
+
+    if status != reqwest::StatusCode::RANGE_NOT_SATISFIABLE {
+        let mut file = if offset > 0 {
+            OpenOptions::new().append(true).open(&part)
+        } else {
+            File::create(&part)
+        }.map_err(io_err)?;
+        log::info!("Downloading {} ({:?} bytes) to {}", url, total, part.display());
+
+        let mut downloaded = offset;
+        let mut stream = response.bytes_stream();
+        while let Some(chunk) = stream.next().await {
+            if cancel.load(Ordering::SeqCst) {
+                log::info!("Download of {} cancelled at {} bytes", dest.display(), downloaded);
+                return Err(ModelError::Cancelled);
+            }
+            let chunk = chunk.map_err(|e| ModelError::Network { url: url.to_string(), details: e.to_string() })?;
+            file.write_all(&chunk).map_err(io_err)?;
+            downloaded += chunk.len() as u64;
+            on_progress(downloaded, total);
+        }
+        file.sync_all().map_err(io_err)?;
+    }
+
+    if let Some(expected) = expected_sha256 {
+        let actual = sha256_file(&part)?;
+        if !actual.eq_ignore_ascii_case(expected) {
+            let _ = fs::remove_file(&part);
+            return Err(ModelError::ChecksumMismatch { path: dest.to_path_buf(), expected: expected.to_string(), actual });
+        }
+        log::info!("Verified SHA-256 of {}", dest.display());
+    } else {
+        log::warn!("No checksum in the catalog for {}, skipping verification", dest.display());
+    }
+
+    fs::rename(&part, dest).map_err(|source| ModelError::Io { path: dest.to_path_buf(), source })?;
+    if let Some(expected) = expected_sha256 {
+        manage::mark_verified(dest, expected);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Local stand-in for the model host: serves `body` over HTTP/1.1 and honours
+    /// `Range: bytes=N-`, recording the range each request asked for
+    async fn spawn_stand_in_server(body: Vec<u8>) -> (String, tokio::sync::mpsc::UnboundedReceiver<Option<u64>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (ranges_tx, ranges_rx) = tokio::sync::mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut request = Vec::new();
+                let mut buf = [0u8; 1024];
+                while !request.ends_with(b"\r\n\r\n") {
+                    let n = stream.read(&mut buf).await.unwrap();
+                    if n == 0 {
+                        break;
+                    }
+                    request.extend_from_slice(&buf[..n]);
+                }
+                let request = String::from_utf8_lossy(&request).to_lowercase();
+                let start = request.lines()
+                    .find_map(|line| line.strip_prefix("range: bytes="))
+                    .and_then(|range| range.trim_end_matches('-').parse::<u64>().ok());
+                ranges_tx.send(start).unwrap();
+
+                let head = match start {
+                    Some(start) => format!("HTTP/1.1 206 Partial Content\r\nContent-Length: {}\r\nContent-Range: bytes {}-{}/{}\r\n\r\n",
+                        body.len() as u64 - start, start, body.len() - 1, body.len()),
+                    None => format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n", body.len()),
+                };
+                stream.write_all(head.as_bytes()).await.unwrap();
+                stream.write_all(&body[start.unwrap_or(0) as usize..]).await.unwrap();
+            }
+        });
+        (format!("http://{}/ggml-test.bin", addr), ranges_rx)
+    }
+
+    #[tokio::test]
+    async fn test_resumes_verifies_and_renames() {
+        let body: Vec<u8> = (0..200_000u32).map(|i| (i % 251) as u8).collect();
+        let expected = format!("{:x}", Sha256::digest(&body));
+        let (url, mut ranges) = spawn_stand_in_server(body.clone()).await;
+
+        let dir = std::env::temp_dir().join(format!("vanta_download_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let dest = dir.join("ggml-test.bin");
+        let client = reqwest::Client::new();
+
+        // Interrupted earlier: the first 50 000 bytes are already on disk
+        fs::write(part_path(&dest), &body[..50_000]).unwrap();
+        let mut last = (0, None);
+        download_file(&client, &url, &dest, Some(&expected), &AtomicBool::new(false), |done, total| last = (done, total)).await.unwrap();
+        assert_eq!(ranges.recv().await, Some(Some(50_000)));
+        assert_eq!(last, (200_000, Some(200_000)));
+        assert_eq!(fs::read(&dest).unwrap(), body);
+        assert!(!part_path(&dest).exists());
+
+        // A bad checksum never produces the final file
+        fs::remove_file(&dest).unwrap();
+        let err = download_file(&client, &url, &dest, Some("00"), &AtomicBool::new(false), |_, _| {}).await.unwrap_err();
+        assert!(matches!(err, ModelError::ChecksumMismatch { .. }));
+        assert!(!dest.exists() && !part_path(&dest).exists());
+
+        // Cancelling keeps nothing at the final path
+        let err = download_file(&client, &url, &dest, Some(&expected), &AtomicBool::new(true), |_, _| {}).await.unwrap_err();
+        assert!(matches!(err, ModelError::Cancelled));
+        assert!(!dest.exists());
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_truncated_file_from_older_version_is_resumed() {
+        let body: Vec<u8> = (0..200_000u32).map(|i| (i % 251) as u8).collect();
+        let expected = format!("{:x}", Sha256::digest(&body));
+        let (url, mut ranges) = spawn_stand_in_server(body.clone()).await;
+
+        let dir = std::env::temp_dir().join(format!("vanta_download_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let dest = dir.join("ggml-test.bin");
+        let file = ModelFile { path: "ggml-test.bin".into(), url: url.clone(), size: Some(body.len() as u64), sha256: Some(expected.clone()) };
+
+        // Written straight to the final path and cut short
+        fs::write(&dest, &body[..50_000]).unwrap();
+        assert!(!reuse_existing(&dest, &file).unwrap());
+        assert!(!dest.exists() && part_path(&dest).exists());
+        download_file(&reqwest::Client::new(), &url, &dest, Some(&expected), &AtomicBool::new(false), |_, _| {}).await.unwrap();
+        assert_eq!(ranges.recv().await, Some(Some(50_000)));
+        assert!(reuse_existing(&dest, &file).unwrap());
+
+        // Right size, wrong content
+        fs::write(&dest, vec![0u8; body.len()]).unwrap();
+        assert!(!reuse_existing(&dest, &file).unwrap());
+        assert!(!dest.exists());
+
+        let _ = fs::remove_dir_all(dir);
+    }
+}
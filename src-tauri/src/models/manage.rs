@@ -0,0 +1,271 @@
+// Installed models - inventory, verification, deletion and import of local files
+//
+// Hashing a 1.5GB model takes seconds, so a successful SHA-256 check is remembered in a
+// `<file>.verified` sidecar holding the digest and size. Listing only compares sizes
+// and sidecars; `verify` does the full hash.
+
+use super::download::{part_path, sha256_file};
+use super::{ModelDescriptor, ModelFile, ModelProvider};
+use crate::errors::ModelError;
+use serde::Serialize;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// First bytes of a whisper.cpp model: legacy ggml ("ggml" as a little-endian u32) or GGUF
+const GGML_MAGIC: &[u8; 4] = b"lmgg";
+const GGUF_MAGIC: &[u8; 4] = b"GGUF";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Verification {
+    /// SHA-256 matched the catalog
+    Verified,
+    /// Size matches, or the catalog has no checksum; not hashed yet
+    Unverified,
+    /// Size or checksum differs from the catalog
+    Corrupt,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InstalledModel {
+    pub id: String,
+    pub display_name: String,
+    pub provider: ModelProvider,
+    pub path: PathBuf,
+    pub size_bytes: u64,
+    pub verification: Verification,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelInventory {
+    pub models_dir: PathBuf,
+    pub models: Vec<InstalledModel>,
+    /// Everything under the models directory, including unknown and partial files
+    pub total_bytes: u64,
+    /// Interrupted downloads waiting to be resumed
+    pub partial_bytes: u64,
+}
+
+fn marker_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".verified");
+    path.with_file_name(name)
+}
+
+/// Remembers that `path` hashed to `sha256`
+pub fn mark_verified(path: &Path, sha256: &str) {
+    let size = fs::metadata(path).map(|meta| meta.len()).unwrap_or(0);
+    if let Err(e) = fs::write(marker_path(path), format!("{} {}", sha256, size)) {
+        log::warn!("Failed to record verification of {}: {}", path.display(), e);
+    }
+}
+
+fn file_status(path: &Path, file: &ModelFile) -> Verification {
+    let size = fs::metadata(path).map(|meta| meta.len()).unwrap_or(0);
+    if file.size.is_some_and(|expected| expected != size) {
+        return Verification::Corrupt;
+    }
+    let Some(expected) = &file.sha256 else {
+        return Verification::Unverified;
+    };
+    let marker = fs::read_to_string(marker_path(path)).unwrap_or_default();
+    match marker.split_once(' ') {
+        Some((sha, marked_size)) if sha.eq_ignore_ascii_case(expected) && marked_size.trim() == size.to_string() => Verification::Verified,
+        Some((sha, marked_size)) if marked_size.trim() == size.to_string() && !sha.eq_ignore_ascii_case(expected) => Verification::Corrupt,
+        _ => Verification::Unverified,
+    }
+}
+
+/// Worst status across a model's files
+fn combine(statuses: impl Iterator<Item = Verification>) -> Verification {
+    statuses.fold(Verification::Verified, |worst, status| match (worst, status) {
+        (Verification::Corrupt, _) | (_, Verification::Corrupt) => Verification::Corrupt,
+        (Verification::Unverified, _) | (_, Verification::Unverified) => Verification::Unverified,
+        _ => Verification::Verified,
+    })
+}
+
+fn dir_size(path: &Path, partial: &mut u64) -> u64 {
+    let Ok(entries) = fs::read_dir(path) else {
+        return 0;
+    };
+    entries.filter_map(Result::ok).map(|entry| {
+        let path = entry.path();
+        match entry.metadata() {
+            Ok(meta) if meta.is_dir() => dir_size(&path, partial),
+            Ok(meta) => {
+                if path.extension().is_some_and(|ext| ext == "part") {
+                    *partial += meta.len();
+                }
+                meta.len()
+            }
+            Err(_) => 0,
+        }
+    }).sum()
+}
+
+/// Catalog models whose files are all present, with sizes and verification status
+pub fn inventory(models_dir: &Path, catalog: &[ModelDescriptor]) -> ModelInventory {
+    let models = catalog.iter()
+        .filter(|model| model.files.iter().all(|file| models_dir.join(&file.path).is_file()))
+        .map(|model| InstalledModel {
+            id: model.id.clone(),
+            display_name: model.display_name.clone(),
+            provider: model.provider,
+            path: model.path(models_dir),
+            size_bytes: model.files.iter()
+                .filter_map(|file| fs::metadata(models_dir.join(&file.path)).ok())
+                .map(|meta| meta.len())
+                .sum(),
+            verification: combine(model.files.iter().map(|file| file_status(&models_dir.join(&file.path), file))),
+        })
+        .collect();
+
+    let mut partial_bytes = 0;
+    let total_bytes = dir_size(models_dir, &mut partial_bytes);
+    ModelInventory { models_dir: models_dir.to_path_buf(), models, total_bytes, partial_bytes }
+}
+
+/// Hashes every file of an installed model and records the result
+pub fn verify(models_dir: &Path, model: &ModelDescriptor) -> Result<Verification, ModelError> {
+    let mut statuses = Vec::new();
+    for file in &model.files {
+        let path = models_dir.join(&file.path);
+        if !path.is_file() {
+            return Err(ModelError::NotInstalled(model.id.clone()));
+        }
+        let actual = sha256_file(&path)?;
+        mark_verified(&path, &actual);
+        statuses.push(file_status(&path, file));
+    }
+    let status = combine(statuses.into_iter());
+    log::info!("Verified {}: {:?}", model.id, status);
+    Ok(status)
+}
+
+/// Removes a model's files, their verification markers and any partial download
+pub fn delete(models_dir: &Path, model: &ModelDescriptor) -> Result<u64, ModelError> {
+    let mut freed = 0;
+    for file in &model.files {
+        let path = models_dir.join(&file.path);
+        for candidate in [marker_path(&path), part_path(&path), path] {
+            if let Ok(meta) = fs::metadata(&candidate) {
+                fs::remove_file(&candidate).map_err(|source| ModelError::Io { path: candidate.clone(), source })?;
+                freed += meta.len();
+            }
+        }
+    }
+    // ONNX models live in their own folder; drop it once it is empty
+    if model.provider == ModelProvider::Onnx {
+        let _ = fs::remove_dir(model.path(models_dir));
+    }
+    log::info!("Deleted model {} ({} bytes freed)", model.id, freed);
+    Ok(freed)
+}
+
+fn check_magic(source: &Path) -> Result<(), ModelError> {
+    let mut magic = [0u8; 4];
+    File::open(source)
+        .and_then(|mut file| file.read_exact(&mut magic))
+        .map_err(|source_err| ModelError::InvalidFile { path: source.to_path_buf(), reason: source_err.to_string() })?;
+    if &magic != GGML_MAGIC && &magic != GGUF_MAGIC {
+        return Err(ModelError::InvalidFile { path: source.to_path_buf(), reason: "not a whisper.cpp (ggml) model file".to_string() });
+    }
+    Ok(())
+}
+
+/// Copies a ggml file into the models directory as `model` (or, if `None`, as the catalog
+/// model whose checksum it matches). The file is checked before anything is written.
+pub fn import(models_dir: &Path, source: &Path, model: Option<&ModelDescriptor>, catalog: &[ModelDescriptor]) -> Result<ModelDescriptor, ModelError> {
+    check_magic(source)?;
+    let actual = sha256_file(source)?;
+
+    let model = match model {
+        Some(model) => model.clone(),
+        None => catalog.iter()
+            .find(|model| model.files.len() == 1 && model.files[0].sha256.as_deref().is_some_and(|sha| sha.eq_ignore_ascii_case(&actual)))
+            .cloned()
+            .ok_or_else(|| ModelError::InvalidFile { path: source.to_path_buf(), reason: "its checksum matches no model in the catalog".to_string() })?,
+    };
+    if model.provider != ModelProvider::Whisper || model.files.len() != 1 {
+        return Err(ModelError::InvalidFile { path: source.to_path_buf(), reason: format!("{} cannot be imported from a single file", model.display_name) });
+    }
+
+    let file = &model.files[0];
+    let size = fs::metadata(source).map(|meta| meta.len()).unwrap_or(0);
+    if let Some(expected) = &file.sha256 {
+        if !expected.eq_ignore_ascii_case(&actual) {
+            return Err(ModelError::ChecksumMismatch { path: source.to_path_buf(), expected: expected.clone(), actual });
+        }
+    } else if file.size.is_some_and(|expected| expected != size) {
+        return Err(ModelError::InvalidFile { path: source.to_path_buf(), reason: format!("expected {} bytes, file has {}", file.size.unwrap_or(0), size) });
+    }
+
+    // Copy next to the destination, then rename, so a failed copy never looks installed
+    let dest = models_dir.join(&file.path);
+    let part = part_path(&dest);
+    let io_err = |source| ModelError::Io { path: dest.clone(), source };
+    fs::create_dir_all(models_dir).map_err(io_err)?;
+    fs::copy(source, &part).map_err(io_err)?;
+    fs::rename(&part, &dest).map_err(io_err)?;
+    mark_verified(&dest, &actual);
+
+    log::info!("Imported {} as {} ({} bytes)", source.display(), model.id, size);
+    Ok(model)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sha2::{Digest, Sha256};
+
+    fn test_model(id: &str, sha256: Option<String>) -> ModelDescriptor {
+        ModelDescriptor {
+            id: id.to_string(),
+            display_name: id.to_string(),
+            provider: ModelProvider::Whisper,
+            family: id.to_string(),
+            languages: Vec::new(),
+            quantization: None,
+            size_mb: 1,
+            ram_mb: 2,
+            files: vec![ModelFile { path: format!("ggml-{}.bin", id), url: String::new(), size: None, sha256 }],
+        }
+    }
+
+    #[test]
+    fn test_import_list_and_delete() {
+        let root = std::env::temp_dir().join(format!("vanta_models_{}", uuid::Uuid::new_v4()));
+        let models_dir = root.join("models");
+        fs::create_dir_all(&root).unwrap();
+
+        let contents = [GGML_MAGIC.as_slice(), &[7u8; 4096]].concat();
+        let source = root.join("usb-copy.bin");
+        fs::write(&source, &contents).unwrap();
+        let not_a_model = root.join("notes.txt");
+        fs::write(&not_a_model, "hello world").unwrap();
+
+        let catalog = vec![test_model("tiny", Some(format!("{:x}", Sha256::digest(&contents)))), test_model("base", Some("00".repeat(32)))];
+
+        assert!(matches!(import(&models_dir, &not_a_model, None, &catalog), Err(ModelError::InvalidFile { .. })));
+        assert!(matches!(import(&models_dir, &source, Some(&catalog[1]), &catalog), Err(ModelError::ChecksumMismatch { .. })));
+        assert_eq!(import(&models_dir, &source, None, &catalog).unwrap().id, "tiny");
+
+        let listed = inventory(&models_dir, &catalog);
+        assert_eq!(listed.models.len(), 1);
+        assert_eq!((listed.models[0].size_bytes, listed.models[0].verification), (4100, Verification::Verified));
+
+        // Changing the file invalidates the cached verification
+        fs::write(models_dir.join("ggml-tiny.bin"), [GGML_MAGIC.as_slice(), &[8u8; 10]].concat()).unwrap();
+        assert_eq!(inventory(&models_dir, &catalog).models[0].verification, Verification::Unverified);
+        assert_eq!(verify(&models_dir, &catalog[0]).unwrap(), Verification::Corrupt);
+
+        assert!(delete(&models_dir, &catalog[0]).unwrap() > 0);
+        let listed = inventory(&models_dir, &catalog);
+        assert!(listed.models.is_empty());
+        assert_eq!(listed.total_bytes, 0);
+
+        let _ = fs::remove_dir_all(root);
+    }
+}
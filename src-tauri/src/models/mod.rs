@@ -0,0 +1,98 @@
+// Model registry - which files make up each model and which provider runs it
+
+pub mod catalog;
+pub mod download;
+pub mod manage;
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Provider a model needs at runtime
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ModelProvider {
+    /// ggml model run by the whisper.cpp CLI
+    Whisper,
+    /// ONNX export run in-process through ONNX Runtime (CPU)
+    Onnx,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelFile {
+    /// Path relative to the models directory
+    pub path: String,
+    pub url: String,
+    /// Expected size in bytes, when known; a file of any other size is incomplete
+    #[serde(default)]
+    pub size: Option<u64>,
+    /// Lowercase hex SHA-256, verified after download when known
+    #[serde(default)]
+    pub sha256: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelDescriptor {
+    pub id: String,
+    pub display_name: String,
+    pub provider: ModelProvider,
+    /// Base model this is a variant of, e.g. "large-v3-turbo"
+    pub family: String,
+    /// ISO 639-1 codes; empty for multilingual models
+    #[serde(default)]
+    pub languages: Vec<String>,
+    /// ggml quantization such as "q5_0" or "q8_0"; `None` for full precision
+    #[serde(default)]
+    pub quantization: Option<String>,
+    /// Approximate download size
+    pub size_mb: u64,
+    /// Approximate memory needed while transcribing
+    pub ram_mb: u64,
+    pub files: Vec<ModelFile>,
+}
+
+impl ModelDescriptor {
+    /// What the provider is pointed at: the ggml file for Whisper, the model folder for ONNX
+    pub fn path(&self, models_dir: &Path) -> PathBuf {
+        match self.provider {
+            ModelProvider::Whisper => models_dir.join(&self.files[0].path),
+            ModelProvider::Onnx => models_dir.join(&self.id),
+        }
+    }
+
+    pub fn is_installed(&self, models_dir: &Path) -> bool {
+        self.files.iter().all(|file| {
+            match (std::fs::metadata(models_dir.join(&file.path)), file.size) {
+                (Ok(meta), Some(size)) => meta.len() == size,
+                (Ok(_), None) => true,
+                (Err(_), _) => false,
+            }
+        })
+    }
+}
+
+pub fn models_dir() -> PathBuf {
+    crate::paths::models_dir()
+}
+
+/// A catalog model as shown in settings
+#[derive(Debug, Clone, Serialize)]
+pub struct CatalogEntry {
+    #[serde(flatten)]
+    pub model: ModelDescriptor,
+    pub installed: bool,
+}
+
+impl CatalogEntry {
+    pub fn list(models: Vec<ModelDescriptor>) -> Vec<CatalogEntry> {
+        let dir = models_dir();
+        models.into_iter().map(|model| CatalogEntry { installed: model.is_installed(&dir), model }).collect()
+    }
+}
+
+/// Every model the app can download, from the newest available catalog manifest
+pub fn catalog() -> Vec<ModelDescriptor> {
+    catalog::load().models
+}
+
+pub fn find(id: &str) -> Option<ModelDescriptor> {
+    catalog().into_iter().find(|model| model.id == id)
+}
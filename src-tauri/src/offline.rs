@@ -0,0 +1,408 @@
+// Offline queue - cloud dictations that failed for lack of network, retried later
+//
+// Each job is the recording as gzip-compressed 16kHz 16-bit PCM plus a JSON metadata
+// file in the data dir, so queued dictations survive restarts. A background worker
+// retries due jobs with exponential backoff and hands finished transcripts back to the
+// app for delivery (history, clipboard and a notification). Jobs that run out of attempts
+// or grow too old are given up on, and their recording is kept as a WAV file instead.
+
+use crate::errors::TranscriptionError;
+use crate::transcription::chain::SaveAudioProvider;
+use crate::transcription::{pcm16_le_bytes, resample_to_16k, write_wav_file, AudioBuffer, TranscriptionProvider};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::future::Future;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Queued audio is always stored at this rate
+const QUEUE_SAMPLE_RATE: u32 = 16000;
+
+/// How often the worker looks for due jobs when nothing is scheduled sooner
+const POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct OfflineJob {
+    pub id: String,
+    /// Provider to retry with, e.g. "Streaming"
+    pub provider: String,
+    pub created_at: String,
+    pub audio_secs: f32,
+    pub attempts: u32,
+    /// Unix time in milliseconds
+    pub next_attempt_ms: i64,
+    pub last_error: Option<String>,
+}
+
+/// What became of a retried job
+#[derive(Debug, Clone)]
+pub enum OfflineResult {
+    /// The queued dictation finally transcribed
+    Transcribed { job: OfflineJob, text: String, latency_ms: u64 },
+    /// Retries ran out; the recording was saved to `saved_to` when that succeeded
+    Expired { job: OfflineJob, saved_to: Option<PathBuf> },
+}
+
+/// Delay before retry `attempts + 1`: `base`, doubling each time, capped at `max`.
+/// A job is given up after `max_attempts` retries or once it is older than `max_age`.
+#[derive(Debug, Clone, Copy)]
+pub struct Backoff {
+    pub base: Duration,
+    pub max: Duration,
+    pub max_attempts: u32,
+    pub max_age: Duration,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self {
+            base: Duration::from_secs(30),
+            max: Duration::from_secs(3600),
+            max_attempts: 48,
+            max_age: Duration::from_secs(7 * 24 * 3600),
+        }
+    }
+}
+
+impl Backoff {
+    pub fn delay(&self, attempts: u32) -> Duration {
+        self.base.saturating_mul(2u32.saturating_pow(attempts.min(16))).min(self.max)
+    }
+
+    fn is_exhausted(&self, job: &OfflineJob, now_ms: i64) -> bool {
+        let age_ms = chrono::DateTime::parse_from_rfc3339(&job.created_at)
+            .map(|created| now_ms - created.timestamp_millis())
+            .unwrap_or(0);
+        job.attempts >= self.max_attempts || age_ms > self.max_age.as_millis() as i64
+    }
+}
+
+pub struct OfflineQueue {
+    dir: PathBuf,
+    backoff: Backoff,
+    /// Where recordings of given-up jobs are kept
+    saved_audio_dir: PathBuf,
+}
+
+impl Default for OfflineQueue {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OfflineQueue {
+    pub fn new() -> Self {
+        Self {
+            saved_audio_dir: SaveAudioProvider::default_dir(),
+            ..Self::with_dir(crate::paths::storage().data_dir.join("offline_queue"), Backoff::default())
+        }
+    }
+
+    pub fn with_dir(dir: PathBuf, backoff: Backoff) -> Self {
+        if !dir.exists() {
+            let _ = fs::create_dir_all(&dir);
+        }
+        Self { saved_audio_dir: dir.join("expired"), dir, backoff }
+    }
+
+    fn audio_path(&self, id: &str) -> PathBuf {
+        self.dir.join(format!("{}.pcm.gz", id))
+    }
+
+    fn meta_path(&self, id: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", id))
+    }
+
+    /// Stores a dictation for `provider`; the first retry is one backoff step away
+    pub fn enqueue(&self, provider: &str, audio: AudioBuffer, sample_rate: u32) -> Result<OfflineJob, Box<dyn std::error::Error + Send + Sync>> {
+        let audio_secs = audio.len() as f32 / sample_rate as f32;
+        let audio = resample_to_16k(audio, sample_rate);
+
+        let job = OfflineJob {
+            id: uuid::Uuid::new_v4().to_string(),
+            provider: provider.to_string(),
+            created_at: chrono::Local::now().to_rfc3339(),
+            audio_secs,
+            attempts: 0,
+            next_attempt_ms: now_ms() + self.backoff.delay(0).as_millis() as i64,
+            last_error: None,
+        };
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&pcm16_le_bytes(&audio))?;
+        fs::write(self.audio_path(&job.id), encoder.finish()?)?;
+        // Metadata last: a job without it is never picked up
+        self.save(&job)?;
+
+        log::info!("Queued {:.1}s dictation {} for {} while offline", audio_secs, job.id, provider);
+        Ok(job)
+    }
+
+    /// Queues a dictation whose transcription failed for lack of network; other failures aren't retried
+    pub fn enqueue_if_offline(&self, err: &TranscriptionError, provider: &str, audio: AudioBuffer, sample_rate: u32) -> Option<OfflineJob> {
+        if !err.is_network() {
+            return None;
+        }
+        match self.enqueue(provider, audio, sample_rate) {
+            Ok(job) => Some(job),
+            Err(e) => {
+                log::error!("Failed to queue dictation offline: {}", e);
+                None
+            }
+        }
+    }
+
+    fn save(&self, job: &OfflineJob) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        fs::write(self.meta_path(&job.id), serde_json::to_string_pretty(job)?)?;
+        Ok(())
+    }
+
+    fn load_audio(&self, job: &OfflineJob) -> Result<AudioBuffer, Box<dyn std::error::Error + Send + Sync>> {
+        let mut bytes = Vec::new();
+        GzDecoder::new(fs::File::open(self.audio_path(&job.id))?).read_to_end(&mut bytes)?;
+        Ok(bytes.chunks_exact(2).map(|b| i16::from_le_bytes([b[0], b[1]]) as f32 / 32768.0).collect())
+    }
+
+    pub fn remove(&self, id: &str) {
+        let _ = fs::remove_file(self.meta_path(id));
+        let _ = fs::remove_file(self.audio_path(id));
+    }
+
+    /// Gives up on `job`, keeping its recording as a WAV file where possible
+    fn expire(&self, job: OfflineJob) -> OfflineResult {
+        let path = self.saved_audio_dir.join(format!("offline-{}.wav", job.id));
+        let saved = fs::create_dir_all(&self.saved_audio_dir)
+            .map_err(|e| e.into())
+            .and_then(|_| self.load_audio(&job))
+            .and_then(|audio| write_wav_file(&audio, QUEUE_SAMPLE_RATE, &path));
+        let saved_to = match saved {
+            Ok(()) => Some(path),
+            Err(e) => {
+                log::error!("Could not save the recording of offline job {}: {}", job.id, e);
+                None
+            }
+        };
+        log::warn!("Giving up on offline job {} after {} attempts (last error: {:?})", job.id, job.attempts, job.last_error);
+        self.remove(&job.id);
+        OfflineResult::Expired { job, saved_to }
+    }
+
+    /// Oldest first
+    pub fn pending(&self) -> Vec<OfflineJob> {
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+        let mut jobs: Vec<OfflineJob> = entries
+            .filter_map(Result::ok)
+            .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|entry| fs::read_to_string(entry.path()).ok())
+            .filter_map(|json| serde_json::from_str(&json).ok())
+            .collect();
+        jobs.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+        jobs
+    }
+
+    /// Retries every job due at `now_ms` with the provider `provider_for` returns for it.
+    /// Successful and given-up jobs are removed from disk; other failures are rescheduled.
+    pub async fn retry_due<F>(&self, now_ms: i64, timeout: Duration, provider_for: F) -> Vec<OfflineResult>
+    where
+        F: Fn(&str) -> Option<Arc<dyn TranscriptionProvider>>,
+    {
+        let mut results = Vec::new();
+        for mut job in self.pending().into_iter().filter(|job| job.next_attempt_ms <= now_ms) {
+            if self.backoff.is_exhausted(&job, now_ms) {
+                results.push(self.expire(job));
+                continue;
+            }
+            let outcome = match (provider_for(&job.provider), self.load_audio(&job)) {
+                (None, _) => Err(format!("provider '{}' is no longer available", job.provider)),
+                (_, Err(e)) => {
+                    log::error!("Dropping offline job {}: audio unreadable ({})", job.id, e);
+                    self.remove(&job.id);
+                    continue;
+                }
+                (Some(provider), Ok(audio)) => {
+                    let start = Instant::now();
+                    match tokio::time::timeout(timeout, provider.transcribe(audio, QUEUE_SAMPLE_RATE)).await {
+                        Ok(Ok(text)) if !text.trim().is_empty() => Ok((text, start.elapsed())),
+                        Ok(Ok(_)) => Err("empty transcript".to_string()),
+                        Ok(Err(e)) => Err(e.to_string()),
+                        Err(_) => Err(format!("timed out after {:?}", timeout)),
+                    }
+                }
+            };
+
+            match outcome {
+                Ok((text, latency)) => {
+                    log::info!("Offline job {} transcribed after {} retries", job.id, job.attempts + 1);
+                    self.remove(&job.id);
+                    results.push(OfflineResult::Transcribed { job, text, latency_ms: latency.as_millis() as u64 });
+                }
+                Err(e) => {
+                    let delay = self.backoff.delay(job.attempts + 1);
+                    job.attempts += 1;
+                    job.next_attempt_ms = now_ms + delay.as_millis() as i64;
+                    job.last_error = Some(e.clone());
+                    if self.backoff.is_exhausted(&job, now_ms) {
+                        results.push(self.expire(job));
+                        continue;
+                    }
+                    log::warn!("Offline job {} retry failed: {}; next attempt in {:?}", job.id, e, delay);
+                    if let Err(e) = self.save(&job) {
+                        log::error!("Failed to reschedule offline job {}: {}", job.id, e);
+                    }
+                }
+            }
+        }
+        results
+    }
+
+    /// Retries due jobs forever, sleeping until the next one is scheduled
+    pub async fn run<F, D, Fut>(self: Arc<Self>, timeout: Duration, provider_for: F, deliver: D)
+    where
+        F: Fn(&str) -> Option<Arc<dyn TranscriptionProvider>>,
+        D: Fn(OfflineResult) -> Fut,
+        Fut: Future<Output = ()>,
+    {
+        log::info!("Offline queue worker started with {} pending jobs", self.pending().len());
+        loop {
+            for result in self.retry_due(now_ms(), timeout, &provider_for).await {
+                deliver(result).await;
+            }
+            let wait = self.pending().iter()
+                .map(|job| Duration::from_millis((job.next_attempt_ms - now_ms()).max(0) as u64))
+                .min()
+                .map_or(POLL_INTERVAL, |next| next.min(POLL_INTERVAL));
+            tokio::time::sleep(wait.max(Duration::from_secs(1))).await;
+        }
+    }
+}
+
+/// Providers that need the network, whose failures are worth queueing
+pub fn is_cloud_provider(name: &str) -> bool {
+    matches!(name, "Cloud" | "Streaming")
+}
+
+fn now_ms() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transcription::chain::{ChainStep, ProviderChain};
+    use crate::transcription::StreamingCloudProvider;
+    use futures_util::{SinkExt, StreamExt};
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::tungstenite::Message;
+
+    /// Local stand-in for the streaming cloud service: answers "stop" with a final transcript
+    async fn spawn_stand_in_server() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (tcp, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(tcp).await.unwrap();
+            let mut samples = 0usize;
+            while let Some(Ok(msg)) = ws.next().await {
+                match msg {
+                    Message::Binary(data) => samples += data.len() / 2,
+                    Message::Text(text) if text.contains("stop") => {
+                        ws.send(Message::Text(format!(r#"{{"type":"final","text":"{} samples"}}"#, samples))).await.unwrap();
+                        ws.send(Message::Text(r#"{"type":"done"}"#.into())).await.unwrap();
+                        break;
+                    }
+                    _ => {}
+                }
+            }
+        });
+        format!("ws://{}", addr)
+    }
+
+    #[tokio::test]
+    async fn test_retries_with_backoff_until_server_is_back() {
+        let dir = std::env::temp_dir().join(format!("vanta_offline_{}", uuid::Uuid::new_v4()));
+        let backoff = Backoff { base: Duration::from_secs(10), max: Duration::from_secs(60), ..Backoff::default() };
+        let queue = OfflineQueue::with_dir(dir.clone(), backoff);
+
+        // 0.5s at 48kHz is stored as 8000 samples at 16kHz
+        let job = queue.enqueue("Streaming", vec![0.1; 24000], 48000).unwrap();
+        assert_eq!(queue.pending(), vec![job.clone()]);
+        assert!(queue.retry_due(now_ms(), Duration::from_secs(5), |_| unreachable!()).await.is_empty());
+
+        // Network down: nothing listening on the endpoint
+        let dead_port = TcpListener::bind("127.0.0.1:0").await.unwrap().local_addr().unwrap().port();
+        let offline: Arc<dyn TranscriptionProvider> = Arc::new(StreamingCloudProvider::new(format!("ws://127.0.0.1:{}", dead_port), "en".into()));
+        let due = job.next_attempt_ms;
+        assert!(queue.retry_due(due, Duration::from_secs(5), |_| Some(offline.clone())).await.is_empty());
+        let rescheduled = &queue.pending()[0];
+        assert_eq!(rescheduled.attempts, 1);
+        assert_eq!(rescheduled.next_attempt_ms, due + 20_000);
+        assert!(rescheduled.last_error.is_some());
+
+        // Back online
+        let online: Arc<dyn TranscriptionProvider> = Arc::new(StreamingCloudProvider::new(spawn_stand_in_server().await, "en".into()));
+        let results = queue.retry_due(due + 20_000, Duration::from_secs(5), |_| Some(online.clone())).await;
+        assert_eq!(results.len(), 1);
+        assert!(matches!(&results[0], OfflineResult::Transcribed { text, .. } if text == "8000 samples"));
+        assert!(queue.pending().is_empty());
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_streaming_connection_failure_is_queued() {
+        let dir = std::env::temp_dir().join(format!("vanta_offline_{}", uuid::Uuid::new_v4()));
+        let queue = OfflineQueue::with_dir(dir.clone(), Backoff::default());
+
+        // Streaming primary with no fallback, as in the default config
+        let dead_port = TcpListener::bind("127.0.0.1:0").await.unwrap().local_addr().unwrap().port();
+        let provider: Arc<dyn TranscriptionProvider> = Arc::new(StreamingCloudProvider::new(format!("ws://127.0.0.1:{}", dead_port), "en".into()));
+        let chain = ProviderChain::new(vec![ChainStep { provider: provider.clone(), timeout: Duration::from_secs(5) }]);
+        let session = tokio::spawn(async move { provider.transcribe(vec![0.1; 1600], 16000).await });
+
+        let err = TranscriptionError::from_boxed(chain.finish_streamed(session, vec![0.1; 1600], 16000).await.unwrap_err());
+        assert!(matches!(err, TranscriptionError::Connection { .. }), "{:?}", err);
+        let job = queue.enqueue_if_offline(&err, "Streaming", vec![0.1; 1600], 16000).unwrap();
+        assert_eq!(queue.pending(), vec![job]);
+
+        assert!(queue.enqueue_if_offline(&TranscriptionError::NoProvider, "Streaming", vec![0.1; 1600], 16000).is_none());
+        assert_eq!(queue.pending().len(), 1);
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_and_keeps_the_recording() {
+        let dir = std::env::temp_dir().join(format!("vanta_offline_{}", uuid::Uuid::new_v4()));
+        let backoff = Backoff { max_attempts: 2, ..Backoff::default() };
+        let queue = OfflineQueue::with_dir(dir.clone(), backoff);
+        let job = queue.enqueue("Removed", vec![0.1; 1600], 16000).unwrap();
+
+        // The provider is gone: first failure reschedules, the second gives up
+        let first = job.next_attempt_ms;
+        assert!(queue.retry_due(first, Duration::from_secs(1), |_| None).await.is_empty());
+        let second = queue.pending()[0].next_attempt_ms;
+        let results = queue.retry_due(second, Duration::from_secs(1), |_| None).await;
+
+        let [OfflineResult::Expired { job: expired, saved_to: Some(wav) }] = results.as_slice() else {
+            panic!("expected one expired job, got {:?}", results);
+        };
+        assert_eq!(expired.attempts, 2);
+        assert!(expired.last_error.as_deref().unwrap().contains("no longer available"));
+        assert!(wav.exists());
+        assert!(queue.pending().is_empty());
+
+        // Too old counts as exhausted even without failed attempts
+        let stale = OfflineJob { created_at: "2020-01-01T00:00:00+00:00".into(), attempts: 0, ..job };
+        assert!(Backoff::default().is_exhausted(&stale, now_ms()));
+
+        let _ = fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,116 @@
+// Storage paths - where config, models, logs and app data live
+//
+// Installed copies use the per-user config and data folders. A file named `portable`
+// next to the executable switches to portable mode, where everything lives in a `data`
+// folder beside it so the app can run from a USB stick. Models can additionally be kept
+// in a custom folder (e.g. on a larger drive) via `custom_models_dir` in config.
+
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::{OnceLock, RwLock};
+
+const APP_DIR: &str = "VantaDictate";
+/// Presence of this file next to the executable enables portable mode
+pub const PORTABLE_MARKER: &str = "portable";
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StoragePaths {
+    pub portable: bool,
+    pub config_dir: PathBuf,
+    /// History, evaluations, caches, the offline queue and saved audio
+    pub data_dir: PathBuf,
+    pub log_dir: PathBuf,
+    /// Default models folder; `models_dir()` applies the custom override
+    pub default_models_dir: PathBuf,
+}
+
+impl StoragePaths {
+    fn layout(exe_dir: Option<&Path>, portable: bool) -> Self {
+        match exe_dir {
+            Some(exe_dir) if portable => {
+                let root = exe_dir.join("data");
+                Self {
+                    portable: true,
+                    config_dir: root.clone(),
+                    data_dir: root.clone(),
+                    log_dir: root.join("logs"),
+                    default_models_dir: root.join("models"),
+                }
+            }
+            _ => {
+                let data_dir = dirs::data_dir().unwrap_or_else(|| PathBuf::from(".")).join(APP_DIR);
+                Self {
+                    portable: false,
+                    config_dir: dirs::config_dir().unwrap_or_else(|| PathBuf::from(".")).join(APP_DIR),
+                    log_dir: data_dir.join("logs"),
+                    default_models_dir: data_dir.join("models"),
+                    data_dir,
+                }
+            }
+        }
+    }
+
+    fn detect() -> Self {
+        let exe_dir = std::env::current_exe().ok().and_then(|exe| exe.parent().map(Path::to_path_buf));
+        let portable = exe_dir.as_ref().is_some_and(|dir| dir.join(PORTABLE_MARKER).exists());
+        Self::layout(exe_dir.as_deref(), portable)
+    }
+}
+
+/// Resolved once per process; moving the marker takes effect on restart
+pub fn storage() -> &'static StoragePaths {
+    static PATHS: OnceLock<StoragePaths> = OnceLock::new();
+    PATHS.get_or_init(StoragePaths::detect)
+}
+
+/// `name` in the data dir, creating the data dir if needed
+pub fn data_file(name: &str) -> PathBuf {
+    let dir = &storage().data_dir;
+    if !dir.exists() {
+        let _ = std::fs::create_dir_all(dir);
+    }
+    dir.join(name)
+}
+
+pub fn log_file() -> PathBuf {
+    storage().log_dir.join("vanta.log")
+}
+
+static CUSTOM_MODELS_DIR: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+/// Where models are downloaded to and loaded from
+pub fn models_dir() -> PathBuf {
+    let custom = CUSTOM_MODELS_DIR.read().unwrap_or_else(|e| e.into_inner()).clone();
+    custom.unwrap_or_else(|| storage().default_models_dir.clone())
+}
+
+/// Applies `custom_models_dir` from config; empty restores the default. Installed models
+/// are not moved, so switching folders shows whatever the new one contains.
+pub fn set_models_dir(custom: &str) {
+    let custom = custom.trim();
+    let dir = (!custom.is_empty()).then(|| PathBuf::from(custom));
+    let mut current = CUSTOM_MODELS_DIR.write().unwrap_or_else(|e| e.into_inner());
+    if *current != dir {
+        log::info!("Models folder: {}", dir.as_ref().unwrap_or(&storage().default_models_dir).display());
+        *current = dir;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_portable_layout_stays_next_to_the_executable() {
+        let exe_dir = Path::new("/opt/vanta");
+        let portable = StoragePaths::layout(Some(exe_dir), true);
+        for dir in [&portable.config_dir, &portable.data_dir, &portable.log_dir, &portable.default_models_dir] {
+            assert!(dir.starts_with(exe_dir.join("data")), "{}", dir.display());
+        }
+
+        let installed = StoragePaths::layout(Some(exe_dir), false);
+        assert!(!installed.portable);
+        assert!(installed.default_models_dir.ends_with(Path::new(APP_DIR).join("models")));
+        assert!(!installed.data_dir.starts_with(exe_dir));
+    }
+}
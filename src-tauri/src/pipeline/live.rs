@@ -0,0 +1,136 @@
+// Live config - rebuilds the worker's providers and settings from config while running
+//
+// Everything the worker reads from config is built into one `Components` value. The
+// worker takes a snapshot at the start of each job, so a swap never changes providers in
+// the middle of a dictation: the job in flight (and its background refinement) finishes
+// with the components it started with, and the next job picks up the new ones. The
+// hotkey is registered once at startup, so changing it is reported as needing a restart.
+
+use super::{DictationJob, JobQueue, Pipeline};
+use crate::config::AppConfig;
+use crate::injection::FocusTarget;
+use crate::transcription::{DictationContext, Router, Shadow, TwoPass};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+/// Settings that are read live elsewhere; changing only these keeps the providers loaded
+const NON_PROVIDER_SETTINGS: [&str; 7] = [
+    "autostart",
+    "focus_delay_ms",
+    "enable_typing_fallback",
+    "queue_policy",
+    "max_queued_jobs",
+    "model_catalog_url",
+    "latency_target_ms",
+];
+
+/// Settings read once at startup; changing them needs a restart
+const RESTART_SETTINGS: [&str; 1] = ["hotkey"];
+
+/// Providers and per-dictation settings, rebuilt as a unit when the config changes
+pub struct Components {
+    pub router: Arc<Router>,
+    pub two_pass: Option<TwoPass>,
+    pub shadow: Option<Arc<Shadow>>,
+    /// Recent transcripts per target window, `None` when context is off
+    pub context: Option<Mutex<DictationContext<FocusTarget>>>,
+    /// Queue cloud dictations that fail for lack of network
+    pub offline_queue_enabled: bool,
+    pub injection_test_mode: bool,
+}
+
+impl Components {
+    pub fn from_config(config: &AppConfig) -> Self {
+        Self {
+            router: Arc::new(Router::from_config(config)),
+            two_pass: TwoPass::from_config(config),
+            shadow: Shadow::from_config(config).map(Arc::new),
+            context: DictationContext::from_config(config).map(Mutex::new),
+            offline_queue_enabled: config.offline_queue_enabled,
+            injection_test_mode: config.injection_test_mode,
+        }
+    }
+
+    /// Previous dictations into `target`, if context is enabled and still fresh
+    pub fn prompt_for(&self, target: FocusTarget) -> Option<String> {
+        let mut context = self.context.as_ref()?.lock().ok()?;
+        context.prompt_for(target, Instant::now())
+    }
+
+    pub fn remember(&self, target: FocusTarget, text: &str) {
+        if let Some(Ok(mut context)) = self.context.as_ref().map(|context| context.lock()) {
+            context.record(target, text, Instant::now());
+        }
+    }
+}
+
+fn provider_settings(config: &AppConfig) -> serde_json::Value {
+    let mut value = serde_json::to_value(config).unwrap_or_default();
+    if let Some(fields) = value.as_object_mut() {
+        for key in NON_PROVIDER_SETTINGS.iter().chain(&RESTART_SETTINGS) {
+            fields.remove(*key);
+        }
+    }
+    value
+}
+
+/// Settings in `RESTART_SETTINGS` that differ between the two configs
+fn restart_settings_changed(started: &AppConfig, config: &AppConfig) -> Vec<&'static str> {
+    let (started, config) = (serde_json::to_value(started).unwrap_or_default(), serde_json::to_value(config).unwrap_or_default());
+    RESTART_SETTINGS.into_iter().filter(|key| started.get(key) != config.get(key)).collect()
+}
+
+/// What applying a config changed
+#[derive(Default)]
+pub struct Applied {
+    /// The new router when providers were rebuilt, so the caller can health-check and warm them up
+    pub router: Option<Arc<Router>>,
+    /// Settings that differ from what the app started with and only apply after a restart
+    pub restart_required: Vec<&'static str>,
+}
+
+/// Applies saved or edited settings to the running pipeline
+pub struct LiveConfig {
+    pipeline: Arc<Pipeline>,
+    job_queue: Arc<JobQueue<DictationJob>>,
+    started: AppConfig,
+    applied: Mutex<AppConfig>,
+}
+
+impl LiveConfig {
+    /// `config` is what the pipeline was built from
+    pub fn new(pipeline: Arc<Pipeline>, job_queue: Arc<JobQueue<DictationJob>>, config: AppConfig) -> Self {
+        Self { pipeline, job_queue, started: config.clone(), applied: Mutex::new(config) }
+    }
+
+    /// Applies `config` if it differs from what is running
+    pub fn apply(&self, config: AppConfig) -> Applied {
+        let mut applied = self.applied.lock().unwrap_or_else(|e| e.into_inner());
+        if serde_json::to_value(&*applied).ok() == serde_json::to_value(&config).ok() {
+            log::debug!("Config unchanged, nothing to apply");
+            return Applied::default();
+        }
+
+        let injector = self.pipeline.injector();
+        injector.set_focus_delay(config.focus_delay_ms);
+        injector.set_fallback_enabled(config.enable_typing_fallback);
+        self.job_queue.set_policy(config.queue_policy, config.max_queued_jobs);
+
+        let rebuilt = if provider_settings(&applied) != provider_settings(&config) {
+            let components = Components::from_config(&config);
+            let router = components.router.clone();
+            self.pipeline.reconfigure(components);
+            Some(router)
+        } else {
+            None
+        };
+        log::info!("Applied new config (providers {})", if rebuilt.is_some() { "rebuilt" } else { "unchanged" });
+
+        let restart_required = restart_settings_changed(&self.started, &config);
+        if !restart_required.is_empty() {
+            log::info!("Restart needed to apply: {}", restart_required.join(", "));
+        }
+        *applied = config;
+        Applied { router: rebuilt, restart_required }
+    }
+}
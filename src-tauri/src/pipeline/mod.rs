@@ -0,0 +1,426 @@
+// Pipeline module - Processing worker: transcribe, format, inject and record queued dictations
+
+pub mod live;
+pub mod queue;
+
+pub use live::{Components, LiveConfig};
+pub use queue::{BackpressurePolicy, JobQueue, PushOutcome, QueuedAudio};
+
+use crate::errors::{FormattingError, TranscriptionError, UserFacingError};
+use crate::formatting::{FormattingEngine, TranscriptionMode};
+use crate::history::{HistoryEntry, HistoryStore};
+use crate::hotkey::CancelFlag;
+use crate::injection::{FocusTarget, ReplaceOutcome, TextInjector};
+use crate::offline::{self, OfflineQueue, OfflineResult};
+use crate::transcription::{context, progress};
+use crate::transcription::two_pass::Refinement;
+use crate::transcription::{AudioBuffer, ChainOutcome, Router};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use tauri::{Emitter, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+/// How long the HUD stays up once the worker is idle; long enough to read an error.
+/// The HUD renders nothing when there is nothing to show.
+const HUD_LINGER: Duration = Duration::from_secs(5);
+
+/// Streaming transcription started on press and awaited by the worker
+pub type StreamingJob = tokio::task::JoinHandle<Result<String, Box<dyn std::error::Error + Send + Sync>>>;
+
+/// One finished recording waiting to be processed
+pub struct DictationJob {
+    pub audio: AudioBuffer,
+    pub sample_rate: u32,
+    pub streaming_job: Option<StreamingJob>,
+    pub target: FocusTarget,
+    pub released_at: Instant,
+    pub stop_duration: Duration,
+}
+
+impl QueuedAudio for DictationJob {
+    fn samples_mut(&mut self) -> &mut Vec<f32> {
+        &mut self.audio
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Text for different windows has to be injected separately
+    fn can_merge_with(&self, other: &Self) -> bool {
+        self.target == other.target
+    }
+
+    fn on_merged(&mut self) {
+        // The stream only saw the first recording; transcribe the merged buffer instead
+        if let Some(job) = self.streaming_job.take() {
+            job.abort();
+        }
+    }
+}
+
+/// Shows a failure on the HUD with its code and remediation hint
+pub fn emit_error<E: UserFacingError>(app: &tauri::AppHandle, err: &E) {
+    let payload = err.payload();
+    log::error!("[{}] {}", payload.code, payload.message);
+    if let Some(hud) = app.get_webview_window("hud") {
+        let _ = hud.emit("pipeline-error", payload);
+    }
+}
+
+/// Tells the HUD how many dictations are waiting or being processed
+pub fn emit_queue_depth(app: &tauri::AppHandle, depth: usize) {
+    if let Some(hud) = app.get_webview_window("hud") {
+        let _ = hud.emit("queue-depth", depth);
+    }
+}
+
+pub struct Pipeline {
+    app: tauri::AppHandle,
+    /// Swapped by `reconfigure`; each job keeps the snapshot it started with
+    components: RwLock<Arc<Components>>,
+    /// Where cloud dictations go when the network is down
+    offline_queue: Arc<OfflineQueue>,
+    formatting_engine: FormattingEngine,
+    injector: Arc<TextInjector>,
+    history_store: Arc<HistoryStore>,
+    cancel_flag: CancelFlag,
+    /// The capture loop owns the HUD while recording; the worker only hides it when idle
+    recording: AtomicBool,
+}
+
+impl Pipeline {
+    pub fn new(
+        app: tauri::AppHandle,
+        components: Components,
+        offline_queue: Arc<OfflineQueue>,
+        formatting_engine: FormattingEngine,
+        injector: Arc<TextInjector>,
+        history_store: Arc<HistoryStore>,
+        cancel_flag: CancelFlag,
+    ) -> Self {
+        let components = RwLock::new(Arc::new(components));
+        Self { app, components, offline_queue, formatting_engine, injector, history_store, cancel_flag, recording: AtomicBool::new(false) }
+    }
+
+    /// What the next dictation will run with
+    pub fn components(&self) -> Arc<Components> {
+        self.components.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Takes effect from the next job; the one in flight keeps its providers
+    pub fn reconfigure(&self, components: Components) {
+        *self.components.write().unwrap_or_else(|e| e.into_inner()) = Arc::new(components);
+    }
+
+    pub fn injector(&self) -> &TextInjector {
+        &self.injector
+    }
+
+    pub fn set_recording(&self, recording: bool) {
+        self.recording.store(recording, Ordering::SeqCst);
+    }
+
+    /// Processes jobs one at a time, in recording order, forever
+    pub async fn run(self: Arc<Self>, queue: Arc<JobQueue<DictationJob>>) {
+        log::info!("Processing worker started");
+        loop {
+            let job = queue.next().await;
+            self.cancel_flag.reset();
+            emit_queue_depth(&self.app, queue.depth());
+
+            let released_at = job.released_at;
+            let components = self.components();
+            let prompt = components.prompt_for(job.target);
+            let (progress_tx, progress_rx) = tokio::sync::mpsc::unbounded_channel();
+            let forwarder = tokio::spawn(forward_progress(self.app.clone(), progress_rx));
+            // Escape / cancel hotkey drops the job, which kills any
+            // transcription child process and removes its temp files
+            tokio::select! {
+                _ = context::with_prompt(prompt, progress::with_progress(progress_tx, self.process(&components, job))) => {}
+                _ = self.cancel_flag.cancelled() => {
+                    log::info!("Transcription cancelled after {:?}", released_at.elapsed());
+                    self.emit_hud("transcription-cancelled", ());
+                }
+            }
+            let _ = forwarder.await;
+
+            let remaining = queue.finish();
+            emit_queue_depth(&self.app, remaining);
+            if remaining == 0 {
+                self.clone().hide_hud_when_idle(queue.clone());
+            }
+        }
+    }
+
+    fn hide_hud_when_idle(self: Arc<Self>, queue: Arc<JobQueue<DictationJob>>) {
+        tokio::spawn(async move {
+            tokio::time::sleep(HUD_LINGER).await;
+            if queue.depth() == 0 && !self.recording.load(Ordering::SeqCst) {
+                if let Some(hud) = self.app.get_webview_window("hud") {
+                    let _ = hud.hide();
+                }
+            }
+        });
+    }
+
+    fn emit_hud<S: serde::Serialize + Clone>(&self, event: &str, payload: S) {
+        if let Some(hud) = self.app.get_webview_window("hud") {
+            let _ = hud.emit(event, payload);
+        }
+    }
+
+    async fn process(&self, components: &Components, job: DictationJob) {
+        let DictationJob { audio: audio_data, sample_rate, streaming_job, target, released_at: release_time, stop_duration } = job;
+        let duration_secs = audio_data.len() as f32 / sample_rate as f32;
+        log::info!("Processing dictation: {} samples ({:.2}s), waited {:?} in queue",
+            audio_data.len(), duration_secs, release_time.elapsed());
+
+        // Two-pass: inject a fast draft now, refine in the background
+        let draft_pass = components.two_pass.as_ref().filter(|two_pass| {
+            streaming_job.is_none() && !components.injection_test_mode && two_pass.applies_to(duration_secs)
+        });
+        if let Some(two_pass) = draft_pass {
+            match two_pass.draft(audio_data.clone(), sample_rate).await {
+                Ok(draft) if !draft.is_empty() => {
+                    let draft = self.formatting_engine.format(&draft);
+                    if let Err(e) = self.injector.inject_into(target, &draft) {
+                        log::error!("Draft injection failed: {}", e);
+                        emit_error(&self.app, &e);
+                        return;
+                    }
+                    log::info!("⏱️  DRAFT INJECTED: {:?} after release", release_time.elapsed());
+                    components.remember(target, &draft);
+                    tokio::spawn(refine_in_background(
+                        self.app.clone(),
+                        components.router.clone(),
+                        self.injector.clone(),
+                        self.history_store.clone(),
+                        target,
+                        draft,
+                        context::current_prompt(),
+                        audio_data,
+                        sample_rate,
+                    ));
+                    return;
+                }
+                Ok(_) => log::warn!("Draft transcription was empty, transcribing normally"),
+                Err(e) => log::warn!("Draft transcription failed: {}, transcribing normally", e),
+            }
+        }
+
+        // Shadow mode needs its own copy; it runs after the primary and never affects injection
+        let shadow_audio = components.shadow.as_ref()
+            .filter(|_| !components.injection_test_mode)
+            .map(|_| audio_data.clone());
+
+        // Streaming only runs without routing rules, so its route is the configured chain
+        let route = if streaming_job.is_some() || components.injection_test_mode {
+            components.router.default_route()
+        } else {
+            components.router.route(audio_data.len() as f32 / sample_rate as f32).await
+        };
+
+        // Cloud dictations that fail for lack of network are kept and retried later,
+        // judged by the provider this dictation is actually routed to
+        let offline_audio = route.chain().primary()
+            .filter(|_| components.offline_queue_enabled && !components.injection_test_mode)
+            .map(|step| step.provider.name())
+            .filter(|name| offline::is_cloud_provider(name))
+            .map(|name| (name, audio_data.clone()));
+
+        // PHASE 1: Injection Test Mode
+        let transcribe_start = Instant::now();
+        let outcome = if components.injection_test_mode {
+            log::info!("INJECTION TEST MODE: Skipping transcription");
+            ChainOutcome { text: "Test transcription successful".to_string(), provider: "InjectionTest".to_string(), step: 0 }
+        } else {
+            log::info!("Starting transcription...");
+            let result = match streaming_job {
+                // Frames were already streamed; stopping the recorder closed the stream
+                Some(job) => route.chain().finish_streamed(job, audio_data, sample_rate).await,
+                None => route.transcribe_with_source(audio_data, sample_rate).await,
+            };
+            match result {
+                Ok(outcome) => {
+                    let transcribe_duration = transcribe_start.elapsed();
+                    if outcome.text.is_empty() {
+                        log::error!("Transcription returned empty text [took: {:?}]", transcribe_duration);
+                        emit_error(&self.app, &TranscriptionError::EmptyResult { provider: outcome.provider });
+                        return;
+                    }
+                    log::info!("Transcription success via {}: '{}' (len: {}) [took: {:?}]",
+                        outcome.provider, outcome.text, outcome.text.len(), transcribe_duration);
+                    outcome
+                }
+                Err(e) => {
+                    let transcribe_duration = transcribe_start.elapsed();
+                    log::error!("Transcription failed: {} [took: {:?}]", e, transcribe_duration);
+                    let err = TranscriptionError::from_boxed(e);
+                    if let Some((provider, audio)) = offline_audio {
+                        if self.offline_queue.enqueue_if_offline(&err, provider, audio, sample_rate).is_some() {
+                            let pending = self.offline_queue.pending().len();
+                            emit_error(&self.app, &TranscriptionError::QueuedOffline { provider: provider.to_string(), pending });
+                            return;
+                        }
+                    }
+                    emit_error(&self.app, &err);
+                    return;
+                }
+            }
+        };
+
+        let transcribe_duration = transcribe_start.elapsed();
+        if let (Some(shadow), Some(audio)) = (&components.shadow, shadow_audio) {
+            shadow.compare_in_background(&outcome, transcribe_duration, audio, sample_rate);
+        }
+
+        let format_start = Instant::now();
+        let formatted = self.formatting_engine.format(&outcome.text);
+        let format_duration = format_start.elapsed();
+        log::debug!("Formatted text for injection: '{}' [took: {:?}]", formatted, format_duration);
+        if formatted.trim().is_empty() {
+            emit_error(&self.app, &FormattingError::EmptyOutput);
+            return;
+        }
+
+        // inject_into() restores focus to the job's window and has fallback
+        let inject_start = Instant::now();
+        if let Err(e) = self.injector.inject_into(target, &formatted) {
+            log::error!("Injection pipeline failed: {}", e);
+            emit_error(&self.app, &e);
+        } else {
+            components.remember(target, &formatted);
+            let inject_duration = inject_start.elapsed();
+            let total_duration = release_time.elapsed();
+            log::info!("Injection pipeline completed successfully! [inject took: {:?}]", inject_duration);
+            log::info!("⏱️  TOTAL PIPELINE: {:?} (stop: {:?}, transcribe: {:?}, format: {:?}, inject: {:?})",
+                total_duration, stop_duration, transcribe_duration, format_duration, inject_duration);
+        }
+
+        // Record in history, including which provider produced the text
+        if !components.injection_test_mode {
+            let entry = HistoryEntry::new(formatted, outcome.provider, duration_secs, transcribe_duration.as_millis() as u64);
+            if let Err(e) = self.history_store.append(&entry) {
+                log::warn!("Failed to write history entry: {}", e);
+            }
+        }
+    }
+}
+
+/// Hands a dictation that transcribed after coming back online to the user: it is recorded
+/// in history and copied to the clipboard, since the window it was meant for has moved on
+pub async fn deliver_offline_result(app: tauri::AppHandle, injector: Arc<TextInjector>, history_store: Arc<HistoryStore>, result: OfflineResult) {
+    let (job, text, latency_ms) = match result {
+        OfflineResult::Transcribed { job, text, latency_ms } => (job, text, latency_ms),
+        OfflineResult::Expired { job, saved_to } => {
+            let body = match &saved_to {
+                Some(path) => format!("Recording from {} saved to {}", job.created_at, path.display()),
+                None => format!("Recording from {} could not be saved", job.created_at),
+            };
+            if let Err(e) = app.notification().builder().title("Offline dictation could not be transcribed").body(body).show() {
+                log::warn!("Failed to show notification: {}", e);
+            }
+            let _ = app.emit("offline-dictation-expired", job);
+            return;
+        }
+    };
+    let formatted = FormattingEngine::new(TranscriptionMode::Formatted).format(&text);
+    log::info!("Delivering offline dictation {} from {}: '{}'", job.id, job.created_at, formatted);
+
+    let entry = HistoryEntry::new(formatted.clone(), job.provider, job.audio_secs, latency_ms);
+    if let Err(e) = history_store.append(&entry) {
+        log::warn!("Failed to write history entry: {}", e);
+    }
+
+    let copied = {
+        let formatted = formatted.clone();
+        tokio::task::spawn_blocking(move || injector.copy_to_clipboard(&formatted)).await
+    };
+    let body = match copied {
+        Ok(Ok(())) => format!("Copied to clipboard: {}", formatted),
+        Ok(Err(e)) => {
+            log::error!("Failed to copy offline dictation: {}", e);
+            format!("Saved to history: {}", formatted)
+        }
+        Err(e) => {
+            log::error!("Clipboard task failed: {}", e);
+            format!("Saved to history: {}", formatted)
+        }
+    };
+
+    if let Err(e) = app.notification().builder().title("Offline dictation transcribed").body(body).show() {
+        log::warn!("Failed to show notification: {}", e);
+    }
+    let _ = app.emit("offline-dictation-delivered", entry);
+}
+
+/// Relays provider progress to the HUD until the job's sender is dropped
+async fn forward_progress(app: tauri::AppHandle, mut progress_rx: tokio::sync::mpsc::UnboundedReceiver<progress::TranscriptionProgress>) {
+    while let Some(update) = progress_rx.recv().await {
+        if let Some(hud) = app.get_webview_window("hud") {
+            let _ = hud.emit("transcription-progress", update);
+        }
+    }
+}
+
+/// Second pass of a two-pass dictation: re-transcribe with the routed chain and swap
+/// the injected draft for the result, or offer a diff when the draft can't be replaced
+#[allow(clippy::too_many_arguments)]
+async fn refine_in_background(
+    app: tauri::AppHandle,
+    router: Arc<Router>,
+    injector: Arc<TextInjector>,
+    history_store: Arc<HistoryStore>,
+    target: FocusTarget,
+    draft: String,
+    prompt: Option<String>,
+    audio: AudioBuffer,
+    sample_rate: u32,
+) {
+    let duration_secs = audio.len() as f32 / sample_rate as f32;
+    let refine_start = Instant::now();
+
+    let outcome = match context::with_prompt(prompt, router.transcribe_with_source(audio, sample_rate)).await {
+        Ok(outcome) if !outcome.text.is_empty() => outcome,
+        Ok(_) => {
+            log::warn!("Refinement returned empty text, keeping draft");
+            return;
+        }
+        Err(e) => {
+            log::warn!("Refinement failed: {}, keeping draft", e);
+            return;
+        }
+    };
+    let refine_duration = refine_start.elapsed();
+    let refined = FormattingEngine::new(TranscriptionMode::Formatted).format(&outcome.text);
+    log::info!("Refined via {}: '{}' [took: {:?}]", outcome.provider, refined, refine_duration);
+
+    let entry = HistoryEntry::new(refined.clone(), outcome.provider, duration_secs, refine_duration.as_millis() as u64);
+    if let Err(e) = history_store.append(&entry) {
+        log::warn!("Failed to write history entry: {}", e);
+    }
+
+    if refined == draft {
+        log::info!("Refined text matches draft, nothing to replace");
+        return;
+    }
+
+    let replace = {
+        let (draft, refined) = (draft.clone(), refined.clone());
+        tokio::task::spawn_blocking(move || injector.replace_recent(target, &draft, &refined).map_err(|e| e.to_string())).await
+    };
+    match replace {
+        Ok(Ok(ReplaceOutcome::Replaced)) => log::info!("Draft replaced with refined text"),
+        Ok(Ok(outcome)) => {
+            log::info!("Offering refinement diff instead of replacing ({:?})", outcome);
+            if let Some(window) = app.get_webview_window("main") {
+                let _ = window.show();
+            }
+            let _ = app.emit("refinement-available", Refinement::new(draft, refined));
+        }
+        Ok(Err(e)) => log::error!("Failed to replace draft: {}", e),
+        Err(e) => log::error!("Replacement task failed: {}", e),
+    }
+}
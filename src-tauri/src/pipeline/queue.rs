@@ -0,0 +1,235 @@
+// Job queue - hands finished recordings from the capture loop to the processing worker
+//
+// Capture never waits on transcription. Jobs are processed one at a time in the order
+// they were recorded, so injected text always lands in dictation order. What happens
+// when the worker falls behind is decided by the backpressure policy.
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use tokio::sync::Notify;
+
+/// Silence inserted between merged recordings so words don't run together
+const MERGE_GAP_SECS: f32 = 0.3;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackpressurePolicy {
+    /// Keep every job, however far behind the worker is
+    #[default]
+    Queue,
+    /// Reject new recordings while the queue is full
+    Drop,
+    /// Append new audio to the last waiting job once the queue is full, if both
+    /// recordings can share a buffer; otherwise the new job is queued
+    Merge,
+}
+
+/// Audio of one dictation plus whatever the worker needs to finish it
+pub trait QueuedAudio {
+    fn samples_mut(&mut self) -> &mut Vec<f32>;
+    fn sample_rate(&self) -> u32;
+    /// Whether `other` may be appended to this job, e.g. only when both go to the same window
+    fn can_merge_with(&self, _other: &Self) -> bool {
+        true
+    }
+    /// Called on the job that absorbed another one, e.g. to drop streaming state
+    fn on_merged(&mut self) {}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PushOutcome {
+    /// Jobs waiting or in progress after the push
+    Queued { depth: usize },
+    Merged { depth: usize },
+    Dropped { depth: usize },
+}
+
+struct State<J> {
+    pending: VecDeque<J>,
+    in_flight: bool,
+    policy: BackpressurePolicy,
+    max_pending: usize,
+}
+
+pub struct JobQueue<J> {
+    state: Mutex<State<J>>,
+    notify: Notify,
+}
+
+impl<J: QueuedAudio> JobQueue<J> {
+    pub fn new(policy: BackpressurePolicy, max_pending: usize) -> Self {
+        Self {
+            state: Mutex::new(State { pending: VecDeque::new(), in_flight: false, policy, max_pending: max_pending.max(1) }),
+            notify: Notify::new(),
+        }
+    }
+
+    /// Applies to the next push; jobs already waiting are kept
+    pub fn set_policy(&self, policy: BackpressurePolicy, max_pending: usize) {
+        let mut state = self.lock();
+        state.policy = policy;
+        state.max_pending = max_pending.max(1);
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State<J>> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Waiting jobs plus the one being processed
+    pub fn depth(&self) -> usize {
+        let state = self.lock();
+        state.pending.len() + state.in_flight as usize
+    }
+
+    pub fn is_busy(&self) -> bool {
+        self.lock().in_flight
+    }
+
+    pub fn push(&self, mut job: J) -> PushOutcome {
+        let mut state = self.lock();
+        let full = state.pending.len() >= state.max_pending;
+
+        let outcome = match state.policy {
+            BackpressurePolicy::Drop if full => {
+                return PushOutcome::Dropped { depth: state.pending.len() + state.in_flight as usize };
+            }
+            BackpressurePolicy::Merge if full => {
+                let last = state.pending.back_mut().expect("full queue has a last job");
+                if last.sample_rate() == job.sample_rate() && last.can_merge_with(&job) {
+                    let gap = (MERGE_GAP_SECS * last.sample_rate() as f32) as usize;
+                    let samples = last.samples_mut();
+                    samples.extend(std::iter::repeat_n(0.0, gap));
+                    samples.append(job.samples_mut());
+                    last.on_merged();
+                    PushOutcome::Merged { depth: state.pending.len() + state.in_flight as usize }
+                } else {
+                    // Different devices or target windows can't share a buffer; keep both
+                    state.pending.push_back(job);
+                    PushOutcome::Queued { depth: state.pending.len() + state.in_flight as usize }
+                }
+            }
+            _ => {
+                state.pending.push_back(job);
+                PushOutcome::Queued { depth: state.pending.len() + state.in_flight as usize }
+            }
+        };
+
+        drop(state);
+        self.notify.notify_one();
+        outcome
+    }
+
+    /// Waits for the next job and marks it in flight until `finish()` is called
+    pub async fn next(&self) -> J {
+        loop {
+            {
+                let mut state = self.lock();
+                if let Some(job) = state.pending.pop_front() {
+                    state.in_flight = true;
+                    return job;
+                }
+            }
+            self.notify.notified().await;
+        }
+    }
+
+    pub fn finish(&self) -> usize {
+        let mut state = self.lock();
+        state.in_flight = false;
+        state.pending.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Job {
+        samples: Vec<f32>,
+        merged: bool,
+        target: u32,
+    }
+
+    impl Job {
+        fn new(value: f32) -> Self {
+            Self { samples: vec![value; 10], merged: false, target: 0 }
+        }
+    }
+
+    impl QueuedAudio for Job {
+        fn samples_mut(&mut self) -> &mut Vec<f32> {
+            &mut self.samples
+        }
+
+        fn sample_rate(&self) -> u32 {
+            100
+        }
+
+        fn can_merge_with(&self, other: &Self) -> bool {
+            self.target == other.target
+        }
+
+        fn on_merged(&mut self) {
+            self.merged = true;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_jobs_come_out_in_order() {
+        let queue = JobQueue::new(BackpressurePolicy::Queue, 1);
+        assert_eq!(queue.push(Job::new(1.0)), PushOutcome::Queued { depth: 1 });
+        assert_eq!(queue.push(Job::new(2.0)), PushOutcome::Queued { depth: 2 });
+
+        assert_eq!(queue.next().await.samples[0], 1.0);
+        assert!(queue.is_busy());
+        assert_eq!(queue.depth(), 2);
+        assert_eq!(queue.finish(), 1);
+        assert_eq!(queue.next().await.samples[0], 2.0);
+    }
+
+    #[tokio::test]
+    async fn test_drop_and_merge_when_full() {
+        let queue = JobQueue::new(BackpressurePolicy::Drop, 1);
+        queue.push(Job::new(1.0));
+        assert_eq!(queue.push(Job::new(2.0)), PushOutcome::Dropped { depth: 1 });
+        queue.set_policy(BackpressurePolicy::Queue, 1);
+        assert_eq!(queue.push(Job::new(2.0)), PushOutcome::Queued { depth: 2 });
+
+        let queue = JobQueue::new(BackpressurePolicy::Merge, 1);
+        queue.push(Job::new(1.0));
+        assert_eq!(queue.push(Job::new(2.0)), PushOutcome::Merged { depth: 1 });
+
+        let merged = queue.next().await;
+        assert!(merged.merged);
+        // 10 + 0.3s gap at 100Hz + 10
+        assert_eq!(merged.samples.len(), 50);
+        assert_eq!(merged.samples[49], 2.0);
+    }
+
+    #[tokio::test]
+    async fn test_merge_keeps_other_targets_separate() {
+        let queue = JobQueue::new(BackpressurePolicy::Merge, 1);
+        queue.push(Job::new(1.0));
+        assert_eq!(queue.push(Job { target: 1, ..Job::new(2.0) }), PushOutcome::Queued { depth: 2 });
+
+        let first = queue.next().await;
+        assert!(!first.merged);
+        assert_eq!(first.samples.len(), 10);
+        queue.finish();
+        assert_eq!(queue.next().await.target, 1);
+    }
+
+    #[tokio::test]
+    async fn test_worker_wakes_on_push() {
+        let queue = std::sync::Arc::new(JobQueue::new(BackpressurePolicy::Queue, 4));
+        let worker = {
+            let queue = queue.clone();
+            tokio::spawn(async move { queue.next().await })
+        };
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        queue.push(Job::new(3.0));
+        assert_eq!(worker.await.unwrap().samples[0], 3.0);
+    }
+}
@@ -0,0 +1,409 @@
+// Provider chain - ordered fallback across providers with per-provider timeouts
+//
+// Each step gets its own timeout. The chain moves on when a provider errors, times out
+// or returns empty text, and reports which provider produced the final transcript.
+
+use super::{
+    write_wav_file, AudioBuffer, Capabilities, CloudSTTProvider, CommandProvider, HealthStatus, Languages,
+    MockProvider, OnnxProvider, StreamingCloudProvider, TranscriptionProvider, WhisperProvider, WyomingProvider,
+};
+use crate::config::AppConfig;
+use crate::errors::TranscriptionError;
+use crate::models;
+use async_trait::async_trait;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Builds a single provider from its config name, or `None` if the name is unknown
+pub fn create_provider(name: &str, config: &AppConfig) -> Option<Arc<dyn TranscriptionProvider>> {
+    let language = config.language.split('-').next().unwrap_or("en").to_string();
+
+    let provider: Arc<dyn TranscriptionProvider> = match name {
+        "Whisper" => {
+            let model_path = match models::find(&config.whisper_model) {
+                Some(model) => model.path(&models::models_dir()),
+                None => models::models_dir().join(format!("ggml-{}.bin", config.whisper_model)),
+            };
+            log::info!("Using Whisper provider with model: {}", model_path.display());
+
+            // Check if model exists at startup
+            if !model_path.exists() {
+                log::warn!("Whisper model not found at: {}. User must download it.", model_path.display());
+            }
+
+            Arc::new(WhisperProvider::new(
+                model_path.to_string_lossy().to_string(),
+                config.whisper.clone(),
+                config.whisper_binary_path.clone(),
+            ))
+        }
+        "Onnx" => {
+            let model_dir = match models::find(&config.onnx_model) {
+                Some(model) if model.provider == models::ModelProvider::Onnx => model.path(&models::models_dir()),
+                _ => {
+                    log::warn!("'{}' is not an ONNX model, using its name as the model folder", config.onnx_model);
+                    models::models_dir().join(&config.onnx_model)
+                }
+            };
+            log::info!("Using ONNX provider with model: {}", model_dir.display());
+
+            if !model_dir.exists() {
+                log::warn!("ONNX model not found at: {}. User must download it.", model_dir.display());
+            }
+
+            Arc::new(OnnxProvider::new(model_dir))
+        }
+        "Cloud" => {
+            log::info!("Using Cloud STT provider");
+            Arc::new(CloudSTTProvider::new(None))
+        }
+        "Streaming" => {
+            log::info!("Using streaming cloud provider at: {}", config.streaming_endpoint);
+            Arc::new(StreamingCloudProvider::new(config.streaming_endpoint.clone(), language))
+        }
+        "Wyoming" => {
+            log::info!("Using Wyoming provider at: {}:{}", config.wyoming_host, config.wyoming_port);
+            Arc::new(WyomingProvider::new(&config.wyoming_host, config.wyoming_port, language))
+        }
+        "Command" => {
+            log::info!("Using external command provider: {}", config.command_template);
+            Arc::new(CommandProvider::new(config.command_template.clone(), config.command_output.clone(), language))
+        }
+        "SaveForLater" => {
+            log::info!("Using save-for-later fallback");
+            Arc::new(SaveAudioProvider::new(SaveAudioProvider::default_dir()))
+        }
+        "Mock" => {
+            log::info!("Using Mock provider");
+            Arc::new(MockProvider::new())
+        }
+        _ => return None,
+    };
+
+    Some(provider)
+}
+
+#[derive(Clone)]
+pub struct ChainStep {
+    pub provider: Arc<dyn TranscriptionProvider>,
+    pub timeout: Duration,
+}
+
+/// Text plus the provider that produced it
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChainOutcome {
+    pub text: String,
+    pub provider: String,
+    /// Index of the step that produced the text
+    pub step: usize,
+}
+
+pub struct ProviderChain {
+    steps: Vec<ChainStep>,
+}
+
+impl ProviderChain {
+    pub fn new(steps: Vec<ChainStep>) -> Self {
+        Self { steps }
+    }
+
+    /// Uses `provider_chain` when configured, otherwise the single `provider` setting.
+    /// Unknown provider names are skipped with a warning instead of silently becoming Mock.
+    pub fn from_config(config: &AppConfig) -> Self {
+        let entries = config.effective_provider_chain();
+
+        let steps = entries.iter()
+            .filter_map(|entry| match create_provider(&entry.provider, config) {
+                Some(provider) => Some(ChainStep {
+                    provider,
+                    timeout: Duration::from_millis(entry.timeout_ms),
+                }),
+                None => {
+                    log::warn!("Unknown provider '{}' in provider chain, skipping", entry.provider);
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+
+        log::info!("Provider chain: [{}]", steps.iter().map(|s| s.provider.name()).collect::<Vec<_>>().join(" -> "));
+        Self::new(steps)
+    }
+
+    /// Copy of this chain with `step` tried first, e.g. the provider picked by the routing policy
+    pub fn with_primary(&self, step: ChainStep) -> Self {
+        let mut steps = Vec::with_capacity(self.steps.len() + 1);
+        steps.push(step);
+        steps.extend(self.steps.iter().cloned());
+        Self::new(steps)
+    }
+
+    pub fn steps(&self) -> &[ChainStep] {
+        &self.steps
+    }
+
+    /// First provider in the chain, used for streaming while the hotkey is held
+    pub fn primary(&self) -> Option<&ChainStep> {
+        self.steps.first()
+    }
+
+    pub async fn transcribe_with_source(&self, audio: AudioBuffer, sample_rate: u32) -> Result<ChainOutcome, Box<dyn std::error::Error + Send + Sync>> {
+        self.transcribe_from(0, audio, sample_rate).await
+    }
+
+    /// Runs the chain starting at step `start`, e.g. after the streaming primary already failed
+    pub async fn transcribe_from(&self, start: usize, audio: AudioBuffer, sample_rate: u32) -> Result<ChainOutcome, Box<dyn std::error::Error + Send + Sync>> {
+        if self.steps.is_empty() {
+            return Err(TranscriptionError::NoProvider.into());
+        }
+        self.fall_back_from(start, Vec::new(), audio, sample_rate).await
+    }
+
+    /// Tries the steps from `start` on, after the `failures` already seen
+    async fn fall_back_from(
+        &self,
+        start: usize,
+        mut failures: Vec<(String, TranscriptionError)>,
+        audio: AudioBuffer,
+        sample_rate: u32,
+    ) -> Result<ChainOutcome, Box<dyn std::error::Error + Send + Sync>> {
+        for (index, step) in self.steps.iter().enumerate().skip(start) {
+            let name = step.provider.name();
+            let step_start = std::time::Instant::now();
+
+            match tokio::time::timeout(step.timeout, step.provider.transcribe(audio.clone(), sample_rate)).await {
+                Ok(Ok(text)) if !text.trim().is_empty() => {
+                    log::info!("Provider '{}' succeeded [took: {:?}]", name, step_start.elapsed());
+                    return Ok(ChainOutcome { text, provider: name.to_string(), step: index });
+                }
+                Ok(Ok(_)) => {
+                    log::warn!("Provider '{}' returned empty text, trying next", name);
+                    failures.push((name.to_string(), TranscriptionError::EmptyResult { provider: name.to_string() }));
+                }
+                Ok(Err(e)) => {
+                    log::warn!("Provider '{}' failed: {}, trying next", name, e);
+                    failures.push((name.to_string(), TranscriptionError::from_boxed(e)));
+                }
+                Err(_) => {
+                    log::warn!("Provider '{}' timed out after {:?}, trying next", name, step.timeout);
+                    failures.push((name.to_string(), TranscriptionError::Timeout { provider: name.to_string(), secs: step.timeout.as_secs_f32() }));
+                }
+            }
+        }
+
+        // A single attempt keeps its own error; otherwise report all, hinting from the first
+        if failures.is_empty() {
+            return Err(TranscriptionError::NoProvider.into());
+        }
+        if failures.len() == 1 {
+            return Err(failures.remove(0).1.into());
+        }
+        let attempts = failures.iter()
+            .map(|(name, e)| format!("{}: {}", name, e))
+            .collect::<Vec<_>>()
+            .join("; ");
+        let primary = Box::new(failures.remove(0).1);
+        Err(TranscriptionError::AllProvidersFailed { attempts, primary }.into())
+    }
+
+    /// Awaits a streaming session started on the primary provider, honouring its timeout,
+    /// and falls back to the rest of the chain on error, timeout or empty text. The
+    /// primary's failure is reported like any other step's, so a dropped connection still
+    /// reads as a network error.
+    pub async fn finish_streamed(
+        &self,
+        job: tokio::task::JoinHandle<Result<String, Box<dyn std::error::Error + Send + Sync>>>,
+        audio: AudioBuffer,
+        sample_rate: u32,
+    ) -> Result<ChainOutcome, Box<dyn std::error::Error + Send + Sync>> {
+        let Some(primary) = self.primary() else {
+            return Err(TranscriptionError::NoProvider.into());
+        };
+        let name = primary.provider.name();
+        let abort = job.abort_handle();
+
+        let failure = match tokio::time::timeout(primary.timeout, job).await {
+            Ok(Ok(Ok(text))) if !text.trim().is_empty() => {
+                return Ok(ChainOutcome { text, provider: name.to_string(), step: 0 });
+            }
+            Ok(Ok(Ok(_))) => {
+                log::warn!("Streaming provider '{}' returned empty text, trying next", name);
+                TranscriptionError::EmptyResult { provider: name.to_string() }
+            }
+            Ok(Ok(Err(e))) => {
+                log::warn!("Streaming provider '{}' failed: {}, trying next", name, e);
+                TranscriptionError::from_boxed(e)
+            }
+            Ok(Err(e)) => {
+                log::warn!("Streaming provider '{}' task failed: {}, trying next", name, e);
+                TranscriptionError::EngineFailed { engine: name.to_string(), details: e.to_string() }
+            }
+            Err(_) => {
+                abort.abort();
+                log::warn!("Streaming provider '{}' timed out after {:?}, trying next", name, primary.timeout);
+                TranscriptionError::Timeout { provider: name.to_string(), secs: primary.timeout.as_secs_f32() }
+            }
+        };
+
+        self.fall_back_from(1, vec![(name.to_string(), failure)], audio, sample_rate).await
+    }
+}
+
+#[async_trait]
+impl TranscriptionProvider for ProviderChain {
+    async fn transcribe(&self, audio: AudioBuffer, sample_rate: u32) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(self.transcribe_with_source(audio, sample_rate).await?.text)
+    }
+
+    fn name(&self) -> &'static str {
+        "Chain"
+    }
+
+    fn supports_streaming(&self) -> bool {
+        false
+    }
+}
+
+/// Last-resort chain step: keeps the recording on disk so it can be transcribed later
+pub struct SaveAudioProvider {
+    dir: PathBuf,
+}
+
+impl SaveAudioProvider {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    pub fn default_dir() -> PathBuf {
+        crate::paths::storage().data_dir.join("saved_audio")
+    }
+}
+
+#[async_trait]
+impl TranscriptionProvider for SaveAudioProvider {
+    async fn transcribe(&self, audio: AudioBuffer, sample_rate: u32) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        std::fs::create_dir_all(&self.dir)?;
+        let path = self.dir.join(format!("{}.wav", chrono::Local::now().format("%Y%m%d-%H%M%S-%3f")));
+        write_wav_file(&audio, sample_rate, &path)?;
+        log::info!("Saved untranscribed audio to: {}", path.display());
+
+        // Never produces text; the error tells the user where the audio went
+        Err(TranscriptionError::SavedForLater { path }.into())
+    }
+
+    fn name(&self) -> &'static str {
+        "SaveForLater"
+    }
+
+    fn supports_streaming(&self) -> bool {
+        false
+    }
+
+    async fn health_check(&self) -> HealthStatus {
+        HealthStatus::ready(format!("Saves audio to {}", self.dir.display()))
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities { languages: Languages::Any, streaming: false, timestamps: false, translation: false, context: false }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::UserFacingError;
+
+    struct FakeProvider {
+        name: &'static str,
+        result: Result<&'static str, &'static str>,
+        delay_ms: u64,
+    }
+
+    #[async_trait]
+    impl TranscriptionProvider for FakeProvider {
+        async fn transcribe(&self, _audio: AudioBuffer, _sample_rate: u32) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+            tokio::time::sleep(Duration::from_millis(self.delay_ms)).await;
+            self.result.map(str::to_string).map_err(Into::into)
+        }
+
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        fn supports_streaming(&self) -> bool {
+            false
+        }
+    }
+
+    fn step(name: &'static str, result: Result<&'static str, &'static str>, delay_ms: u64, timeout_ms: u64) -> ChainStep {
+        ChainStep {
+            provider: Arc::new(FakeProvider { name, result, delay_ms }),
+            timeout: Duration::from_millis(timeout_ms),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_falls_through_errors_timeouts_and_empty_results() {
+        let chain = ProviderChain::new(vec![
+            step("Broken", Err("model missing"), 0, 1000),
+            step("Slow", Ok("too late"), 500, 20),
+            step("Silent", Ok("   "), 0, 1000),
+            step("Good", Ok("hello"), 0, 1000),
+        ]);
+
+        let outcome = chain.transcribe_with_source(vec![0.0; 160], 16000).await.unwrap();
+        assert_eq!(outcome, ChainOutcome { text: "hello".to_string(), provider: "Good".to_string(), step: 3 });
+    }
+
+    #[tokio::test]
+    async fn test_reports_every_failure() {
+        let chain = ProviderChain::new(vec![
+            step("A", Err("offline"), 0, 1000),
+            step("B", Ok(""), 0, 1000),
+        ]);
+
+        let err = TranscriptionError::from_boxed(chain.transcribe_with_source(vec![], 16000).await.unwrap_err());
+        assert_eq!(err.code(), "TRANSCRIPTION_ALL_PROVIDERS_FAILED");
+        let err = err.to_string();
+        assert!(err.contains("A: offline"));
+        assert!(err.contains("B: B returned an empty transcript"));
+    }
+
+    #[tokio::test]
+    async fn test_transcribe_from_skips_primary() {
+        let chain = ProviderChain::new(vec![
+            step("Primary", Ok("primary"), 0, 1000),
+            step("Backup", Ok("backup"), 0, 1000),
+        ]);
+
+        let outcome = chain.transcribe_from(1, vec![], 16000).await.unwrap();
+        assert_eq!((outcome.provider.as_str(), outcome.step), ("Backup", 1));
+    }
+
+    #[tokio::test]
+    async fn test_streamed_primary_failure_keeps_its_error() {
+        let chain = ProviderChain::new(vec![
+            step("Streaming", Ok("unused"), 0, 1000),
+            step("Backup", Err("model missing"), 0, 1000),
+        ]);
+        let session = tokio::spawn(async {
+            Err(TranscriptionError::Connection { endpoint: "streaming endpoint".into(), details: "refused".into() }.into())
+        });
+
+        let err = TranscriptionError::from_boxed(chain.finish_streamed(session, vec![], 16000).await.unwrap_err());
+        assert_eq!(err.code(), "TRANSCRIPTION_ALL_PROVIDERS_FAILED");
+        assert!(err.is_network());
+    }
+
+    #[tokio::test]
+    async fn test_save_for_later_writes_wav() {
+        let dir = std::env::temp_dir().join(format!("vanta-saved-{}", uuid::Uuid::new_v4()));
+        let provider = SaveAudioProvider::new(dir.clone());
+
+        let err = provider.transcribe(vec![0.0; 1600], 16000).await.unwrap_err();
+        assert!(err.to_string().starts_with("Audio saved for later"));
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,296 @@
+// External command provider - plug any speech-to-text CLI in via a command-line template
+//
+// Example: `my-stt --in {wav} --lang {lang}`
+//   {wav}         path of a temporary 16kHz mono 16-bit WAV file
+//   {lang}        configured language code (e.g. "en")
+//   {sample_rate} sample rate of the WAV file (always 16000)
+//   {prompt}      recent dictations into the same window, empty when context is off
+//
+// The template is split into arguments before placeholders are substituted, so paths
+// containing spaces stay a single argument. Quote parts with "..." or '...' to keep
+// literal spaces together.
+
+use super::context;
+use super::lifecycle::{self, Capabilities, HealthStatus, Languages};
+use super::{hidden_command, resample_to_16k, write_wav_file, AudioBuffer, TempFile, TranscriptionProvider};
+use crate::errors::TranscriptionError;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// How the transcript is extracted from the command's stdout
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "mode", content = "pattern", rename_all = "snake_case")]
+pub enum OutputParser {
+    /// Whole stdout, trimmed
+    #[default]
+    Stdout,
+    /// Dotted path into JSON stdout, e.g. `result.text` or `segments.*.text`
+    JsonPath(String),
+    /// Regex over stdout; every match's first capture group (or whole match) is joined
+    Regex(String),
+}
+
+impl OutputParser {
+    pub fn parse(&self, stdout: &str) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        match self {
+            OutputParser::Stdout => Ok(stdout.trim().to_string()),
+            OutputParser::JsonPath(path) => {
+                let json: Value = serde_json::from_str(stdout.trim())
+                    .map_err(|e| format!("Command output is not valid JSON: {}", e))?;
+                let mut parts = Vec::new();
+                collect_json_path(&json, &path_segments(path), &mut parts);
+                if parts.is_empty() {
+                    return Err(format!("JSON path '{}' not found in command output", path).into());
+                }
+                Ok(parts.join(" ").trim().to_string())
+            }
+            OutputParser::Regex(pattern) => {
+                let re = regex::Regex::new(pattern)
+                    .map_err(|e| format!("Invalid output regex '{}': {}", pattern, e))?;
+                let parts: Vec<&str> = re.captures_iter(stdout)
+                    .filter_map(|caps| caps.get(1).or_else(|| caps.get(0)))
+                    .map(|m| m.as_str().trim())
+                    .filter(|s| !s.is_empty())
+                    .collect();
+                Ok(parts.join(" "))
+            }
+        }
+    }
+}
+
+fn path_segments(path: &str) -> Vec<&str> {
+    path.trim_start_matches('$')
+        .split(['.', '[', ']'])
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+fn collect_json_path(value: &Value, segments: &[&str], out: &mut Vec<String>) {
+    let Some((head, rest)) = segments.split_first() else {
+        match value {
+            Value::String(s) => out.push(s.clone()),
+            Value::Array(items) => items.iter().for_each(|item| collect_json_path(item, &[], out)),
+            Value::Null => {}
+            other => out.push(other.to_string()),
+        }
+        return;
+    };
+
+    match (value, *head) {
+        (Value::Array(items), "*") => items.iter().for_each(|item| collect_json_path(item, rest, out)),
+        (Value::Array(items), index) => {
+            if let Some(item) = index.parse::<usize>().ok().and_then(|i| items.get(i)) {
+                collect_json_path(item, rest, out);
+            }
+        }
+        (Value::Object(map), key) => {
+            if let Some(item) = map.get(key) {
+                collect_json_path(item, rest, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Splits a command-line template into arguments, honouring single and double quotes
+pub fn split_template(template: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_arg = false;
+    let mut quote: Option<char> = None;
+
+    for ch in template.chars() {
+        match (quote, ch) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), c) => current.push(c),
+            (None, '"') | (None, '\'') => {
+                quote = Some(ch);
+                in_arg = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_arg {
+                    args.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            }
+            (None, c) => {
+                current.push(c);
+                in_arg = true;
+            }
+        }
+    }
+    if in_arg {
+        args.push(current);
+    }
+    args
+}
+
+pub struct CommandProvider {
+    template: String,
+    output: OutputParser,
+    language: String,
+}
+
+impl CommandProvider {
+    pub fn new(template: String, output: OutputParser, language: String) -> Self {
+        log::info!("CommandProvider initialized with template: {}", template);
+        Self { template, output, language }
+    }
+
+    fn build_args(&self, wav_path: &std::path::Path) -> Vec<String> {
+        let wav = wav_path.to_string_lossy();
+        let prompt = context::current_prompt().unwrap_or_default();
+        split_template(&self.template)
+            .into_iter()
+            .map(|arg| arg
+                .replace("{wav}", &wav)
+                .replace("{lang}", &self.language)
+                .replace("{sample_rate}", "16000")
+                .replace("{prompt}", &prompt))
+            .collect()
+    }
+}
+
+#[async_trait]
+impl TranscriptionProvider for CommandProvider {
+    async fn transcribe(&self, audio: AudioBuffer, sample_rate: u32) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        log::info!("Transcribing {} samples at {}Hz with external command", audio.len(), sample_rate);
+
+        let audio_16k = resample_to_16k(audio, sample_rate);
+        let audio_file = TempFile::new(std::env::temp_dir().join(format!("vanta_audio_{}.wav", uuid::Uuid::new_v4())));
+        write_wav_file(&audio_16k, 16000, audio_file.path())?;
+
+        let args = self.build_args(audio_file.path());
+        let output_parser = self.output.clone();
+
+        // Dropping this future (cancel) kills the child; the guard removes the WAV
+        let result: Result<String, Box<dyn std::error::Error + Send + Sync>> = async move {
+            let (program, rest) = args.split_first().ok_or("Command template is empty")?;
+            log::info!("Running external STT command: {} {:?}", program, rest);
+
+            let output = tokio::process::Command::from(hidden_command(program))
+                .args(rest)
+                .kill_on_drop(true)
+                .output()
+                .await
+                .map_err(|e| TranscriptionError::EngineNotFound { program: program.clone(), details: e.to_string() })?;
+
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            log::debug!("External command stdout:\n{}", stdout);
+
+            if !output.status.success() {
+                log::error!("External command failed with exit code: {:?}", output.status.code());
+                return Err(TranscriptionError::EngineFailed { engine: "External command".to_string(), details: stderr.trim().to_string() }.into());
+            }
+
+            output_parser.parse(&stdout)
+        }.await;
+        drop(audio_file);
+
+        let transcription = result?;
+        log::info!("External command transcription complete: '{}' (len: {})", transcription, transcription.len());
+        Ok(transcription)
+    }
+
+    fn name(&self) -> &'static str {
+        "Command"
+    }
+
+    fn supports_streaming(&self) -> bool {
+        false
+    }
+
+    async fn health_check(&self) -> HealthStatus {
+        let args = split_template(&self.template);
+        let Some(program) = args.first() else {
+            return HealthStatus::unavailable(&TranscriptionError::NotConfigured {
+                provider: "Command".to_string(),
+                missing: "command template".to_string(),
+            });
+        };
+        match lifecycle::find_program(program) {
+            Some(path) => HealthStatus::ready(format!("Runs {}", path.display())),
+            None => HealthStatus::unavailable(&TranscriptionError::EngineNotFound {
+                program: program.clone(),
+                details: "not found on PATH".to_string(),
+            }),
+        }
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            languages: Languages::Unknown,
+            streaming: false,
+            timestamps: false,
+            translation: false,
+            context: self.template.contains("{prompt}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_template_keeps_quoted_args() {
+        let args = split_template(r#"my-stt --in {wav} --prompt "hello world" --x 'a b'"#);
+        assert_eq!(args, vec!["my-stt", "--in", "{wav}", "--prompt", "hello world", "--x", "a b"]);
+    }
+
+    #[test]
+    fn test_output_parsers() {
+        assert_eq!(OutputParser::Stdout.parse("  hi there \n").unwrap(), "hi there");
+
+        let json = r#"{"result":{"text":"full"},"segments":[{"text":" one"},{"text":"two "}]}"#;
+        assert_eq!(OutputParser::JsonPath("result.text".into()).parse(json).unwrap(), "full");
+        assert_eq!(OutputParser::JsonPath("$.segments[1].text".into()).parse(json).unwrap(), "two");
+        assert_eq!(OutputParser::JsonPath("segments.*.text".into()).parse(json).unwrap(), "one two");
+        assert!(OutputParser::JsonPath("missing".into()).parse(json).is_err());
+
+        let log = "[00:00.000 --> 00:01.000] hello\n[00:01.000 --> 00:02.000] world\n";
+        assert_eq!(OutputParser::Regex(r"\] (.+)".into()).parse(log).unwrap(), "hello world");
+    }
+
+    #[test]
+    fn test_output_parser_config_format() {
+        let parser: OutputParser = serde_json::from_str(r#"{"mode":"json_path","pattern":"text"}"#).unwrap();
+        assert_eq!(parser, OutputParser::JsonPath("text".into()));
+        let parser: OutputParser = serde_json::from_str(r#"{"mode":"stdout"}"#).unwrap();
+        assert_eq!(parser, OutputParser::Stdout);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_runs_command_with_wav_placeholder() {
+        // 100 samples -> 44 byte header + 200 bytes of PCM
+        let provider = CommandProvider::new(
+            "wc -c {wav}".to_string(),
+            OutputParser::Regex(r"^\s*(\d+)".to_string()),
+            "en".to_string(),
+        );
+        assert_eq!(provider.transcribe(vec![0.0; 100], 16000).await.unwrap(), "244");
+
+        let provider = CommandProvider::new("echo lang={lang}".to_string(), OutputParser::Stdout, "de".to_string());
+        assert_eq!(provider.transcribe(vec![0.0; 100], 16000).await.unwrap(), "lang=de");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_cancel_kills_child_and_removes_wav() {
+        let marker = std::env::temp_dir().join(format!("vanta-cancel-{}.txt", uuid::Uuid::new_v4()));
+        let template = format!("sh -c 'echo {{wav}} > {} && sleep 5'", marker.display());
+        let provider = CommandProvider::new(template, OutputParser::Stdout, "en".to_string());
+
+        let start = std::time::Instant::now();
+        let cancelled = tokio::time::timeout(std::time::Duration::from_millis(300), provider.transcribe(vec![0.0; 100], 16000)).await;
+        assert!(cancelled.is_err());
+        assert!(start.elapsed() < std::time::Duration::from_secs(2));
+
+        let wav = std::fs::read_to_string(&marker).unwrap();
+        assert!(!std::path::Path::new(wav.trim()).exists());
+        let _ = std::fs::remove_file(&marker);
+    }
+}
@@ -0,0 +1,127 @@
+// Dictation context - recent transcripts passed to the decoder as a prompt
+//
+// A sentence split across several push-to-talk presses loses its context between
+// recordings, so names drift in spelling and capitalization. The pipeline keeps the
+// last few transcripts sent to each window and scopes them around the next job with
+// `with_prompt`; providers that accept a prompt read it with `current_prompt`.
+
+use crate::config::AppConfig;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+/// whisper.cpp keeps at most ~224 prompt tokens; stay well under that in characters
+const MAX_PROMPT_CHARS: usize = 600;
+
+tokio::task_local! {
+    static PROMPT: Option<String>;
+}
+
+/// Runs `future` with `prompt` visible to providers through `current_prompt`
+pub async fn with_prompt<F: Future>(prompt: Option<String>, future: F) -> F::Output {
+    PROMPT.scope(prompt, future).await
+}
+
+/// Context for the transcription running in this task, if any
+pub fn current_prompt() -> Option<String> {
+    PROMPT.try_with(|prompt| prompt.clone()).ok().flatten()
+}
+
+/// Recent transcripts for the window dictated into last
+pub struct DictationContext<T> {
+    max_dictations: usize,
+    idle_reset: Duration,
+    target: Option<T>,
+    last_at: Option<Instant>,
+    recent: VecDeque<String>,
+}
+
+impl<T: PartialEq + Copy> DictationContext<T> {
+    pub fn new(max_dictations: usize, idle_reset: Duration) -> Self {
+        Self { max_dictations, idle_reset, target: None, last_at: None, recent: VecDeque::new() }
+    }
+
+    /// `None` unless context is enabled
+    pub fn from_config(config: &AppConfig) -> Option<Self> {
+        if !config.context_enabled || config.context_max_dictations == 0 {
+            return None;
+        }
+        log::info!("Dictation context enabled: last {} dictations, reset after {}s idle",
+            config.context_max_dictations, config.context_idle_reset_secs);
+        Some(Self::new(config.context_max_dictations, Duration::from_secs(config.context_idle_reset_secs)))
+    }
+
+    /// Prompt for a dictation into `target`, forgetting everything first if the
+    /// window changed or nothing was dictated for longer than the idle timeout
+    pub fn prompt_for(&mut self, target: T, now: Instant) -> Option<String> {
+        let idle = self.last_at.is_some_and(|last| now.saturating_duration_since(last) > self.idle_reset);
+        if self.target != Some(target) || idle {
+            if !self.recent.is_empty() {
+                log::info!("Dictation context reset ({})", if idle { "idle" } else { "window changed" });
+            }
+            self.recent.clear();
+            self.target = Some(target);
+        }
+
+        if self.recent.is_empty() {
+            return None;
+        }
+        let joined = self.recent.iter().map(String::as_str).collect::<Vec<_>>().join(" ");
+        Some(tail_at_word(&joined, MAX_PROMPT_CHARS).to_string())
+    }
+
+    /// Remembers text that was sent to `target`
+    pub fn record(&mut self, target: T, text: &str, now: Instant) {
+        if self.target != Some(target) {
+            self.recent.clear();
+            self.target = Some(target);
+        }
+        self.last_at = Some(now);
+        let text = text.trim();
+        if text.is_empty() {
+            return;
+        }
+        self.recent.push_back(text.to_string());
+        while self.recent.len() > self.max_dictations {
+            self.recent.pop_front();
+        }
+    }
+}
+
+/// Last `max_chars` characters of `text`, starting at a word boundary
+fn tail_at_word(text: &str, max_chars: usize) -> &str {
+    let count = text.chars().count();
+    if count <= max_chars {
+        return text;
+    }
+    let start = text.char_indices().nth(count - max_chars).map(|(i, _)| i).unwrap_or(0);
+    let tail = &text[start..];
+    match tail.find(char::is_whitespace) {
+        Some(space) => tail[space..].trim_start(),
+        None => tail,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_context_resets_on_window_change_and_idle() {
+        let mut context = DictationContext::new(2, Duration::from_secs(30));
+        let start = Instant::now();
+
+        assert_eq!(context.prompt_for(1, start), None);
+        context.record(1, "Meeting with Siobhan.", start);
+        context.record(1, "She owns the Kubernetes rollout.", start);
+        context.record(1, "Ask about the budget.", start);
+        assert_eq!(context.prompt_for(1, start + Duration::from_secs(5)).as_deref(),
+            Some("She owns the Kubernetes rollout. Ask about the budget."));
+
+        assert_eq!(context.prompt_for(2, start + Duration::from_secs(6)), None);
+        context.record(2, "Dear Siobhan,", start + Duration::from_secs(6));
+        assert_eq!(context.prompt_for(2, start + Duration::from_secs(60)), None);
+
+        assert_eq!(tail_at_word("alpha beta gamma", 8), "gamma");
+    }
+}
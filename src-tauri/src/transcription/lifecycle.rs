@@ -0,0 +1,186 @@
+// Provider lifecycle - warm-up, health checks and capability reporting
+//
+// Run once at launch so a missing model, binary or server shows up in settings
+// instead of on the first real dictation.
+
+use super::chain::ChainStep;
+use crate::errors::{ErrorPayload, TranscriptionError, UserFacingError};
+use serde::Serialize;
+use std::time::Instant;
+
+/// Languages a provider can transcribe
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", content = "codes", rename_all = "snake_case")]
+pub enum Languages {
+    /// Multilingual model, language detected or set per request
+    Any,
+    Only(Vec<String>),
+    /// Decided by a remote server or external program
+    Unknown,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Capabilities {
+    pub languages: Languages,
+    pub streaming: bool,
+    pub timestamps: bool,
+    pub translation: bool,
+    /// Accepts previous dictations as a decoder prompt
+    pub context: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthState {
+    Ready,
+    /// Usable, but something is worth surfacing (e.g. nothing could be verified)
+    Degraded,
+    Unavailable,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HealthStatus {
+    pub state: HealthState,
+    pub message: String,
+    /// Code and remediation hint when the provider is unavailable
+    pub error: Option<ErrorPayload>,
+}
+
+impl HealthStatus {
+    pub fn ready(message: impl Into<String>) -> Self {
+        Self { state: HealthState::Ready, message: message.into(), error: None }
+    }
+
+    pub fn degraded(message: impl Into<String>) -> Self {
+        Self { state: HealthState::Degraded, message: message.into(), error: None }
+    }
+
+    pub fn unavailable<E: UserFacingError>(err: &E) -> Self {
+        let payload = err.payload();
+        Self { state: HealthState::Unavailable, message: payload.message.clone(), error: Some(payload) }
+    }
+
+    pub fn is_usable(&self) -> bool {
+        self.state != HealthState::Unavailable
+    }
+}
+
+/// Everything settings shows about one provider in the chain
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderReport {
+    pub provider: String,
+    /// Position in the fallback chain, 0 = primary
+    pub position: usize,
+    pub capabilities: Capabilities,
+    pub health: HealthStatus,
+    /// `None` when warm-up was skipped because the provider is unavailable
+    pub warm_up_ms: Option<u64>,
+    pub warm_up_error: Option<String>,
+}
+
+/// Health-checks every step, then warms up the usable ones, in chain order
+pub async fn check_chain(steps: &[ChainStep], warm_up: bool) -> Vec<ProviderReport> {
+    let mut reports = Vec::with_capacity(steps.len());
+    for (position, step) in steps.iter().enumerate() {
+        let provider = &step.provider;
+        let health = provider.health_check().await;
+        log::info!("Provider {} health: {:?} - {}", provider.name(), health.state, health.message);
+
+        let (warm_up_ms, warm_up_error) = if warm_up && health.is_usable() {
+            let start = Instant::now();
+            match provider.warm_up().await {
+                Ok(()) => {
+                    log::info!("Provider {} warmed up in {:?}", provider.name(), start.elapsed());
+                    (Some(start.elapsed().as_millis() as u64), None)
+                }
+                Err(e) => {
+                    log::warn!("Provider {} warm-up failed: {}", provider.name(), e);
+                    (Some(start.elapsed().as_millis() as u64), Some(e.to_string()))
+                }
+            }
+        } else {
+            (None, None)
+        };
+
+        reports.push(ProviderReport {
+            provider: provider.name().to_string(),
+            position,
+            capabilities: provider.capabilities(),
+            health,
+            warm_up_ms,
+            warm_up_error,
+        });
+    }
+    reports
+}
+
+/// Checks that a TCP server accepts connections, for network providers
+pub(crate) async fn probe_tcp(address: &str, label: &str) -> HealthStatus {
+    let connect = tokio::net::TcpStream::connect(address);
+    match tokio::time::timeout(std::time::Duration::from_secs(3), connect).await {
+        Ok(Ok(_)) => HealthStatus::ready(format!("{} reachable at {}", label, address)),
+        Ok(Err(e)) => HealthStatus::unavailable(&TranscriptionError::Connection { endpoint: format!("{} at {}", label, address), details: e.to_string() }),
+        Err(_) => HealthStatus::unavailable(&TranscriptionError::Timeout { provider: label.to_string(), secs: 3.0 }),
+    }
+}
+
+/// Resolves a bare program name against PATH the way the OS would when spawning it
+pub(crate) fn find_program(program: &str) -> Option<std::path::PathBuf> {
+    let path = std::path::Path::new(program);
+    if path.components().count() > 1 {
+        return path.exists().then(|| path.to_path_buf());
+    }
+    let extensions: &[&str] = if cfg!(windows) { &["", ".exe", ".cmd", ".bat"] } else { &[""] };
+    std::env::split_paths(&std::env::var_os("PATH")?)
+        .flat_map(|dir| extensions.iter().map(move |ext| dir.join(format!("{}{}", program, ext))))
+        .find(|candidate| candidate.is_file())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transcription::{AudioBuffer, TranscriptionProvider};
+    use async_trait::async_trait;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    struct MissingModel;
+
+    #[async_trait]
+    impl TranscriptionProvider for MissingModel {
+        async fn transcribe(&self, _audio: AudioBuffer, _sample_rate: u32) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+            unreachable!("warm-up must not run for unavailable providers")
+        }
+
+        fn name(&self) -> &'static str {
+            "Missing"
+        }
+
+        fn supports_streaming(&self) -> bool {
+            false
+        }
+
+        async fn health_check(&self) -> HealthStatus {
+            HealthStatus::unavailable(&TranscriptionError::ModelNotFound { path: "ggml-base.bin".into() })
+        }
+
+        async fn warm_up(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+            unreachable!("warm-up must not run for unavailable providers")
+        }
+    }
+
+    #[tokio::test]
+    async fn test_check_chain_reports_each_step() {
+        let steps = vec![
+            ChainStep { provider: Arc::new(MissingModel), timeout: Duration::from_secs(1) },
+            ChainStep { provider: Arc::new(crate::transcription::MockProvider::new()), timeout: Duration::from_secs(1) },
+        ];
+        let reports = check_chain(&steps, true).await;
+
+        assert_eq!(reports[0].health.state, HealthState::Unavailable);
+        assert_eq!(reports[0].health.error.as_ref().map(|e| e.code), Some("TRANSCRIPTION_MODEL_NOT_FOUND"));
+        assert_eq!(reports[0].warm_up_ms, None);
+        assert_eq!((reports[1].position, reports[1].health.state), (1, HealthState::Ready));
+        assert!(reports[1].warm_up_ms.is_some());
+    }
+}
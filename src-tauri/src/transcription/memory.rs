@@ -0,0 +1,105 @@
+// Memory probe - peak memory of transcription child processes
+//
+// Like progress reports, probing is scoped around a future with `with_probe`; while a
+// probe is active, `wait_with_progress` samples the child's peak resident memory until it
+// exits. Outside a probe nothing is sampled, so dictations pay nothing for it.
+
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+const SAMPLE_INTERVAL: Duration = Duration::from_millis(50);
+
+tokio::task_local! {
+    static PEAK_BYTES: Arc<AtomicU64>;
+}
+
+/// Runs `future` and returns the highest memory use of any child process it waited on,
+/// or `None` if nothing could be measured on this platform
+pub async fn with_probe<F: Future>(future: F) -> (F::Output, Option<u64>) {
+    let peak = Arc::new(AtomicU64::new(0));
+    let output = PEAK_BYTES.scope(peak.clone(), future).await;
+    let bytes = peak.load(Ordering::SeqCst);
+    (output, (bytes > 0).then_some(bytes))
+}
+
+pub(crate) fn is_probing() -> bool {
+    PEAK_BYTES.try_with(|_| ()).is_ok()
+}
+
+/// Drives `future`, sampling the memory of process `pid` until it completes
+pub(crate) async fn sample_while<F: Future>(pid: u32, future: F) -> F::Output {
+    tokio::pin!(future);
+    let mut interval = tokio::time::interval(SAMPLE_INTERVAL);
+    loop {
+        tokio::select! {
+            output = &mut future => return output,
+            _ = interval.tick() => {
+                if let Some(bytes) = peak_resident_bytes(pid) {
+                    let _ = PEAK_BYTES.try_with(|peak| peak.fetch_max(bytes, Ordering::SeqCst));
+                }
+            }
+        }
+    }
+}
+
+/// High-water mark of the process' resident set so far
+#[cfg(target_os = "linux")]
+fn peak_resident_bytes(pid: u32) -> Option<u64> {
+    let status = std::fs::read_to_string(format!("/proc/{}/status", pid)).ok()?;
+    let kb: u64 = status.lines()
+        .find_map(|line| line.strip_prefix("VmHWM:"))?
+        .trim()
+        .trim_end_matches("kB")
+        .trim()
+        .parse()
+        .ok()?;
+    Some(kb * 1024)
+}
+
+#[cfg(windows)]
+fn peak_resident_bytes(pid: u32) -> Option<u64> {
+    use windows::Win32::Foundation::CloseHandle;
+    use windows::Win32::System::ProcessStatus::{K32GetProcessMemoryInfo, PROCESS_MEMORY_COUNTERS};
+    use windows::Win32::System::Threading::{OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION};
+
+    unsafe {
+        let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+        let mut counters = PROCESS_MEMORY_COUNTERS::default();
+        let size = std::mem::size_of::<PROCESS_MEMORY_COUNTERS>() as u32;
+        let ok = K32GetProcessMemoryInfo(process, &mut counters, size).as_bool();
+        let _ = CloseHandle(process);
+        ok.then_some(counters.PeakWorkingSetSize as u64)
+    }
+}
+
+/// `ps` reports current (not peak) RSS; sampling keeps the highest value seen
+#[cfg(all(unix, not(target_os = "linux")))]
+fn peak_resident_bytes(pid: u32) -> Option<u64> {
+    let output = std::process::Command::new("ps").args(["-o", "rss=", "-p", &pid.to_string()]).output().ok()?;
+    let kb: u64 = String::from_utf8_lossy(&output.stdout).trim().parse().ok()?;
+    Some(kb * 1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_samples_only_inside_a_probe() {
+        let pid = std::process::id();
+        let sleep = || tokio::time::sleep(Duration::from_millis(120));
+
+        assert!(!is_probing());
+        sample_while(pid, sleep()).await;
+
+        let ((), peak) = with_probe(async {
+            assert!(is_probing());
+            sample_while(pid, sleep()).await
+        }).await;
+        if cfg!(any(target_os = "linux", windows)) {
+            assert!(peak.unwrap() > 1024 * 1024, "{:?}", peak);
+        }
+    }
+}
@@ -1,13 +1,297 @@
 // Transcription module - Provider trait and implementations
 
+pub mod chain;
+pub mod command;
+pub mod context;
+pub mod lifecycle;
+pub mod memory;
+pub mod onnx;
+pub mod progress;
+pub mod routing;
+pub mod shadow;
+pub mod streaming;
+pub mod two_pass;
+pub mod validator;
+pub mod whisper_binary;
+pub mod whisper_params;
+pub mod wyoming;
+
+use crate::errors::TranscriptionError;
 use async_trait::async_trait;
+use tokio::sync::mpsc;
+
+pub use chain::{ChainOutcome, ProviderChain};
+pub use command::CommandProvider;
+pub use context::DictationContext;
+pub use lifecycle::{Capabilities, HealthStatus, Languages, ProviderReport};
+pub use onnx::OnnxProvider;
+pub use progress::TranscriptionProgress;
+pub use routing::Router;
+pub use shadow::Shadow;
+pub use streaming::StreamingCloudProvider;
+pub use two_pass::TwoPass;
+pub use validator::{TranscriptValidator, Validation};
+pub use whisper_binary::WhisperBinary;
+pub use whisper_params::WhisperParams;
+pub use wyoming::WyomingProvider;
 
 pub type AudioBuffer = Vec<f32>;
 
+/// Converts float samples to mono little-endian 16-bit PCM, the wire format most servers expect
+pub(crate) fn pcm16_le_bytes(samples: &[f32]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(samples.len() * 2);
+    for &s in samples {
+        bytes.extend_from_slice(&((s.clamp(-1.0, 1.0) * 32767.0) as i16).to_le_bytes());
+    }
+    bytes
+}
+
+/// Audio frames pushed by the recorder while the hotkey is held.
+/// The stream ends when the sender is dropped (recording stopped).
+pub type AudioFrameReceiver = mpsc::UnboundedReceiver<AudioBuffer>;
+
+/// Partial results reported by streaming providers before the final text is ready
+#[derive(Debug, Clone, PartialEq)]
+pub enum TranscriptEvent {
+    /// Current hypothesis for the segment being spoken, replaced by the next interim
+    Interim(String),
+    /// A committed segment that will not change anymore
+    Final(String),
+}
+
+pub type TranscriptEventSender = mpsc::UnboundedSender<TranscriptEvent>;
+
+/// Writes mono 16-bit PCM WAV, the input format whisper.cpp and most STT CLIs accept
+pub(crate) fn write_wav_file(audio: &[f32], sample_rate: u32, path: &std::path::Path) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    use std::io::Write;
+    
+    // Convert f32 samples to i16
+    let samples_i16: Vec<i16> = audio.iter()
+        .map(|&s| (s.clamp(-1.0, 1.0) * 32767.0) as i16)
+        .collect();
+    
+    let num_channels: u16 = 1; // Mono
+    let bits_per_sample: u16 = 16;
+    let num_samples = samples_i16.len() as u32;
+    let byte_rate = sample_rate * num_channels as u32 * (bits_per_sample / 8) as u32;
+    let block_align = num_channels * (bits_per_sample / 8);
+    let data_size = num_samples * (bits_per_sample / 8) as u32;
+    let file_size = 36 + data_size;
+    
+    let mut file = std::fs::File::create(path)?;
+    
+    // RIFF header
+    file.write_all(b"RIFF")?;
+    file.write_all(&file_size.to_le_bytes())?;
+    file.write_all(b"WAVE")?;
+    
+    // fmt chunk
+    file.write_all(b"fmt ")?;
+    file.write_all(&16u32.to_le_bytes())?; // Subchunk1Size (16 for PCM)
+    file.write_all(&1u16.to_le_bytes())?; // AudioFormat (1 = PCM)
+    file.write_all(&num_channels.to_le_bytes())?; // NumChannels
+    file.write_all(&sample_rate.to_le_bytes())?; // SampleRate
+    file.write_all(&byte_rate.to_le_bytes())?; // ByteRate
+    file.write_all(&block_align.to_le_bytes())?; // BlockAlign
+    file.write_all(&bits_per_sample.to_le_bytes())?; // BitsPerSample
+    
+    // data chunk
+    file.write_all(b"data")?;
+    file.write_all(&data_size.to_le_bytes())?;
+    
+    // Write samples
+    for sample in samples_i16 {
+        file.write_all(&sample.to_le_bytes())?;
+    }
+    
+    file.flush()?;
+
+    Ok(())
+}
+
+/// Reads a PCM16 or float32 WAV file, downmixed to mono. Returns samples and sample rate.
+pub fn read_wav_file(path: &std::path::Path) -> Result<(AudioBuffer, u32), Box<dyn std::error::Error + Send + Sync>> {
+    let bytes = std::fs::read(path)?;
+    if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+        return Err(format!("{} is not a WAV file", path.display()).into());
+    }
+
+    let mut format: Option<(u16, u16, u32, u16)> = None;
+    let mut pos = 12;
+    while pos + 8 <= bytes.len() {
+        let id = &bytes[pos..pos + 4];
+        let size = u32::from_le_bytes(bytes[pos + 4..pos + 8].try_into()?) as usize;
+        let body = &bytes[pos + 8..(pos + 8 + size).min(bytes.len())];
+
+        if id == b"fmt " && body.len() >= 16 {
+            let audio_format = u16::from_le_bytes([body[0], body[1]]);
+            let channels = u16::from_le_bytes([body[2], body[3]]);
+            let sample_rate = u32::from_le_bytes(body[4..8].try_into()?);
+            let bits = u16::from_le_bytes([body[14], body[15]]);
+            format = Some((audio_format, channels, sample_rate, bits));
+        } else if id == b"data" {
+            let (audio_format, channels, sample_rate, bits) = format.ok_or("WAV data chunk before fmt chunk")?;
+            let interleaved: Vec<f32> = match (audio_format, bits) {
+                (1, 16) => body.chunks_exact(2).map(|b| i16::from_le_bytes([b[0], b[1]]) as f32 / 32768.0).collect(),
+                (3, 32) => body.chunks_exact(4).map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]])).collect(),
+                _ => return Err(format!("Unsupported WAV encoding (format {}, {} bits)", audio_format, bits).into()),
+            };
+            let channels = channels.max(1) as usize;
+            let mono = interleaved.chunks(channels).map(|c| c.iter().sum::<f32>() / channels as f32).collect();
+            return Ok((mono, sample_rate));
+        }
+
+        // Chunks are padded to an even size
+        pos += 8 + size + (size & 1);
+    }
+
+    Err(format!("{} has no audio data", path.display()).into())
+}
+
+/// Naive decimation to 16kHz (Whisper requirement)
+pub(crate) fn resample_to_16k(audio: AudioBuffer, sample_rate: u32) -> AudioBuffer {
+    if sample_rate == 16000 {
+        return audio;
+    }
+    log::info!("Resampling from {}Hz to 16kHz", sample_rate);
+    let ratio = sample_rate as f32 / 16000.0;
+    audio.iter()
+        .step_by(ratio.ceil() as usize)
+        .copied()
+        .collect()
+}
+
+/// Builds a `Command` that won't flash a console window on Windows
+pub(crate) fn hidden_command(program: &str) -> std::process::Command {
+    #[allow(unused_mut)]
+    let mut command = std::process::Command::new(program);
+    
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+    
+    command
+}
+
+/// Temp file removed on drop, so cancelled transcriptions don't leave audio behind
+pub(crate) struct TempFile(std::path::PathBuf);
+
+impl TempFile {
+    pub fn new(path: std::path::PathBuf) -> Self {
+        Self(path)
+    }
+    
+    pub fn path(&self) -> &std::path::Path {
+        &self.0
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        if self.0.exists() {
+            let _ = std::fs::remove_file(&self.0);
+            log::debug!("Removed temp file: {}", self.0.display());
+        }
+    }
+}
+
+/// Like `Child::wait_with_output`, but reads stderr line by line as it arrives and
+/// forwards whisper.cpp progress lines to the HUD instead of waiting for exit
+pub(crate) async fn wait_with_progress(mut child: tokio::process::Child, provider: &str) -> std::io::Result<std::process::Output> {
+    use tokio::io::{AsyncBufReadExt, AsyncReadExt};
+
+    let started = std::time::Instant::now();
+    let mut stdout_pipe = child.stdout.take().ok_or_else(|| std::io::Error::other("stdout not piped"))?;
+    let stderr_pipe = child.stderr.take().ok_or_else(|| std::io::Error::other("stderr not piped"))?;
+
+    let read_stdout = async {
+        let mut stdout = Vec::new();
+        stdout_pipe.read_to_end(&mut stdout).await.map(|_| stdout)
+    };
+    let read_stderr = async {
+        // Raw bytes, not `lines()`: whisper.cpp prints paths in the ANSI codepage on Windows,
+        // and stopping at the first non-UTF-8 line would lose stderr and block the child
+        let mut stderr = Vec::new();
+        let mut reader = tokio::io::BufReader::new(stderr_pipe);
+        let mut line = Vec::new();
+        while let Ok(read) = reader.read_until(b'\n', &mut line).await {
+            if read == 0 {
+                break;
+            }
+            if let Some(percent) = progress::parse_whisper_progress(&String::from_utf8_lossy(&line)) {
+                log::debug!("{} progress: {}% after {:?}", provider, percent, started.elapsed());
+                progress::report(provider, percent, started);
+            }
+            stderr.append(&mut line);
+        }
+        stderr
+    };
+
+    // Benchmarks probe the child's memory; dictations skip the sampling
+    let (stdout, stderr) = match child.id().filter(|_| memory::is_probing()) {
+        Some(pid) => memory::sample_while(pid, async { tokio::join!(read_stdout, read_stderr) }).await,
+        None => tokio::join!(read_stdout, read_stderr),
+    };
+    let status = child.wait().await?;
+    Ok(std::process::Output { status, stdout: stdout?, stderr })
+}
+
+/// Mean per-segment no-speech probability from whisper.cpp's `-ojf` output.
+/// Builds that don't report it return `None` and the energy check is used instead.
+fn read_no_speech_prob(json_path: &std::path::Path) -> Option<f32> {
+    let json: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(json_path).ok()?).ok()?;
+    let probs: Vec<f32> = json["transcription"].as_array()?
+        .iter()
+        .filter_map(|segment| segment["no_speech_prob"].as_f64())
+        .map(|p| p as f32)
+        .collect();
+    if probs.is_empty() {
+        None
+    } else {
+        Some(probs.iter().sum::<f32>() / probs.len() as f32)
+    }
+}
+
 #[async_trait]
 pub trait TranscriptionProvider: Send + Sync {
     async fn transcribe(&self, audio: AudioBuffer, sample_rate: u32) -> Result<String, Box<dyn std::error::Error + Send + Sync>>;
     fn supports_streaming(&self) -> bool;
+    
+    /// Identifier matching the `provider` config value; recorded in history
+    fn name(&self) -> &'static str;
+
+    /// Transcribe while audio is still being captured. Only used when `supports_streaming()`
+    /// is true; the default buffers all frames and hands them to `transcribe` once capture ends.
+    async fn transcribe_stream(&self, mut frames: AudioFrameReceiver, sample_rate: u32, _events: TranscriptEventSender) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        let mut audio = AudioBuffer::new();
+        while let Some(frame) = frames.recv().await {
+            audio.extend_from_slice(&frame);
+        }
+        self.transcribe(audio, sample_rate).await
+    }
+
+    /// Loads whatever makes the first dictation slow (models, caches). Called once at launch.
+    async fn warm_up(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        Ok(())
+    }
+
+    /// Cheap check that the provider can run right now, without transcribing anything
+    async fn health_check(&self) -> HealthStatus {
+        HealthStatus::degraded("No health check available for this provider")
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            languages: Languages::Unknown,
+            streaming: self.supports_streaming(),
+            timestamps: false,
+            translation: false,
+            context: false,
+        }
+    }
 }
 
 // Mock provider for MVP testing
@@ -28,107 +312,46 @@ impl TranscriptionProvider for MockProvider {
         Ok("This is a test transcription from the mock provider.".to_string())
     }
     
+    fn name(&self) -> &'static str {
+        "Mock"
+    }
+    
     fn supports_streaming(&self) -> bool {
         false
     }
+
+    async fn health_check(&self) -> HealthStatus {
+        HealthStatus::ready("Mock provider (testing only)")
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities { languages: Languages::Any, streaming: false, timestamps: false, translation: false, context: false }
+    }
 }
 
 // Whisper.cpp provider for local transcription (subprocess-based, no LLVM required)
 pub struct WhisperProvider {
     model_path: String,
-    whisper_exe_path: String,
+    /// `whisper_binary_path` from config; empty = bundled copy, then PATH
+    binary_path: String,
+    params: WhisperParams,
 }
 
 impl WhisperProvider {
-    pub fn new(model_path: String) -> Self {
-        // Determine whisper.cpp executable path
-        // In production, this will be bundled with the app in resources
-        let whisper_exe_path = if cfg!(windows) {
-            // Try to find bundled whisper.exe in resources
-            let mut exe_path = std::env::current_exe()
-                .unwrap_or_else(|_| std::path::PathBuf::from("."));
-            
-            // Remove the executable name to get the directory
-            exe_path.pop();
-            
-            // Check common Tauri resource locations
-            let possible_paths = vec![
-                exe_path.join("resources").join("whisper.exe"),  // Installed app
-                exe_path.join("..").join("resources").join("whisper.exe"),  // Dev mode
-                exe_path.join("whisper.exe"),  // Same directory
-            ];
-            
-            let found_path = possible_paths.iter()
-                .find(|p| p.exists())
-                .cloned();
-            
-            if let Some(path) = found_path {
-                log::info!("Found bundled whisper.exe at: {}", path.display());
-                path.to_string_lossy().to_string()
-            } else {
-                log::warn!("Bundled whisper.exe not found, trying PATH");
-                // Fallback: assume whisper.exe is in PATH
-                "whisper.exe".to_string()
-            }
-        } else {
-            "whisper".to_string()
-        };
-        
+    pub fn new(model_path: String, params: WhisperParams, binary_path: String) -> Self {
+        match whisper_binary::locate(&binary_path) {
+            Ok((path, source)) => log::info!("Whisper executable path: {} ({:?})", path.display(), source),
+            Err(e) => log::warn!("{}", e),
+        }
         log::info!("WhisperProvider initialized with model: {}", model_path);
-        log::info!("Whisper executable path: {}", whisper_exe_path);
+        log::info!("Whisper decoding params: {:?}", params);
         
         Self {
             model_path,
-            whisper_exe_path,
+            binary_path,
+            params,
         }
     }
-    
-    fn write_wav_file(&self, audio: &[f32], sample_rate: u32, path: &std::path::Path) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        use std::io::Write;
-        
-        // Convert f32 samples to i16
-        let samples_i16: Vec<i16> = audio.iter()
-            .map(|&s| (s.clamp(-1.0, 1.0) * 32767.0) as i16)
-            .collect();
-        
-        let num_channels: u16 = 1; // Mono
-        let bits_per_sample: u16 = 16;
-        let num_samples = samples_i16.len() as u32;
-        let byte_rate = sample_rate * num_channels as u32 * (bits_per_sample / 8) as u32;
-        let block_align = num_channels * (bits_per_sample / 8);
-        let data_size = num_samples * (bits_per_sample / 8) as u32;
-        let file_size = 36 + data_size;
-        
-        let mut file = std::fs::File::create(path)?;
-        
-        // RIFF header
-        file.write_all(b"RIFF")?;
-        file.write_all(&file_size.to_le_bytes())?;
-        file.write_all(b"WAVE")?;
-        
-        // fmt chunk
-        file.write_all(b"fmt ")?;
-        file.write_all(&16u32.to_le_bytes())?; // Subchunk1Size (16 for PCM)
-        file.write_all(&1u16.to_le_bytes())?; // AudioFormat (1 = PCM)
-        file.write_all(&num_channels.to_le_bytes())?; // NumChannels
-        file.write_all(&sample_rate.to_le_bytes())?; // SampleRate
-        file.write_all(&byte_rate.to_le_bytes())?; // ByteRate
-        file.write_all(&block_align.to_le_bytes())?; // BlockAlign
-        file.write_all(&bits_per_sample.to_le_bytes())?; // BitsPerSample
-        
-        // data chunk
-        file.write_all(b"data")?;
-        file.write_all(&data_size.to_le_bytes())?;
-        
-        // Write samples
-        for sample in samples_i16 {
-            file.write_all(&sample.to_le_bytes())?;
-        }
-        
-        file.flush()?;
-        
-        Ok(())
-    }
 }
 
 #[async_trait]
@@ -136,64 +359,64 @@ impl TranscriptionProvider for WhisperProvider {
     async fn transcribe(&self, audio: AudioBuffer, sample_rate: u32) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
         // Check if model exists
         if !std::path::Path::new(&self.model_path).exists() {
-            return Err(format!("Whisper model not found at: {}\n\nPlease download the model from Settings.", self.model_path).into());
+            return Err(TranscriptionError::ModelNotFound { path: self.model_path.clone().into() }.into());
         }
         
         log::info!("Transcribing {} samples at {}Hz with Whisper.cpp", audio.len(), sample_rate);
         
         // Resample to 16kHz if needed (Whisper requirement)
-        let audio_16k = if sample_rate != 16000 {
-            log::info!("Resampling from {}Hz to 16kHz", sample_rate);
-            let ratio = sample_rate as f32 / 16000.0;
-            audio.iter()
-                .step_by(ratio.ceil() as usize)
-                .copied()
-                .collect()
-        } else {
-            audio
-        };
+        let audio_16k = resample_to_16k(audio, sample_rate);
         
         // Create temp WAV file
         let temp_dir = std::env::temp_dir();
-        let audio_file = temp_dir.join(format!("vanta_audio_{}.wav", uuid::Uuid::new_v4()));
+        let audio_file = TempFile::new(temp_dir.join(format!("vanta_audio_{}.wav", uuid::Uuid::new_v4())));
+        // Full JSON output lands next to the WAV; used only for per-segment metadata
+        let json_file = TempFile::new(audio_file.path().with_extension("json"));
         
-        log::debug!("Writing audio to temp file: {}", audio_file.display());
-        self.write_wav_file(&audio_16k, 16000, &audio_file)?;
+        log::debug!("Writing audio to temp file: {}", audio_file.path().display());
+        write_wav_file(&audio_16k, 16000, audio_file.path())?;
         
-        // Run whisper.cpp as subprocess
+        // Run whisper.cpp as subprocess. Dropping this future (cancel) kills the child
+        // and the TempFile guards remove the WAV/JSON.
         let model_path = self.model_path.clone();
-        let whisper_exe = self.whisper_exe_path.clone();
-        let audio_file_clone = audio_file.clone();
+        let binary = whisper_binary::resolve(&self.binary_path).await?;
+        let whisper_exe = binary.path.to_string_lossy().to_string();
+        let audio_file_clone = audio_file.path().to_path_buf();
+        let mut decoding_args = self.params.to_args();
+        let mut optional_flags = self.params.optional_flags();
+        if let Some(prompt) = context::current_prompt() {
+            decoding_args.push("--prompt".to_string());
+            decoding_args.push(prompt);
+            optional_flags.push("--prompt");
+        }
+        binary.check_flags(&optional_flags)?;
         
-        let result = tokio::task::spawn_blocking(move || -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        let result: Result<String, Box<dyn std::error::Error + Send + Sync>> = async move {
             // Log FULL command line for debugging
             log::info!("=== WHISPER EXECUTION DEBUG ===");
             log::info!("Whisper exe: {}", whisper_exe);
             log::info!("Model path: {}", model_path);
             log::info!("Audio file: {}", audio_file_clone.display());
+            log::info!("Decoding args: {}", decoding_args.join(" "));
             
             // On Windows, hide the console window
-            #[cfg(windows)]
-            let output = {
-                use std::os::windows::process::CommandExt;
-                const CREATE_NO_WINDOW: u32 = 0x08000000;
-                
-                std::process::Command::new(&whisper_exe)
-                    .arg("-m").arg(&model_path)
-                    .arg("-f").arg(audio_file_clone.to_str().unwrap())
-                    .arg("-l").arg("en")
-                    .arg("-nt") // --no-timestamps
-                    .creation_flags(CREATE_NO_WINDOW)
-                    .output()
-            };
-            
-            #[cfg(not(windows))]
-            let output = std::process::Command::new(&whisper_exe)
+            let child = tokio::process::Command::from(hidden_command(&whisper_exe))
                 .arg("-m").arg(&model_path)
                 .arg("-f").arg(audio_file_clone.to_str().unwrap())
                 .arg("-l").arg("en")
                 .arg("-nt") // --no-timestamps
-                .output();
+                .arg("-ojf") // --output-json-full
+                .arg("-of").arg(audio_file_clone.with_extension(""))
+                .arg("-pp") // --print-progress (stderr)
+                .args(&decoding_args)
+                .stdout(std::process::Stdio::piped())
+                .stderr(std::process::Stdio::piped())
+                .kill_on_drop(true)
+                .spawn();
+            let output = match child {
+                Ok(child) => wait_with_progress(child, "Whisper").await,
+                Err(e) => Err(e),
+            };
             
             match output {
                 Ok(output) => {
@@ -235,36 +458,105 @@ impl TranscriptionProvider for WhisperProvider {
                             log::error!("2. Audio file is invalid");
                             log::error!("3. Whisper detected silence");
                             log::error!("4. Whisper crashed silently");
-                            Err("Whisper returned empty transcription".into())
+                            Err(TranscriptionError::EmptyResult { provider: "Whisper".to_string() }.into())
                         } else {
                             Ok(cleaned_text)
                         }
                     } else {
                         log::error!("Whisper.cpp failed with exit code: {}", exit_code);
-                        Err(format!("Whisper.cpp failed: {}", stderr).into())
+                        Err(TranscriptionError::EngineFailed { engine: "Whisper.cpp".to_string(), details: stderr.trim().to_string() }.into())
                     }
                 }
                 Err(e) => {
                     log::error!("Failed to execute whisper.cpp: {}", e);
                     log::error!("Whisper exe path: {}", whisper_exe);
                     log::error!("Make sure whisper.exe exists and has execute permissions");
-                    Err(format!("Failed to execute whisper.cpp: {}\n\nMake sure whisper.exe is bundled with the app or in PATH.", e).into())
+                    Err(TranscriptionError::EngineNotFound { program: whisper_exe.clone(), details: e.to_string() }.into())
                 }
             }
-        }).await?;
+        }.await;
         
-        // Clean up temp file
-        let _ = std::fs::remove_file(&audio_file);
+        // Temp files are removed when the guards drop
+        let no_speech_prob = read_no_speech_prob(json_file.path());
+        drop((audio_file, json_file));
         
         let transcription = result?;
+        
+        // Reject hallucinations ("Thanks for watching.") and repetition loops
+        let params = self.params.sanitized();
+        let validator = TranscriptValidator {
+            no_speech_threshold: params.no_speech_threshold,
+            min_speech_rms: params.min_speech_rms,
+            ..Default::default()
+        };
+        let transcription = match validator.validate(&transcription, &audio_16k, no_speech_prob) {
+            Validation::Accepted(text) => text,
+            Validation::Trimmed { text, reasons } => {
+                log::warn!("Whisper output trimmed: {}", reasons.join("; "));
+                text
+            }
+            Validation::Dropped { reason } => {
+                log::warn!("Whisper output dropped: {}", reason);
+                return Err(TranscriptionError::NoSpeech { reason }.into());
+            }
+        };
         log::info!("Whisper transcription complete: '{}' (len: {})", transcription, transcription.len());
         
         Ok(transcription)
     }
     
+    fn name(&self) -> &'static str {
+        "Whisper"
+    }
+    
     fn supports_streaming(&self) -> bool {
         false
     }
+
+    /// Reads the model once so the OS has it cached before the first dictation
+    async fn warm_up(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let model_path = self.model_path.clone();
+        tokio::task::spawn_blocking(move || -> std::io::Result<u64> {
+            use std::io::Read;
+            let mut file = std::fs::File::open(&model_path)?;
+            let mut buffer = vec![0u8; 1 << 20];
+            let mut total = 0u64;
+            loop {
+                match file.read(&mut buffer)? {
+                    0 => return Ok(total),
+                    n => total += n as u64,
+                }
+            }
+        })
+        .await??;
+        Ok(())
+    }
+
+    async fn health_check(&self) -> HealthStatus {
+        if !std::path::Path::new(&self.model_path).exists() {
+            return HealthStatus::unavailable(&TranscriptionError::ModelNotFound { path: self.model_path.clone().into() });
+        }
+        let binary = whisper_binary::resolve(&self.binary_path).await
+            .and_then(|binary| binary.check_flags(&self.params.optional_flags()).map(|_| binary));
+        match binary {
+            Ok(binary) => HealthStatus::ready(format!("Model {} with {}", self.model_path, binary.describe())),
+            Err(e) => HealthStatus::unavailable(&e),
+        }
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        // English-only checkpoints are named like ggml-base.en.bin
+        let english_only = std::path::Path::new(&self.model_path)
+            .file_name()
+            .is_some_and(|name| name.to_string_lossy().contains(".en"));
+        Capabilities {
+            languages: if english_only { Languages::Only(vec!["en".to_string()]) } else { Languages::Any },
+            streaming: false,
+            timestamps: true,
+            translation: !english_only,
+            context: true,
+        }
+    }
 }
 
 // Cloud STT provider stub
@@ -282,7 +574,7 @@ impl CloudSTTProvider {
 impl TranscriptionProvider for CloudSTTProvider {
     async fn transcribe(&self, _audio: AudioBuffer, _sample_rate: u32) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
         if self.api_key.is_none() {
-            return Err("Cloud STT API key not configured".into());
+            return Err(TranscriptionError::NotConfigured { provider: "Cloud".to_string(), missing: "API key".to_string() }.into());
         }
         
         // TODO: Implement actual cloud API call
@@ -290,7 +582,18 @@ impl TranscriptionProvider for CloudSTTProvider {
         Ok("Cloud STT transcription (not implemented yet)".to_string())
     }
     
+    fn name(&self) -> &'static str {
+        "Cloud"
+    }
+    
     fn supports_streaming(&self) -> bool {
         true
     }
+
+    async fn health_check(&self) -> HealthStatus {
+        match self.api_key {
+            Some(_) => HealthStatus::degraded("Cloud STT is not implemented yet"),
+            None => HealthStatus::unavailable(&TranscriptionError::NotConfigured { provider: "Cloud".to_string(), missing: "API key".to_string() }),
+        }
+    }
 }
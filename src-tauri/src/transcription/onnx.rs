@@ -0,0 +1,271 @@
+// ONNX provider - CPU-only local transcription for non-Whisper models
+//
+// Currently runs Moonshine encoder/decoder exports (onnx-community/moonshine-*-ONNX):
+//   encoder_model.onnx   input_values [1, samples] -> last_hidden_state [1, frames, dim]
+//   decoder_model.onnx   input_ids [1, seq] + encoder_hidden_states -> logits [1, seq, vocab]
+//   tokenizer.json       Hugging Face tokenizer, only the vocabulary is used for decoding
+//
+// Decoding is greedy without a KV cache; utterances are short so the quadratic cost is small.
+
+use super::lifecycle::{Capabilities, HealthStatus, Languages};
+use super::{resample_to_16k, AudioBuffer, TranscriptionProvider};
+use crate::errors::TranscriptionError;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+const BOS_TOKEN: i64 = 1;
+const EOS_TOKEN: i64 = 2;
+
+/// Moonshine emits at most ~6 tokens per second of speech
+const TOKENS_PER_SECOND: f32 = 6.0;
+
+/// Decode-only view of a Hugging Face `tokenizer.json` (SentencePiece-style BPE)
+pub struct Tokenizer {
+    tokens: HashMap<i64, String>,
+    special: std::collections::HashSet<i64>,
+}
+
+impl Tokenizer {
+    pub fn from_json(json: &str) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let value: serde_json::Value = serde_json::from_str(json)?;
+        let vocab = value["model"]["vocab"].as_object().ok_or("tokenizer.json has no model.vocab")?;
+
+        let mut tokens: HashMap<i64, String> = vocab.iter()
+            .filter_map(|(token, id)| id.as_i64().map(|id| (id, token.clone())))
+            .collect();
+        let mut special = std::collections::HashSet::new();
+
+        for added in value["added_tokens"].as_array().into_iter().flatten() {
+            if let (Some(id), Some(content)) = (added["id"].as_i64(), added["content"].as_str()) {
+                tokens.insert(id, content.to_string());
+                if added["special"].as_bool().unwrap_or(false) {
+                    special.insert(id);
+                }
+            }
+        }
+
+        Ok(Self { tokens, special })
+    }
+
+    pub fn decode(&self, ids: &[i64]) -> String {
+        let mut bytes = Vec::new();
+        for id in ids {
+            if self.special.contains(id) {
+                continue;
+            }
+            let Some(token) = self.tokens.get(id) else { continue };
+
+            // Byte-fallback tokens look like <0x0A>
+            if let Some(hex) = token.strip_prefix("<0x").and_then(|t| t.strip_suffix('>')) {
+                if let Ok(byte) = u8::from_str_radix(hex, 16) {
+                    bytes.push(byte);
+                    continue;
+                }
+            }
+            bytes.extend_from_slice(token.replace('\u{2581}', " ").as_bytes());
+        }
+        String::from_utf8_lossy(&bytes).trim().to_string()
+    }
+}
+
+#[cfg(feature = "onnx")]
+struct MoonshineModel {
+    encoder: ort::session::Session,
+    decoder: ort::session::Session,
+    tokenizer: Tokenizer,
+}
+
+#[cfg(feature = "onnx")]
+impl MoonshineModel {
+    fn load(model_dir: &Path, threads: usize) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        use ort::session::{builder::GraphOptimizationLevel, Session};
+
+        let open = |file: &str| -> Result<Session, Box<dyn std::error::Error + Send + Sync>> {
+            let path = model_dir.join(file);
+            if !path.exists() {
+                return Err(TranscriptionError::ModelNotFound { path }.into());
+            }
+            Ok(Session::builder()?
+                .with_optimization_level(GraphOptimizationLevel::Level3)?
+                .with_intra_threads(threads)?
+                .commit_from_file(path)?)
+        };
+
+        let tokenizer = Tokenizer::from_json(&std::fs::read_to_string(model_dir.join("tokenizer.json"))?)?;
+
+        Ok(Self {
+            encoder: open("encoder_model.onnx")?,
+            decoder: open("decoder_model.onnx")?,
+            tokenizer,
+        })
+    }
+
+    fn transcribe(&mut self, audio: &[f32]) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        use ort::value::TensorRef;
+
+        // 1. Encode the whole utterance once
+        let encoder_outputs = self.encoder.run(ort::inputs![
+            "input_values" => TensorRef::from_array_view(([1usize, audio.len()], audio))?
+        ])?;
+        let (shape, hidden) = encoder_outputs["last_hidden_state"].try_extract_tensor::<f32>()?;
+        let hidden_shape: Vec<usize> = shape.iter().map(|&d| d as usize).collect();
+        let hidden = hidden.to_vec();
+        drop(encoder_outputs);
+
+        // 2. Greedy decode until EOS or the length budget runs out
+        let max_tokens = ((audio.len() as f32 / 16000.0) * TOKENS_PER_SECOND).ceil() as usize + 4;
+        let mut tokens = vec![BOS_TOKEN];
+
+        for _ in 0..max_tokens {
+            let outputs = self.decoder.run(ort::inputs![
+                "input_ids" => TensorRef::from_array_view(([1usize, tokens.len()], tokens.as_slice()))?,
+                "encoder_hidden_states" => TensorRef::from_array_view((hidden_shape.clone(), hidden.as_slice()))?
+            ])?;
+            let (shape, logits) = outputs["logits"].try_extract_tensor::<f32>()?;
+            let vocab = shape[2] as usize;
+            let last = &logits[(tokens.len() - 1) * vocab..tokens.len() * vocab];
+
+            let next = last.iter()
+                .enumerate()
+                .max_by(|a, b| a.1.total_cmp(b.1))
+                .map(|(id, _)| id as i64)
+                .unwrap_or(EOS_TOKEN);
+            if next == EOS_TOKEN {
+                break;
+            }
+            tokens.push(next);
+        }
+
+        Ok(self.tokenizer.decode(&tokens[1..]))
+    }
+}
+
+#[cfg(not(feature = "onnx"))]
+struct MoonshineModel;
+
+#[cfg(not(feature = "onnx"))]
+impl MoonshineModel {
+    fn load(_model_dir: &Path, _threads: usize) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        Err(TranscriptionError::NotConfigured {
+            provider: "Onnx".to_string(),
+            missing: "this build was compiled without the `onnx` feature".to_string(),
+        }.into())
+    }
+
+    fn transcribe(&mut self, _audio: &[f32]) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        unreachable!("MoonshineModel cannot be loaded without the `onnx` feature")
+    }
+}
+
+pub struct OnnxProvider {
+    model_dir: PathBuf,
+    threads: usize,
+    // Loaded on first use; sessions are reused across dictations
+    model: Arc<Mutex<Option<MoonshineModel>>>,
+}
+
+impl OnnxProvider {
+    pub fn new(model_dir: PathBuf) -> Self {
+        let threads = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(4);
+        log::info!("OnnxProvider initialized with model dir: {} ({} threads)", model_dir.display(), threads);
+        Self {
+            model_dir,
+            threads,
+            model: Arc::new(Mutex::new(None)),
+        }
+    }
+}
+
+#[async_trait]
+impl TranscriptionProvider for OnnxProvider {
+    async fn transcribe(&self, audio: AudioBuffer, sample_rate: u32) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        log::info!("Transcribing {} samples at {}Hz with ONNX model", audio.len(), sample_rate);
+
+        let audio_16k = resample_to_16k(audio, sample_rate);
+        let model = self.model.clone();
+        let model_dir = self.model_dir.clone();
+        let threads = self.threads;
+
+        let transcription = tokio::task::spawn_blocking(move || -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+            let mut guard = model.lock().map_err(|_| "ONNX model lock poisoned")?;
+            if guard.is_none() {
+                let load_start = std::time::Instant::now();
+                *guard = Some(MoonshineModel::load(&model_dir, threads)?);
+                log::info!("ONNX model loaded in {:?}", load_start.elapsed());
+            }
+            match guard.as_mut() {
+                Some(model) => model.transcribe(&audio_16k),
+                None => Err("ONNX model failed to load".into()),
+            }
+        }).await??;
+
+        log::info!("ONNX transcription complete: '{}' (len: {})", transcription, transcription.len());
+        Ok(transcription)
+    }
+
+    fn name(&self) -> &'static str {
+        "Onnx"
+    }
+
+    fn supports_streaming(&self) -> bool {
+        false
+    }
+
+    /// Loads the ONNX sessions now instead of on the first dictation
+    async fn warm_up(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let model = self.model.clone();
+        let model_dir = self.model_dir.clone();
+        let threads = self.threads;
+        tokio::task::spawn_blocking(move || -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+            let mut guard = model.lock().map_err(|_| "ONNX model lock poisoned")?;
+            if guard.is_none() {
+                *guard = Some(MoonshineModel::load(&model_dir, threads)?);
+            }
+            Ok(())
+        }).await?
+    }
+
+    async fn health_check(&self) -> HealthStatus {
+        if !cfg!(feature = "onnx") {
+            return HealthStatus::unavailable(&TranscriptionError::NotConfigured {
+                provider: "Onnx".to_string(),
+                missing: "this build was compiled without the `onnx` feature".to_string(),
+            });
+        }
+        if !self.model_dir.exists() {
+            return HealthStatus::unavailable(&TranscriptionError::ModelNotFound { path: self.model_dir.clone() });
+        }
+        HealthStatus::ready(format!("Model {}", self.model_dir.display()))
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        // Moonshine checkpoints are English-only
+        Capabilities { languages: Languages::Only(vec!["en".to_string()]), streaming: false, timestamps: false, translation: false, context: false }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenizer_decode() {
+        let json = r#"{
+            "added_tokens": [
+                {"id": 1, "content": "<s>", "special": true},
+                {"id": 2, "content": "</s>", "special": true}
+            ],
+            "model": {"vocab": {"<s>": 1, "</s>": 2, "▁Hello": 3, ",": 4, "▁wor": 5, "ld": 6, "<0x21>": 7}}
+        }"#;
+        let tokenizer = Tokenizer::from_json(json).unwrap();
+        assert_eq!(tokenizer.decode(&[1, 3, 4, 5, 6, 7, 2]), "Hello, world!");
+    }
+
+    #[tokio::test]
+    async fn test_missing_model_is_reported() {
+        let provider = OnnxProvider::new(std::env::temp_dir().join("vanta-missing-onnx-model"));
+        assert!(provider.transcribe(vec![0.0; 1600], 16000).await.is_err());
+    }
+}
@@ -0,0 +1,94 @@
+// Transcription progress - percent-done reports from long-running providers
+//
+// The pipeline scopes a sender around each job with `with_progress`; providers call
+// `report` from anywhere inside that task without it being threaded through the chain.
+// Outside a scope (benchmarks, background refinement) reports are silently dropped.
+
+use serde::Serialize;
+use std::future::Future;
+use std::time::Instant;
+use tokio::sync::mpsc;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TranscriptionProgress {
+    pub provider: String,
+    pub percent: u8,
+    pub elapsed_ms: u64,
+}
+
+pub type ProgressSender = mpsc::UnboundedSender<TranscriptionProgress>;
+
+tokio::task_local! {
+    static PROGRESS: ProgressSender;
+}
+
+/// Runs `future` with `sender` receiving every progress report made inside it
+pub async fn with_progress<F: Future>(sender: ProgressSender, future: F) -> F::Output {
+    PROGRESS.scope(sender, future).await
+}
+
+pub fn report(provider: &str, percent: u8, started: Instant) {
+    let _ = PROGRESS.try_with(|sender| {
+        let _ = sender.send(TranscriptionProgress {
+            provider: provider.to_string(),
+            percent: percent.min(100),
+            elapsed_ms: started.elapsed().as_millis() as u64,
+        });
+    });
+}
+
+/// Percent from whisper.cpp's `-pp` stderr output, e.g.
+/// "whisper_print_progress_callback: progress =  45%"
+pub fn parse_whisper_progress(line: &str) -> Option<u8> {
+    let rest = &line[line.find("progress =")? + "progress =".len()..];
+    rest.trim().trim_end_matches('%').trim().parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_whisper_progress() {
+        assert_eq!(parse_whisper_progress("whisper_print_progress_callback: progress =  45%"), Some(45));
+        assert_eq!(parse_whisper_progress("whisper_full_with_state: progress = 100%"), Some(100));
+        assert_eq!(parse_whisper_progress("whisper_init_from_file: loading model"), None);
+    }
+
+    #[tokio::test]
+    async fn test_reports_reach_scoped_sender_only() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let started = Instant::now();
+        with_progress(tx, async { report("Whisper", 30, started) }).await;
+        report("Whisper", 60, started);
+
+        assert_eq!(rx.recv().await.map(|p| p.percent), Some(30));
+        assert!(rx.recv().await.is_none());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_progress_is_read_while_the_child_runs() {
+        // The Latin-1 path line is not valid UTF-8 and must not stop the reader
+        let script = "echo 'progress =  50%' >&2; sleep 0.3; printf 'C:\\Users\\Ren\\351\\n' >&2; echo 'progress = 100%' >&2; echo done";
+        let child = tokio::process::Command::new("sh")
+            .arg("-c").arg(script)
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .spawn()
+            .unwrap();
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let output = tokio::spawn(with_progress(tx, crate::transcription::wait_with_progress(child, "Whisper")));
+
+        let first = rx.recv().await.unwrap();
+        assert_eq!(first.percent, 50);
+        assert!(!output.is_finished(), "progress must arrive before the process exits");
+
+        let output = output.await.unwrap().unwrap();
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "done");
+        assert!(output.stderr.contains(&0xE9));
+        assert!(String::from_utf8_lossy(&output.stderr).contains("progress = 100%"));
+        assert_eq!(rx.recv().await.map(|p| p.percent), Some(100));
+    }
+}
@@ -0,0 +1,357 @@
+// Routing policy - pick provider/model per utterance from duration, language, power and network
+//
+// Rules are evaluated in order and the first match wins. A rule only constrains the
+// conditions it sets, so an empty rule matches everything. The routed provider becomes
+// the first step of the configured fallback chain; with no match the chain runs as-is.
+
+use super::chain::{create_provider, ChainOutcome, ChainStep, ProviderChain};
+use super::{AudioBuffer, TranscriptionProvider};
+use crate::config::AppConfig;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+const NETWORK_PROBE_TIMEOUT_MS: u64 = 800;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PowerSource {
+    Ac,
+    Battery,
+    Unknown,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RoutingRule {
+    /// Shown in logs so users can tell which rule fired
+    #[serde(default)]
+    pub name: String,
+
+    #[serde(default)]
+    pub min_duration_secs: Option<f32>,
+    #[serde(default)]
+    pub max_duration_secs: Option<f32>,
+    /// Language prefixes such as "en" or "de"; empty matches any language
+    #[serde(default)]
+    pub languages: Vec<String>,
+    /// Only match on this power source (unknown power state never matches)
+    #[serde(default)]
+    pub power: Option<PowerSource>,
+    /// Only match when the network is (true) or is not (false) reachable
+    #[serde(default)]
+    pub network: Option<bool>,
+
+    pub provider: String,
+    /// Model id overriding `whisper_model` / `onnx_model` for this route
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default = "default_route_timeout")]
+    pub timeout_ms: u64,
+}
+
+fn default_route_timeout() -> u64 { 60_000 }
+
+/// What the policy knows about the utterance and the machine when it decides
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoutingContext {
+    pub duration_secs: f32,
+    pub language: String,
+    pub power: PowerSource,
+    /// `None` when no rule needs it and the probe was skipped
+    pub network_available: Option<bool>,
+}
+
+impl RoutingRule {
+    pub fn matches(&self, ctx: &RoutingContext) -> bool {
+        if self.min_duration_secs.is_some_and(|min| ctx.duration_secs < min) {
+            return false;
+        }
+        if self.max_duration_secs.is_some_and(|max| ctx.duration_secs > max) {
+            return false;
+        }
+        if !self.languages.is_empty() {
+            let language = ctx.language.split('-').next().unwrap_or_default().to_lowercase();
+            if !self.languages.iter().any(|l| l.to_lowercase() == language) {
+                return false;
+            }
+        }
+        if self.power.is_some_and(|power| power != ctx.power) {
+            return false;
+        }
+        if let Some(required) = self.network {
+            if ctx.network_available != Some(required) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+pub struct RoutingPolicy {
+    rules: Vec<RoutingRule>,
+}
+
+impl RoutingPolicy {
+    pub fn new(rules: Vec<RoutingRule>) -> Self {
+        Self { rules }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    pub fn needs_network_probe(&self) -> bool {
+        self.rules.iter().any(|rule| rule.network.is_some())
+    }
+
+    pub fn select(&self, ctx: &RoutingContext) -> Option<&RoutingRule> {
+        self.rules.iter().find(|rule| rule.matches(ctx))
+    }
+}
+
+type ProviderCache = HashMap<(String, Option<String>), Arc<dyn TranscriptionProvider>>;
+
+/// Routes each utterance through the policy, then through the fallback chain
+pub struct Router {
+    policy: RoutingPolicy,
+    chain: Arc<ProviderChain>,
+    config: AppConfig,
+    // Routed providers are built once per (provider, model) and reused
+    providers: Mutex<ProviderCache>,
+}
+
+impl Router {
+    pub fn new(policy: RoutingPolicy, chain: Arc<ProviderChain>, config: AppConfig) -> Self {
+        Self {
+            policy,
+            chain,
+            config,
+            providers: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn from_config(config: &AppConfig) -> Self {
+        let policy = RoutingPolicy::new(config.routing_rules.clone());
+        if !policy.is_empty() {
+            log::info!("Routing policy enabled with {} rule(s)", config.routing_rules.len());
+        }
+        if policy.needs_network_probe() && config.network_probe_address.is_none() {
+            log::warn!("Routing rules with a network condition are skipped: no network_probe_address is set");
+        }
+        Self::new(policy, Arc::new(ProviderChain::from_config(config)), config.clone())
+    }
+
+    pub fn chain(&self) -> &ProviderChain {
+        &self.chain
+    }
+
+    /// Streaming has to start before the utterance length is known, so it is
+    /// only used when no routing rules are configured.
+    pub fn streaming_enabled(&self) -> bool {
+        self.policy.is_empty()
+    }
+
+    pub async fn context(&self, duration_secs: f32) -> RoutingContext {
+        // Without a probe address, rules with a network condition never match
+        let network_available = match &self.config.network_probe_address {
+            Some(address) if self.policy.needs_network_probe() => Some(is_network_reachable(address).await),
+            _ => None,
+        };
+
+        RoutingContext {
+            duration_secs,
+            language: self.config.language.clone(),
+            power: detect_power_source(),
+            network_available,
+        }
+    }
+
+    fn provider_for(&self, rule: &RoutingRule) -> Option<Arc<dyn TranscriptionProvider>> {
+        let key = (rule.provider.clone(), rule.model.clone());
+        let mut providers = self.providers.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(provider) = providers.get(&key) {
+            return Some(provider.clone());
+        }
+
+        let mut config = self.config.clone();
+        if let Some(model) = &rule.model {
+            // Each provider reads its own model field
+            config.whisper_model = model.clone();
+            config.onnx_model = model.clone();
+        }
+        let provider = create_provider(&rule.provider, &config)?;
+        providers.insert(key, provider.clone());
+        Some(provider)
+    }
+
+    /// The configured chain, without consulting the policy
+    pub fn default_route(&self) -> Route {
+        Route { chain: self.chain.clone(), model: None }
+    }
+
+    /// Picks the chain for an utterance of `duration_secs`: the configured chain, or a copy
+    /// with the matched rule's provider tried first
+    pub async fn route(&self, duration_secs: f32) -> Route {
+        if self.policy.is_empty() {
+            return self.default_route();
+        }
+
+        let ctx = self.context(duration_secs).await;
+        let Some(rule) = self.policy.select(&ctx) else {
+            log::info!("No routing rule matched {:?}, using default chain", ctx);
+            return self.default_route();
+        };
+
+        let Some(provider) = self.provider_for(rule) else {
+            log::warn!("Routing rule '{}' names unknown provider '{}', using default chain", rule.name, rule.provider);
+            return self.default_route();
+        };
+        log::info!("Routing rule '{}' matched {:?} -> {} {:?}", rule.name, ctx, rule.provider, rule.model);
+
+        let routed = ChainStep {
+            provider,
+            timeout: Duration::from_millis(rule.timeout_ms),
+        };
+        Route { chain: Arc::new(self.chain.with_primary(routed)), model: rule.model.clone() }
+    }
+
+    pub async fn transcribe_with_source(&self, audio: AudioBuffer, sample_rate: u32) -> Result<ChainOutcome, Box<dyn std::error::Error + Send + Sync>> {
+        let route = self.route(audio.len() as f32 / sample_rate as f32).await;
+        route.transcribe_with_source(audio, sample_rate).await
+    }
+}
+
+/// The chain one utterance runs through, as picked by the routing policy
+pub struct Route {
+    chain: Arc<ProviderChain>,
+    /// Model of the matched rule; only the routed first step uses it
+    model: Option<String>,
+}
+
+impl Route {
+    pub fn chain(&self) -> &ProviderChain {
+        &self.chain
+    }
+
+    pub async fn transcribe_with_source(&self, audio: AudioBuffer, sample_rate: u32) -> Result<ChainOutcome, Box<dyn std::error::Error + Send + Sync>> {
+        let mut outcome = self.chain.transcribe_with_source(audio, sample_rate).await?;
+        // A fallback of the same provider runs with its own configured model
+        if let Some(model) = self.model.as_ref().filter(|_| outcome.step == 0) {
+            outcome.provider = format!("{}:{}", outcome.provider, model);
+        }
+        Ok(outcome)
+    }
+}
+
+pub async fn is_network_reachable(probe_address: &str) -> bool {
+    let probe = tokio::net::TcpStream::connect(probe_address);
+    matches!(
+        tokio::time::timeout(Duration::from_millis(NETWORK_PROBE_TIMEOUT_MS), probe).await,
+        Ok(Ok(_))
+    )
+}
+
+#[cfg(windows)]
+pub fn detect_power_source() -> PowerSource {
+    use windows::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+
+    let mut status = SYSTEM_POWER_STATUS::default();
+    match unsafe { GetSystemPowerStatus(&mut status) } {
+        Ok(()) => match status.ACLineStatus {
+            0 => PowerSource::Battery,
+            1 => PowerSource::Ac,
+            _ => PowerSource::Unknown,
+        },
+        Err(_) => PowerSource::Unknown,
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn detect_power_source() -> PowerSource {
+    // Any "Mains" supply that is online means we're plugged in
+    let Ok(entries) = std::fs::read_dir("/sys/class/power_supply") else {
+        return PowerSource::Unknown;
+    };
+    let mut has_battery = false;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let kind = std::fs::read_to_string(path.join("type")).unwrap_or_default();
+        match kind.trim() {
+            "Mains" if std::fs::read_to_string(path.join("online")).unwrap_or_default().trim() == "1" => {
+                return PowerSource::Ac;
+            }
+            "Battery" => has_battery = true,
+            _ => {}
+        }
+    }
+    if has_battery { PowerSource::Battery } else { PowerSource::Unknown }
+}
+
+#[cfg(target_os = "macos")]
+pub fn detect_power_source() -> PowerSource {
+    match std::process::Command::new("pmset").args(["-g", "batt"]).output() {
+        Ok(output) => {
+            let text = String::from_utf8_lossy(&output.stdout);
+            if text.contains("'AC Power'") {
+                PowerSource::Ac
+            } else if text.contains("'Battery Power'") {
+                PowerSource::Battery
+            } else {
+                PowerSource::Unknown
+            }
+        }
+        Err(_) => PowerSource::Unknown,
+    }
+}
+
+#[cfg(not(any(windows, target_os = "linux", target_os = "macos")))]
+pub fn detect_power_source() -> PowerSource {
+    PowerSource::Unknown
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ctx(duration_secs: f32, language: &str, power: PowerSource, network: Option<bool>) -> RoutingContext {
+        RoutingContext { duration_secs, language: language.to_string(), power, network_available: network }
+    }
+
+    fn rules() -> Vec<RoutingRule> {
+        serde_json::from_str(r#"[
+            {"name": "short commands", "max_duration_secs": 3, "provider": "Whisper", "model": "tiny"},
+            {"name": "german", "languages": ["de"], "provider": "Whisper", "model": "small"},
+            {"name": "long online", "min_duration_secs": 20, "network": true, "power": "battery", "provider": "Cloud"},
+            {"name": "everything else", "provider": "Whisper", "model": "base"}
+        ]"#).unwrap()
+    }
+
+    #[test]
+    fn test_first_matching_rule_wins() {
+        let policy = RoutingPolicy::new(rules());
+
+        let pick = |c: RoutingContext| policy.select(&c).map(|r| r.name.clone()).unwrap();
+        assert_eq!(pick(ctx(1.5, "de-DE", PowerSource::Ac, None)), "short commands");
+        assert_eq!(pick(ctx(10.0, "de-DE", PowerSource::Ac, None)), "german");
+        assert_eq!(pick(ctx(30.0, "en-US", PowerSource::Battery, Some(true))), "long online");
+        assert_eq!(pick(ctx(30.0, "en-US", PowerSource::Battery, Some(false))), "everything else");
+        assert_eq!(pick(ctx(30.0, "en-US", PowerSource::Ac, Some(true))), "everything else");
+    }
+
+    #[test]
+    fn test_network_probe_only_when_needed() {
+        assert!(RoutingPolicy::new(rules()).needs_network_probe());
+        assert!(!RoutingPolicy::new(rules()[..2].to_vec()).needs_network_probe());
+    }
+
+    #[tokio::test]
+    async fn test_network_probe_against_local_listener() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        assert!(is_network_reachable(&address).await);
+
+        drop(listener);
+        assert!(!is_network_reachable(&address).await);
+    }
+}
@@ -0,0 +1,99 @@
+// Shadow provider - transcribes real dictations with a candidate provider for evaluation
+//
+// The shadow never affects what gets typed: it runs after the primary transcript is known,
+// in a detached task, and only writes both outputs, their diff and latencies to the eval store.
+// It gets the same context prompt as the primary so the comparison is like for like, but no
+// progress sender: the HUD only follows the dictation itself.
+
+use super::chain::create_provider;
+use super::{context, AudioBuffer, ChainOutcome, TranscriptionProvider};
+use crate::config::AppConfig;
+use crate::eval::{EvalRecord, EvalStore};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+pub struct Shadow {
+    provider: Arc<dyn TranscriptionProvider>,
+    /// Shown in reports; includes the model when it differs from the primary
+    label: String,
+    store: Arc<EvalStore>,
+    timeout: Duration,
+}
+
+impl Shadow {
+    pub fn new(provider: Arc<dyn TranscriptionProvider>, label: String, store: Arc<EvalStore>, timeout: Duration) -> Self {
+        Self { provider, label, store, timeout }
+    }
+
+    /// `None` unless a shadow provider is configured
+    pub fn from_config(config: &AppConfig) -> Option<Self> {
+        if config.shadow_provider.is_empty() {
+            return None;
+        }
+
+        let mut shadow_config = config.clone();
+        let mut label = config.shadow_provider.clone();
+        if !config.shadow_whisper_model.is_empty() {
+            shadow_config.whisper_model = config.shadow_whisper_model.clone();
+            if config.shadow_provider == "Whisper" {
+                label = format!("Whisper ({})", config.shadow_whisper_model);
+            }
+        }
+
+        let Some(provider) = create_provider(&config.shadow_provider, &shadow_config) else {
+            log::warn!("Unknown shadow provider '{}', shadow mode disabled", config.shadow_provider);
+            return None;
+        };
+        log::info!("Shadow mode enabled: comparing against {}", label);
+
+        Some(Self::new(provider, label, Arc::new(EvalStore::new()), Duration::from_secs(120)))
+    }
+
+    /// Transcribes `audio` with the shadow provider in the background and records the comparison
+    pub fn compare_in_background(self: &Arc<Self>, primary: &ChainOutcome, primary_latency: Duration, audio: AudioBuffer, sample_rate: u32) {
+        let shadow = self.clone();
+        let primary = (primary.provider.clone(), primary.text.clone(), primary_latency.as_millis() as u64);
+        // Task-locals don't follow `tokio::spawn`
+        let prompt = context::current_prompt();
+        tokio::spawn(async move {
+            let record = context::with_prompt(prompt, shadow.compare(primary, audio, sample_rate)).await;
+            if let Err(e) = shadow.store.append(&record) {
+                log::warn!("Failed to write shadow evaluation: {}", e);
+            }
+        });
+    }
+
+    async fn compare(&self, primary: (String, String, u64), audio: AudioBuffer, sample_rate: u32) -> EvalRecord {
+        let audio_secs = audio.len() as f32 / sample_rate as f32;
+        let start = Instant::now();
+        let result = match tokio::time::timeout(self.timeout, self.provider.transcribe(audio, sample_rate)).await {
+            Ok(Ok(text)) => Ok(text),
+            Ok(Err(e)) => Err(e.to_string()),
+            Err(_) => Err(format!("timed out after {:?}", self.timeout)),
+        };
+        let latency_ms = start.elapsed().as_millis() as u64;
+
+        let record = EvalRecord::new(audio_secs, primary, (self.label.clone(), result, latency_ms));
+        log::info!("Shadow {}: agree={} wer={:.3} latency {}ms vs {}ms",
+            self.label, record.agree, record.wer, record.shadow_latency_ms, record.primary_latency_ms);
+        record
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transcription::MockProvider;
+
+    #[tokio::test]
+    async fn test_compare_records_both_outputs() {
+        let store = Arc::new(EvalStore::with_path(std::env::temp_dir().join("unused.jsonl")));
+        let shadow = Shadow::new(Arc::new(MockProvider::new()), "Mock".into(), store, Duration::from_secs(5));
+
+        let primary = ("Whisper".to_string(), "This is a test transcription from the mock provider".to_string(), 900);
+        let record = shadow.compare(primary, vec![0.0; 16000], 16000).await;
+        assert!(record.agree);
+        assert_eq!(record.primary_latency_ms, 900);
+        assert!(record.shadow_latency_ms >= 500);
+    }
+}
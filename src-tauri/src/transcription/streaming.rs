@@ -0,0 +1,301 @@
+// Streaming cloud provider - PCM frames over WebSocket with interim/final transcripts
+//
+// Wire protocol (text messages are JSON objects tagged by "type"):
+//
+//   client -> server  {"type":"start","sample_rate":16000,"encoding":"pcm_s16le","channels":1,"language":"en"}
+//   client -> server  binary messages: mono little-endian i16 PCM, ~100ms per frame
+//   client -> server  {"type":"stop"}                    no more audio will follow
+//   server -> client  {"type":"interim","text":"..."}    replaces the current partial hypothesis
+//   server -> client  {"type":"final","text":"..."}      commits a segment; segments are joined with spaces
+//   server -> client  {"type":"done"}                    all audio processed, connection may close
+//   server -> client  {"type":"error","message":"..."}   aborts the session
+//
+// Unknown message types from the server are ignored so the protocol can grow.
+
+use super::lifecycle::{self, Capabilities, HealthStatus, Languages};
+use super::{pcm16_le_bytes, AudioBuffer, AudioFrameReceiver, TranscriptEvent, TranscriptEventSender, TranscriptionProvider};
+use crate::errors::TranscriptionError;
+use async_trait::async_trait;
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::Message;
+
+/// Samples per binary frame (100ms at 16kHz)
+const FRAME_SAMPLES: usize = 1600;
+
+/// How long to wait for the server to finish after `stop` was sent
+const FINALIZE_TIMEOUT_SECS: u64 = 30;
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    Start {
+        sample_rate: u32,
+        encoding: String,
+        channels: u16,
+        language: String,
+    },
+    Stop,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+    Interim { text: String },
+    Final { text: String },
+    Done,
+    Error { message: String },
+    #[serde(other)]
+    Unknown,
+}
+
+pub struct StreamingCloudProvider {
+    endpoint: String,
+    language: String,
+}
+
+impl StreamingCloudProvider {
+    pub fn new(endpoint: String, language: String) -> Self {
+        log::info!("StreamingCloudProvider initialized with endpoint: {}", endpoint);
+        Self { endpoint, language }
+    }
+}
+
+/// Applies one server message to the running transcript.
+/// Returns `Ok(true)` once the server reports that it is done.
+fn handle_server_message(
+    text: &str,
+    segments: &mut Vec<String>,
+    events: &TranscriptEventSender,
+) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+    match serde_json::from_str::<ServerMessage>(text)? {
+        ServerMessage::Interim { text } => {
+            let _ = events.send(TranscriptEvent::Interim(text));
+            Ok(false)
+        }
+        ServerMessage::Final { text } => {
+            let text = text.trim().to_string();
+            if !text.is_empty() {
+                let _ = events.send(TranscriptEvent::Final(text.clone()));
+                segments.push(text);
+            }
+            Ok(false)
+        }
+        ServerMessage::Done => Ok(true),
+        ServerMessage::Error { message } => Err(TranscriptionError::EngineFailed { engine: "Streaming server".to_string(), details: message }.into()),
+        ServerMessage::Unknown => {
+            log::debug!("Ignoring unknown streaming message: {}", text);
+            Ok(false)
+        }
+    }
+}
+
+#[async_trait]
+impl TranscriptionProvider for StreamingCloudProvider {
+    async fn transcribe(&self, audio: AudioBuffer, sample_rate: u32) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        // Replay a finished recording through the streaming path
+        let (frame_tx, frame_rx) = mpsc::unbounded_channel();
+        for chunk in audio.chunks(FRAME_SAMPLES) {
+            let _ = frame_tx.send(chunk.to_vec());
+        }
+        drop(frame_tx);
+
+        let (event_tx, _event_rx) = mpsc::unbounded_channel();
+        self.transcribe_stream(frame_rx, sample_rate, event_tx).await
+    }
+
+    fn name(&self) -> &'static str {
+        "Streaming"
+    }
+
+    fn supports_streaming(&self) -> bool {
+        true
+    }
+
+    async fn health_check(&self) -> HealthStatus {
+        let address = reqwest::Url::parse(&self.endpoint).ok().and_then(|url| {
+            Some(format!("{}:{}", url.host_str()?, url.port_or_known_default()?))
+        });
+        match address {
+            Some(address) => lifecycle::probe_tcp(&address, "Streaming endpoint").await,
+            None => HealthStatus::unavailable(&TranscriptionError::NotConfigured {
+                provider: "Streaming".to_string(),
+                missing: format!("a valid ws:// or wss:// endpoint (got '{}')", self.endpoint),
+            }),
+        }
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities { languages: Languages::Unknown, streaming: true, timestamps: false, translation: false, context: false }
+    }
+
+    async fn transcribe_stream(&self, mut frames: AudioFrameReceiver, sample_rate: u32, events: TranscriptEventSender) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        log::info!("Connecting to streaming endpoint: {}", self.endpoint);
+        let (socket, _) = tokio_tungstenite::connect_async(self.endpoint.as_str())
+            .await
+            .map_err(|e| TranscriptionError::Connection { endpoint: format!("streaming endpoint {}", self.endpoint), details: e.to_string() })?;
+        let (mut sink, mut stream) = socket.split();
+
+        let start = ClientMessage::Start {
+            sample_rate,
+            encoding: "pcm_s16le".to_string(),
+            channels: 1,
+            language: self.language.clone(),
+        };
+        sink.send(Message::Text(serde_json::to_string(&start)?)).await?;
+
+        let mut segments = Vec::new();
+        let mut pending: Vec<f32> = Vec::with_capacity(FRAME_SAMPLES);
+        let mut sent_samples = 0usize;
+        let mut done = false;
+
+        // 1. Upload frames while capture is running, reading results as they arrive
+        loop {
+            tokio::select! {
+                frame = frames.recv() => match frame {
+                    Some(frame) => {
+                        pending.extend_from_slice(&frame);
+                        while pending.len() >= FRAME_SAMPLES {
+                            let chunk: Vec<f32> = pending.drain(..FRAME_SAMPLES).collect();
+                            sink.send(Message::Binary(pcm16_le_bytes(&chunk))).await?;
+                            sent_samples += chunk.len();
+                        }
+                    }
+                    None => break,
+                },
+                msg = stream.next() => match msg {
+                    Some(Ok(Message::Text(text))) => {
+                        if handle_server_message(&text, &mut segments, &events)? {
+                            done = true;
+                            break;
+                        }
+                    }
+                    Some(Ok(Message::Close(_))) | None => {
+                        return Err("Streaming server closed the connection before recording finished".into());
+                    }
+                    Some(Ok(_)) => {}
+                    Some(Err(e)) => return Err(e.into()),
+                },
+            }
+        }
+
+        // 2. Flush the tail and tell the server we're finished
+        if !done {
+            if !pending.is_empty() {
+                sink.send(Message::Binary(pcm16_le_bytes(&pending))).await?;
+                sent_samples += pending.len();
+            }
+            sink.send(Message::Text(serde_json::to_string(&ClientMessage::Stop)?)).await?;
+            log::info!("Streamed {} samples, waiting for final transcript", sent_samples);
+
+            // 3. Collect the remaining finals until the server is done
+            let finalize = async {
+                while let Some(msg) = stream.next().await {
+                    match msg? {
+                        Message::Text(text) if handle_server_message(&text, &mut segments, &events)? => return Ok(()),
+                        Message::Close(_) => return Ok(()),
+                        _ => {}
+                    }
+                }
+                Ok::<(), Box<dyn std::error::Error + Send + Sync>>(())
+            };
+            tokio::time::timeout(std::time::Duration::from_secs(FINALIZE_TIMEOUT_SECS), finalize)
+                .await
+                .map_err(|_| TranscriptionError::Timeout { provider: "Streaming".to_string(), secs: FINALIZE_TIMEOUT_SECS as f32 })??;
+        }
+
+        let _ = sink.close().await;
+
+        let transcript = segments.join(" ");
+        log::info!("Streaming transcription complete: '{}' (len: {})", transcript, transcript.len());
+        Ok(transcript)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    /// Loopback server speaking the wire protocol: one interim per binary frame,
+    /// then a final with the sample count once `stop` arrives.
+    async fn spawn_mock_server(fail: bool) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            let (tcp, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(tcp).await.unwrap();
+            let mut samples = 0usize;
+
+            while let Some(Ok(msg)) = ws.next().await {
+                match msg {
+                    Message::Binary(data) => {
+                        samples += data.len() / 2;
+                        let interim = format!(r#"{{"type":"interim","text":"heard {}"}}"#, samples);
+                        ws.send(Message::Text(interim)).await.unwrap();
+                    }
+                    Message::Text(text) => match serde_json::from_str::<ClientMessage>(&text).unwrap() {
+                        ClientMessage::Start { sample_rate, .. } => assert_eq!(sample_rate, 16000),
+                        ClientMessage::Stop => {
+                            if fail {
+                                ws.send(Message::Text(r#"{"type":"error","message":"quota exceeded"}"#.into())).await.unwrap();
+                            } else {
+                                ws.send(Message::Text(r#"{"type":"final","text":"hello"}"#.into())).await.unwrap();
+                                ws.send(Message::Text(format!(r#"{{"type":"final","text":"{} samples"}}"#, samples))).await.unwrap();
+                                ws.send(Message::Text(r#"{"type":"done"}"#.into())).await.unwrap();
+                            }
+                            break;
+                        }
+                    },
+                    _ => {}
+                }
+            }
+        });
+
+        format!("ws://{}", addr)
+    }
+
+    #[tokio::test]
+    async fn test_streams_frames_and_collects_finals() {
+        let endpoint = spawn_mock_server(false).await;
+        let provider = StreamingCloudProvider::new(endpoint, "en".to_string());
+
+        let (frame_tx, frame_rx) = mpsc::unbounded_channel();
+        let (event_tx, mut event_rx) = mpsc::unbounded_channel();
+        // Recorder callbacks deliver small, uneven chunks
+        for _ in 0..50 {
+            frame_tx.send(vec![0.1; 100]).unwrap();
+        }
+        drop(frame_tx);
+
+        let text = provider.transcribe_stream(frame_rx, 16000, event_tx).await.unwrap();
+        assert_eq!(text, "hello 5000 samples");
+
+        let mut events = Vec::new();
+        while let Ok(event) = event_rx.try_recv() {
+            events.push(event);
+        }
+        assert!(events.contains(&TranscriptEvent::Interim("heard 1600".to_string())));
+        assert_eq!(events.last(), Some(&TranscriptEvent::Final("5000 samples".to_string())));
+    }
+
+    #[tokio::test]
+    async fn test_server_error_is_returned() {
+        let endpoint = spawn_mock_server(true).await;
+        let provider = StreamingCloudProvider::new(endpoint, "en".to_string());
+
+        let err = provider.transcribe(vec![0.0; 3200], 16000).await.unwrap_err();
+        assert!(err.to_string().contains("quota exceeded"));
+    }
+
+    #[test]
+    fn test_unknown_server_messages_are_ignored() {
+        let (event_tx, _event_rx) = mpsc::unbounded_channel();
+        let mut segments = Vec::new();
+        let done = handle_server_message(r#"{"type":"metrics","rtf":0.2}"#, &mut segments, &event_tx).unwrap();
+        assert!(!done);
+        assert!(segments.is_empty());
+    }
+}
@@ -0,0 +1,152 @@
+// Two-pass transcription - instant draft from a fast model, refined text from the full chain
+//
+// Long dictations are first transcribed with a small Whisper model and injected right away.
+// The normal routing/chain then re-transcribes the same audio in the background; the caller
+// swaps the draft for the refined text, or offers a word diff if the draft was edited.
+
+use super::chain::create_provider;
+use super::{AudioBuffer, TranscriptionProvider};
+use crate::config::AppConfig;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+pub struct TwoPass {
+    draft: Arc<dyn TranscriptionProvider>,
+    min_duration_secs: f32,
+}
+
+impl TwoPass {
+    pub fn new(draft: Arc<dyn TranscriptionProvider>, min_duration_secs: f32) -> Self {
+        Self { draft, min_duration_secs }
+    }
+
+    /// `None` unless two-pass mode is enabled
+    pub fn from_config(config: &AppConfig) -> Option<Self> {
+        if !config.two_pass_enabled {
+            return None;
+        }
+
+        let mut draft_config = config.clone();
+        draft_config.whisper_model = config.two_pass_draft_model.clone();
+        let draft = create_provider("Whisper", &draft_config)?;
+        log::info!("Two-pass enabled: draft model '{}' for dictations over {:.1}s",
+            config.two_pass_draft_model, config.two_pass_min_secs);
+
+        Some(Self::new(draft, config.two_pass_min_secs))
+    }
+
+    /// Short dictations are fast enough with the refining model alone
+    pub fn applies_to(&self, duration_secs: f32) -> bool {
+        duration_secs >= self.min_duration_secs
+    }
+
+    pub async fn draft(&self, audio: AudioBuffer, sample_rate: u32) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        let start = std::time::Instant::now();
+        let text = self.draft.transcribe(audio, sample_rate).await?;
+        log::info!("Draft transcription: '{}' [took: {:?}]", text, start.elapsed());
+        Ok(text)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", content = "text", rename_all = "snake_case")]
+pub enum DiffOp {
+    Equal(String),
+    Delete(String),
+    Insert(String),
+}
+
+/// Sent to the frontend when the refined text could not replace the draft in place
+#[derive(Debug, Clone, Serialize)]
+pub struct Refinement {
+    pub draft: String,
+    pub refined: String,
+    pub diff: Vec<DiffOp>,
+}
+
+impl Refinement {
+    pub fn new(draft: String, refined: String) -> Self {
+        let diff = word_diff(&draft, &refined);
+        Self { draft, refined, diff }
+    }
+}
+
+/// Word-level diff (LCS), with runs of the same operation merged
+pub fn word_diff(old: &str, new: &str) -> Vec<DiffOp> {
+    let a: Vec<&str> = old.split_whitespace().collect();
+    let b: Vec<&str> = new.split_whitespace().collect();
+
+    // lcs[i][j] = length of the common subsequence of a[i..] and b[j..]
+    let mut lcs = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut ops: Vec<DiffOp> = Vec::new();
+    let mut push = |op: DiffOp| {
+        let merged = match (ops.last_mut(), &op) {
+            (Some(DiffOp::Equal(prev)), DiffOp::Equal(word))
+            | (Some(DiffOp::Delete(prev)), DiffOp::Delete(word))
+            | (Some(DiffOp::Insert(prev)), DiffOp::Insert(word)) => {
+                prev.push(' ');
+                prev.push_str(word);
+                true
+            }
+            _ => false,
+        };
+        if !merged {
+            ops.push(op);
+        }
+    };
+
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] {
+            push(DiffOp::Equal(a[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            push(DiffOp::Delete(a[i].to_string()));
+            i += 1;
+        } else {
+            push(DiffOp::Insert(b[j].to_string()));
+            j += 1;
+        }
+    }
+    a[i..].iter().for_each(|word| push(DiffOp::Delete(word.to_string())));
+    b[j..].iter().for_each(|word| push(DiffOp::Insert(word.to_string())));
+
+    ops
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transcription::MockProvider;
+
+    #[test]
+    fn test_word_diff() {
+        let diff = word_diff("the quick brown fax jumped", "the quick brown fox jumped over");
+        assert_eq!(diff, vec![
+            DiffOp::Equal("the quick brown".into()),
+            DiffOp::Delete("fax".into()),
+            DiffOp::Insert("fox".into()),
+            DiffOp::Equal("jumped".into()),
+            DiffOp::Insert("over".into()),
+        ]);
+        assert_eq!(word_diff("same text", "same text"), vec![DiffOp::Equal("same text".into())]);
+    }
+
+    #[test]
+    fn test_only_long_dictations_get_a_draft() {
+        let two_pass = TwoPass::new(Arc::new(MockProvider::new()), 6.0);
+        assert!(!two_pass.applies_to(2.5));
+        assert!(two_pass.applies_to(6.0));
+    }
+}
@@ -0,0 +1,242 @@
+// Transcript validator - catches Whisper hallucinations and repetition loops
+//
+// Checks run cheapest-first and either drop the whole transcript or trim the bad parts:
+//   1. audio energy: silent clips can't contain real dictation
+//   2. no-speech probability, when the engine reports one
+//   3. known hallucination phrases ("Thanks for watching!"), removed sentence by sentence,
+//      but only from quiet clips; over clearly audible speech they are taken as dictated
+//   4. n-gram loops ("I think that I think that I think that ..."), collapsed to one copy
+
+/// Sentences Whisper produces from silence or noise, learned from subtitle training data.
+/// Compared after lowercasing and stripping punctuation.
+const HALLUCINATION_PHRASES: &[&str] = &[
+    "thank you for watching",
+    "thanks for watching",
+    "thank you so much for watching",
+    "thank you for watching and ill see you next time",
+    "please subscribe",
+    "please subscribe to my channel",
+    "dont forget to like and subscribe",
+    "like and subscribe",
+    "see you in the next video",
+    "ill see you in the next video",
+    "subtitles by the amaraorg community",
+    "subtitles by",
+    "transcription by castingwords",
+    "thank you for listening",
+    "bye bye",
+];
+
+/// Longest phrase (in words) checked for repetition loops
+const MAX_NGRAM: usize = 8;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Validation {
+    /// Text passed unchanged
+    Accepted(String),
+    /// Suspicious parts were removed; the rest is kept
+    Trimmed { text: String, reasons: Vec<String> },
+    /// Nothing trustworthy remains
+    Dropped { reason: String },
+}
+
+impl Validation {
+    /// The text to use, or `None` when the transcript was dropped
+    pub fn text(&self) -> Option<&str> {
+        match self {
+            Validation::Accepted(text) | Validation::Trimmed { text, .. } => Some(text),
+            Validation::Dropped { .. } => None,
+        }
+    }
+}
+
+pub struct TranscriptValidator {
+    /// RMS below this counts as silence (full scale is 1.0); 0 disables the check
+    pub min_speech_rms: f32,
+    /// Hallucination phrases are only removed from clips quieter than this RMS
+    pub quiet_rms: f32,
+    /// Drop when the engine is at least this sure there was no speech
+    pub no_speech_threshold: f32,
+    /// A phrase may repeat back-to-back this many times before it's treated as a loop
+    pub max_repeats: usize,
+}
+
+impl Default for TranscriptValidator {
+    fn default() -> Self {
+        Self {
+            min_speech_rms: 0.001,
+            quiet_rms: 0.01,
+            no_speech_threshold: 0.6,
+            max_repeats: 2,
+        }
+    }
+}
+
+impl TranscriptValidator {
+    pub fn validate(&self, text: &str, audio: &[f32], no_speech_prob: Option<f32>) -> Validation {
+        let text = text.trim();
+        if text.is_empty() {
+            return Validation::Dropped { reason: "empty transcript".to_string() };
+        }
+
+        let rms = rms(audio);
+        if !audio.is_empty() && rms < self.min_speech_rms {
+            return Validation::Dropped { reason: format!("audio is near-silent (rms {:.4})", rms) };
+        }
+        if let Some(prob) = no_speech_prob.filter(|&p| p >= self.no_speech_threshold) {
+            return Validation::Dropped { reason: format!("no-speech probability {:.2}", prob) };
+        }
+
+        let mut reasons = Vec::new();
+
+        let near_silent = audio.is_empty() || rms < self.quiet_rms;
+        let (kept, removed) = if near_silent { remove_hallucinated_sentences(text) } else { (text.to_string(), Vec::new()) };
+        if !removed.is_empty() {
+            reasons.push(format!("removed hallucinated phrase(s): {}", removed.join(" | ")));
+        }
+        if kept.is_empty() {
+            return Validation::Dropped { reason: reasons.join("; ") };
+        }
+
+        let (collapsed, loops) = collapse_repetitions(&kept, self.max_repeats);
+        if loops > 0 {
+            reasons.push(format!("collapsed {} repetition loop(s)", loops));
+        }
+
+        if reasons.is_empty() {
+            Validation::Accepted(text.to_string())
+        } else {
+            Validation::Trimmed { text: collapsed, reasons }
+        }
+    }
+}
+
+pub fn rms(audio: &[f32]) -> f32 {
+    if audio.is_empty() {
+        return 0.0;
+    }
+    (audio.iter().map(|s| s * s).sum::<f32>() / audio.len() as f32).sqrt()
+}
+
+fn normalize(text: &str) -> String {
+    text.chars()
+        .filter(|c| c.is_alphanumeric() || c.is_whitespace())
+        .collect::<String>()
+        .to_lowercase()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Splits after `.`, `!` and `?`, keeping the punctuation with its sentence
+fn split_sentences(text: &str) -> Vec<&str> {
+    let mut sentences = Vec::new();
+    let mut start = 0;
+    for (i, c) in text.char_indices() {
+        if matches!(c, '.' | '!' | '?') {
+            let end = i + c.len_utf8();
+            // Keep runs like "..." or "?!" together
+            if text[end..].starts_with(['.', '!', '?']) {
+                continue;
+            }
+            sentences.push(text[start..end].trim());
+            start = end;
+        }
+    }
+    sentences.push(text[start..].trim());
+    sentences.retain(|s| !s.is_empty());
+    sentences
+}
+
+fn remove_hallucinated_sentences(text: &str) -> (String, Vec<String>) {
+    let mut kept = Vec::new();
+    let mut removed = Vec::new();
+    for sentence in split_sentences(text) {
+        if HALLUCINATION_PHRASES.contains(&normalize(sentence).as_str()) {
+            removed.push(sentence.to_string());
+        } else {
+            kept.push(sentence);
+        }
+    }
+    (kept.join(" "), removed)
+}
+
+/// Collapses any phrase of 1..=MAX_NGRAM words repeated back-to-back more than
+/// `max_repeats` times into a single copy. Returns the text and the number of loops found.
+fn collapse_repetitions(text: &str, max_repeats: usize) -> (String, usize) {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let keys: Vec<String> = words.iter().map(|w| normalize(w)).collect();
+
+    let mut output: Vec<&str> = Vec::with_capacity(words.len());
+    let mut loops = 0;
+    let mut i = 0;
+
+    'outer: while i < words.len() {
+        for n in 1..=MAX_NGRAM.min((words.len() - i) / 2) {
+            let mut repeats = 1;
+            while i + (repeats + 1) * n <= words.len()
+                && keys[i + repeats * n..i + (repeats + 1) * n] == keys[i..i + n]
+            {
+                repeats += 1;
+            }
+            // Single words need a longer run ("no no no" is normal speech)
+            let limit = if n == 1 { max_repeats + 1 } else { max_repeats };
+            if repeats > limit {
+                output.extend_from_slice(&words[i..i + n]);
+                i += repeats * n;
+                loops += 1;
+                continue 'outer;
+            }
+        }
+        output.push(words[i]);
+        i += 1;
+    }
+
+    (output.join(" "), loops)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn speech() -> Vec<f32> {
+        (0..16000).map(|i| (i as f32 * 0.05).sin() * 0.2).collect()
+    }
+
+    #[test]
+    fn test_silence_and_no_speech_are_dropped() {
+        let validator = TranscriptValidator::default();
+        let silence = vec![0.0005; 16000];
+
+        assert!(matches!(validator.validate("Thank you for watching.", &silence, None), Validation::Dropped { .. }));
+        assert!(matches!(validator.validate("Hello there.", &speech(), Some(0.9)), Validation::Dropped { .. }));
+        assert_eq!(validator.validate("Hello there.", &speech(), Some(0.1)), Validation::Accepted("Hello there.".into()));
+    }
+
+    #[test]
+    fn test_hallucinated_sentences_are_trimmed_from_quiet_clips_only() {
+        let validator = TranscriptValidator::default();
+        // Room noise: above the silence floor, well below speech
+        let quiet: Vec<f32> = (0..16000).map(|i| (i as f32 * 0.05).sin() * 0.005).collect();
+
+        let result = validator.validate("Send the report by Friday. Thanks for watching!", &quiet, None);
+        assert_eq!(result.text(), Some("Send the report by Friday."));
+        assert!(matches!(validator.validate("Please subscribe.", &quiet, None), Validation::Dropped { .. }));
+
+        // Real dictations that happen to match a phrase
+        assert_eq!(validator.validate("Bye bye.", &speech(), None), Validation::Accepted("Bye bye.".into()));
+        assert_eq!(validator.validate("Like and subscribe.", &speech(), None).text(), Some("Like and subscribe."));
+    }
+
+    #[test]
+    fn test_repetition_loops_are_collapsed() {
+        let validator = TranscriptValidator::default();
+
+        let looped = "We should ship it. I think that I think that I think that I think that we are done.";
+        assert_eq!(validator.validate(looped, &speech(), None).text(), Some("We should ship it. I think that we are done."));
+
+        // Short, natural repeats survive
+        let natural = "No, no, no. That is very very good.";
+        assert_eq!(validator.validate(natural, &speech(), None), Validation::Accepted(natural.into()));
+    }
+}
@@ -0,0 +1,287 @@
+// Whisper binary - locates and validates the whisper.cpp command-line program
+//
+// The CLI is packaged under different names per platform and release (whisper-cli since
+// 1.7.3, `main` before that), and older builds lack flags WhisperProvider passes. The
+// program is looked up from the configured path, then next to the app, then on PATH, and
+// its `--help` output is checked for every flag that is always passed. Flags only some
+// settings use are checked against the settings in effect when the provider runs. Results
+// are cached until the file changes, so each provider built from config doesn't spawn its
+// own check.
+
+use super::{hidden_command, lifecycle};
+use crate::errors::TranscriptionError;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, SystemTime};
+
+/// Oldest release with every flag in `REQUIRED_FLAGS` (`-nth` arrived in 1.6.0)
+pub const MIN_VERSION: &str = "1.6.0";
+
+/// Flags WhisperProvider passes on every run; the rest depend on settings
+/// (see `WhisperParams::optional_flags`) and are checked with `WhisperBinary::check_flags`
+pub const REQUIRED_FLAGS: [&str; 12] = [
+    "-m", "-f", "-l", "-nt", "-ojf", "-of", "-pp", "-t", "-bs", "-bo", "-tp", "-nth",
+];
+
+const CLI_NAME: &str = if cfg!(windows) { "whisper-cli.exe" } else { "whisper-cli" };
+
+/// Names shipped in release archives, newest first
+const BUNDLED_NAMES: &[&str] = if cfg!(windows) {
+    &["whisper-cli.exe", "whisper.exe", "main.exe"]
+} else {
+    &["whisper-cli", "whisper", "main"]
+};
+
+/// Names package managers install; `main` is too generic to look for on PATH
+const PATH_NAMES: &[&str] = if cfg!(windows) { &["whisper-cli", "whisper"] } else { &["whisper-cli", "whisper-cpp", "whisper"] };
+
+const HELP_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BinarySource {
+    /// `whisper_binary_path` in config
+    Configured,
+    /// Shipped next to the app
+    Bundled,
+    Path,
+}
+
+/// A whisper.cpp program that supports every required flag
+#[derive(Debug, Clone, Serialize)]
+pub struct WhisperBinary {
+    pub path: PathBuf,
+    pub source: BinarySource,
+    /// Only known when the build prints it; older releases don't
+    pub version: Option<String>,
+    /// Every flag its usage text lists
+    #[serde(skip)]
+    flags: HashSet<String>,
+}
+
+impl WhisperBinary {
+    pub fn describe(&self) -> String {
+        let version = self.version.clone().unwrap_or_else(|| format!("{}+", MIN_VERSION));
+        format!("whisper.cpp {} at {}", version, self.path.display())
+    }
+
+    /// Fails if this build lacks any of `flags`, which the current settings need
+    pub fn check_flags(&self, flags: &[&str]) -> Result<(), TranscriptionError> {
+        let missing: Vec<&str> = flags.iter().copied().filter(|flag| !self.flags.contains(*flag)).collect();
+        if missing.is_empty() {
+            return Ok(());
+        }
+        Err(TranscriptionError::EngineIncompatible {
+            program: self.path.display().to_string(),
+            details: format!("this build does not support {}, which the current settings use; update whisper.cpp or change those settings", missing.join(" ")),
+        })
+    }
+}
+
+struct Cached {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    result: Result<WhisperBinary, TranscriptionError>,
+}
+
+fn cache() -> &'static Mutex<HashMap<String, Cached>> {
+    static CACHE: OnceLock<Mutex<HashMap<String, Cached>>> = OnceLock::new();
+    CACHE.get_or_init(Default::default)
+}
+
+/// Finds and validates the program for `configured` (empty = automatic)
+pub async fn resolve(configured: &str) -> Result<WhisperBinary, TranscriptionError> {
+    let (path, source) = locate(configured)?;
+    let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+
+    if let Some(hit) = cache().lock().unwrap_or_else(|e| e.into_inner()).get(configured) {
+        if hit.path == path && hit.modified == modified {
+            return hit.result.clone();
+        }
+    }
+
+    let result = validate(path.clone(), source).await;
+    match &result {
+        Ok(binary) => log::info!("Using {} ({:?})", binary.describe(), binary.source),
+        Err(e) => log::warn!("whisper.cpp check failed: {}", e),
+    }
+    cache().lock().unwrap_or_else(|e| e.into_inner())
+        .insert(configured.to_string(), Cached { path, modified, result: result.clone() });
+    result
+}
+
+/// Where the program would be run from, without running it
+pub fn locate(configured: &str) -> Result<(PathBuf, BinarySource), TranscriptionError> {
+    let configured = configured.trim();
+    if !configured.is_empty() {
+        let path = Path::new(configured);
+        let found = if path.is_dir() {
+            BUNDLED_NAMES.iter().map(|name| path.join(name)).find(|candidate| candidate.is_file())
+        } else {
+            lifecycle::find_program(configured).filter(|candidate| candidate.is_file())
+        };
+        // A path the user chose is never silently swapped for another install
+        return found.map(|path| (path, BinarySource::Configured)).ok_or_else(|| TranscriptionError::EngineNotFound {
+            program: configured.to_string(),
+            details: "the whisper.cpp path set in Settings does not exist".to_string(),
+        });
+    }
+
+    let bundled = bundled_dirs()
+        .into_iter()
+        .flat_map(|dir| BUNDLED_NAMES.iter().map(move |name| dir.join(name)))
+        .find(|candidate| candidate.is_file());
+    if let Some(path) = bundled {
+        return Ok((path, BinarySource::Bundled));
+    }
+
+    PATH_NAMES.iter()
+        .find_map(|name| lifecycle::find_program(name))
+        .map(|path| (path, BinarySource::Path))
+        .ok_or_else(|| TranscriptionError::EngineNotFound {
+            program: CLI_NAME.to_string(),
+            details: format!("not found next to the app or on PATH (looked for {})", PATH_NAMES.join(", ")),
+        })
+}
+
+/// Tauri resource folders: installed app, dev build, macOS bundle, and the exe's own folder
+fn bundled_dirs() -> Vec<PathBuf> {
+    let Some(dir) = std::env::current_exe().ok().and_then(|exe| exe.parent().map(Path::to_path_buf)) else {
+        return Vec::new();
+    };
+    vec![
+        dir.join("resources"),
+        dir.join("..").join("resources"),
+        dir.join("..").join("Resources").join("resources"),
+        dir.join("..").join("Resources"),
+        dir,
+    ]
+}
+
+async fn validate(mut path: PathBuf, source: BinarySource) -> Result<WhisperBinary, TranscriptionError> {
+    let mut help = help_text(&path).await?;
+
+    // 1.7.3+ keeps `main` around as a stub that only prints a rename notice
+    if is_deprecated_stub(&help) {
+        let replacement = path.with_file_name(CLI_NAME);
+        if replacement != path && replacement.is_file() {
+            log::warn!("{} is a deprecated stub, using {} instead", path.display(), replacement.display());
+            help = help_text(&replacement).await?;
+            path = replacement;
+        }
+    }
+
+    let program = path.display().to_string();
+    let missing = missing_flags(&help);
+    if missing.is_empty() {
+        return Ok(WhisperBinary { version: parse_version(&help), path, source, flags: listed_flags(&help) });
+    }
+
+    let details = if is_deprecated_stub(&help) {
+        format!("this is a deprecated placeholder; use {} from the same release", CLI_NAME)
+    } else if missing.len() == REQUIRED_FLAGS.len() {
+        "this does not look like whisper.cpp (its --help lists none of the whisper.cpp options)".to_string()
+    } else {
+        format!("this build is too old, missing {}; whisper.cpp {} or newer is required", missing.join(" "), MIN_VERSION)
+    };
+    Err(TranscriptionError::EngineIncompatible { program, details })
+}
+
+/// Usage text goes to stderr in most releases and stdout in some; the exit code varies too
+async fn help_text(path: &Path) -> Result<String, TranscriptionError> {
+    let program = path.display().to_string();
+    let output = tokio::process::Command::from(hidden_command(&program))
+        .arg("--help")
+        .stdin(std::process::Stdio::null())
+        .kill_on_drop(true)
+        .output();
+    match tokio::time::timeout(HELP_TIMEOUT, output).await {
+        Ok(Ok(output)) => Ok(format!("{}\n{}", String::from_utf8_lossy(&output.stdout), String::from_utf8_lossy(&output.stderr))),
+        Ok(Err(e)) => Err(TranscriptionError::EngineNotFound { program, details: e.to_string() }),
+        Err(_) => Err(TranscriptionError::EngineIncompatible {
+            program,
+            details: format!("it did not answer --help within {}s", HELP_TIMEOUT.as_secs()),
+        }),
+    }
+}
+
+fn is_deprecated_stub(help: &str) -> bool {
+    help.contains("is deprecated") && help.contains("whisper-cli")
+}
+
+fn listed_flags(help: &str) -> HashSet<String> {
+    help.split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|token| token.starts_with('-'))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Required flags the usage text doesn't mention
+pub fn missing_flags(help: &str) -> Vec<&'static str> {
+    let listed = listed_flags(help);
+    REQUIRED_FLAGS.iter().copied().filter(|flag| !listed.contains(*flag)).collect()
+}
+
+/// Finds "version 1.7.4" / "version: v1.7.4" in builds that print it
+fn parse_version(help: &str) -> Option<String> {
+    help.lines()
+        .filter(|line| line.to_lowercase().contains("version"))
+        .flat_map(|line| line.split(|c: char| c.is_whitespace() || c == ':' || c == ','))
+        .map(|token| token.trim_start_matches('v'))
+        .find(|token| token.split('.').count() == 3 && token.split('.').all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit())))
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HELP_1_7: &str = "
+usage: ./build/bin/whisper-cli [options] file0 file1 ...
+
+options:
+  -h,        --help              [default] show this help message and exit
+  -t N,      --threads N         [4      ] number of threads to use during computation
+  -ml N,     --max-len N         [0      ] maximum segment length in characters
+  -bo N,     --best-of N         [5      ] number of best candidates to keep
+  -bs N,     --beam-size N       [5      ] beam size for beam search
+  -nth N,    --no-speech-thold N [0.60   ] no speech threshold
+  -tp,       --temperature N     [0.00   ] The sampling temperature, between 0 and 1
+  -tpi,      --temperature-inc N [0.20   ] The increment of temperature, between 0 and 1
+  -nf,       --no-fallback       [false  ] do not use temperature fallback while decoding
+  -ojf,      --output-json-full  [false  ] include more information in the JSON file
+  -of FNAME, --output-file FNAME [       ] output file path (without file extension)
+  -pp,       --print-progress    [false  ] print progress
+  -nt,       --no-timestamps     [false  ] do not print timestamps
+  -l LANG,   --language LANG     [en     ] spoken language ('auto' for auto-detect)
+             --prompt PROMPT     [       ] initial prompt (max n_text_ctx/2 tokens)
+  -m FNAME,  --model FNAME       [models/ggml-base.en.bin] model path
+  -f FNAME,  --file FNAME        [       ] input audio file path
+  -sns,      --suppress-nst      [false  ] suppress non-speech tokens
+";
+
+    #[test]
+    fn test_checks_help_for_required_flags() {
+        assert!(missing_flags(HELP_1_7).is_empty());
+        assert_eq!(parse_version(HELP_1_7), None);
+
+        // 1.5.x predates the no-speech threshold and non-speech suppression
+        let old = HELP_1_7.lines().filter(|line| !line.contains("-nth") && !line.contains("-sns")).collect::<Vec<_>>().join("\n");
+        assert_eq!(missing_flags(&old), vec!["-nth"]);
+
+        // Optional flags only fail when the settings use them
+        let no_sns = HELP_1_7.lines().filter(|line| !line.contains("-sns")).collect::<Vec<_>>().join("\n");
+        assert!(missing_flags(&no_sns).is_empty());
+        let binary = WhisperBinary { path: PathBuf::from("whisper-cli"), source: BinarySource::Path, version: None, flags: listed_flags(&no_sns) };
+        assert!(binary.check_flags(&["-tpi", "--prompt"]).is_ok());
+        assert!(matches!(binary.check_flags(&["-tpi", "-sns"]), Err(TranscriptionError::EngineIncompatible { details, .. }) if details.contains("support -sns,")));
+
+        let stub = "WARNING: The binary 'main' is deprecated.\n Please use 'whisper-cli' instead.";
+        assert!(is_deprecated_stub(stub));
+        assert_eq!(missing_flags(stub).len(), REQUIRED_FLAGS.len());
+
+        assert_eq!(parse_version("whisper.cpp version: v1.7.4 (abc123)").as_deref(), Some("1.7.4"));
+    }
+}
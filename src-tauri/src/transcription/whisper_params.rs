@@ -0,0 +1,143 @@
+// Whisper decoding parameters - user-tunable whisper.cpp options shared by every Whisper backend
+//
+// Defaults scale with the machine: threads follow the logical core count (leaving one for
+// audio capture and the UI), and beam search is only used where there are cores to pay for it.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WhisperParams {
+    #[serde(default = "default_threads")]
+    pub threads: usize,
+
+    /// 1 means greedy decoding
+    #[serde(default = "default_beam_size")]
+    pub beam_size: usize,
+
+    /// Candidates sampled when decoding falls back to a non-zero temperature
+    #[serde(default = "default_best_of")]
+    pub best_of: usize,
+
+    #[serde(default)]
+    pub temperature: f32,
+
+    /// Temperature step for fallback when a segment fails the entropy/logprob checks; 0 disables fallback
+    #[serde(default = "default_temperature_inc")]
+    pub temperature_inc: f32,
+
+    /// Maximum segment length in characters; 0 means no limit
+    #[serde(default)]
+    pub max_segment_len: u32,
+
+    #[serde(default = "default_no_speech_threshold")]
+    pub no_speech_threshold: f32,
+
+    #[serde(default = "default_true")]
+    pub suppress_non_speech: bool,
+
+    /// Clips quieter than this RMS level are dropped as silence; 0 disables the check.
+    /// Applied to the transcript afterwards, not passed to whisper.cpp.
+    #[serde(default = "default_min_speech_rms")]
+    pub min_speech_rms: f32,
+}
+
+fn logical_cores() -> usize {
+    std::thread::available_parallelism().map(|n| n.get()).unwrap_or(4)
+}
+
+fn default_threads() -> usize { logical_cores().saturating_sub(1).clamp(1, 8) }
+fn default_beam_size() -> usize { if logical_cores() >= 4 { 5 } else { 1 } }
+fn default_best_of() -> usize { if logical_cores() >= 4 { 5 } else { 2 } }
+fn default_temperature_inc() -> f32 { 0.2 }
+fn default_no_speech_threshold() -> f32 { 0.6 }
+fn default_true() -> bool { true }
+fn default_min_speech_rms() -> f32 { 0.001 }
+
+impl Default for WhisperParams {
+    fn default() -> Self {
+        Self {
+            threads: default_threads(),
+            beam_size: default_beam_size(),
+            best_of: default_best_of(),
+            temperature: 0.0,
+            temperature_inc: default_temperature_inc(),
+            max_segment_len: 0,
+            no_speech_threshold: default_no_speech_threshold(),
+            suppress_non_speech: true,
+            min_speech_rms: default_min_speech_rms(),
+        }
+    }
+}
+
+impl WhisperParams {
+    /// Clamps hand-edited values into ranges whisper.cpp accepts
+    pub fn sanitized(&self) -> Self {
+        Self {
+            threads: self.threads.clamp(1, logical_cores().max(1)),
+            beam_size: self.beam_size.clamp(1, 16),
+            best_of: self.best_of.clamp(1, 16),
+            temperature: self.temperature.clamp(0.0, 1.0),
+            temperature_inc: self.temperature_inc.clamp(0.0, 1.0),
+            max_segment_len: self.max_segment_len,
+            no_speech_threshold: self.no_speech_threshold.clamp(0.0, 1.0),
+            suppress_non_speech: self.suppress_non_speech,
+            min_speech_rms: self.min_speech_rms.clamp(0.0, 0.1),
+        }
+    }
+
+    /// whisper.cpp command-line flags for these parameters
+    pub fn to_args(&self) -> Vec<String> {
+        let params = self.sanitized();
+        let mut args = vec![
+            "-t".to_string(), params.threads.to_string(),
+            "-bs".to_string(), params.beam_size.to_string(),
+            "-bo".to_string(), params.best_of.to_string(),
+            "-tp".to_string(), params.temperature.to_string(),
+            "-nth".to_string(), params.no_speech_threshold.to_string(),
+        ];
+        if params.temperature_inc > 0.0 {
+            args.extend(["-tpi".to_string(), params.temperature_inc.to_string()]);
+        } else {
+            args.push("-nf".to_string()); // --no-fallback
+        }
+        if params.max_segment_len > 0 {
+            args.extend(["-ml".to_string(), params.max_segment_len.to_string()]);
+        }
+        if params.suppress_non_speech {
+            args.push("-sns".to_string()); // --suppress-nst
+        }
+        args
+    }
+
+    /// Flags `to_args` passes only for some settings, so older builds may still work without them
+    pub fn optional_flags(&self) -> Vec<&'static str> {
+        let params = self.sanitized();
+        let mut flags = vec![if params.temperature_inc > 0.0 { "-tpi" } else { "-nf" }];
+        if params.max_segment_len > 0 {
+            flags.push("-ml");
+        }
+        if params.suppress_non_speech {
+            flags.push("-sns");
+        }
+        flags
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_partial_section_keeps_defaults() {
+        let params: WhisperParams = serde_json::from_str(r#"{ "beam_size": 0, "temperature_inc": 0.0 }"#).unwrap();
+        assert_eq!(params.best_of, default_best_of());
+        assert!(params.suppress_non_speech);
+
+        let args = params.to_args();
+        let beam = args.iter().position(|a| a == "-bs").unwrap();
+        assert_eq!(args[beam + 1], "1");
+        assert!(args.contains(&"-nf".to_string()));
+        assert!(!args.contains(&"-tpi".to_string()));
+        assert_eq!(params.optional_flags(), ["-nf", "-sns"]);
+    }
+}
@@ -0,0 +1,255 @@
+// Wyoming protocol provider - self-hosted speech servers (wyoming-faster-whisper, etc.)
+//
+// Every event is a single JSON header line, optionally followed by extra data and a
+// binary payload whose sizes are announced in the header:
+//
+//   {"type":"audio-chunk","data":{...},"data_length":N,"payload_length":M}\n
+//   <N bytes of JSON merged into "data"><M bytes of payload>
+//
+// A transcription session is `transcribe` -> `audio-start` -> `audio-chunk`* -> `audio-stop`,
+// answered by a single `transcript` event.
+
+use super::lifecycle::{self, HealthStatus};
+use super::{pcm16_le_bytes, AudioBuffer, TranscriptionProvider};
+use crate::errors::TranscriptionError;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+
+const PROTOCOL_VERSION: &str = "1.5.0";
+
+/// Samples per `audio-chunk` event
+const CHUNK_SAMPLES: usize = 1024;
+
+const CONNECT_TIMEOUT_SECS: u64 = 5;
+const RESPONSE_TIMEOUT_SECS: u64 = 120;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct EventHeader {
+    #[serde(rename = "type")]
+    event_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    data: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    data_length: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    payload_length: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    version: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WyomingEvent {
+    pub event_type: String,
+    pub data: Value,
+    pub payload: Vec<u8>,
+}
+
+impl WyomingEvent {
+    pub fn new(event_type: &str, data: Value) -> Self {
+        Self { event_type: event_type.to_string(), data, payload: Vec::new() }
+    }
+
+    pub fn with_payload(mut self, payload: Vec<u8>) -> Self {
+        self.payload = payload;
+        self
+    }
+}
+
+pub async fn write_event<W: AsyncWrite + Unpin>(writer: &mut W, event: &WyomingEvent) -> std::io::Result<()> {
+    let header = EventHeader {
+        event_type: event.event_type.clone(),
+        data: if event.data.is_null() { None } else { Some(event.data.clone()) },
+        data_length: None,
+        payload_length: if event.payload.is_empty() { None } else { Some(event.payload.len()) },
+        version: Some(PROTOCOL_VERSION.to_string()),
+    };
+    let mut line = serde_json::to_vec(&header)?;
+    line.push(b'\n');
+    writer.write_all(&line).await?;
+    if !event.payload.is_empty() {
+        writer.write_all(&event.payload).await?;
+    }
+    writer.flush().await
+}
+
+/// Reads the next event, or `None` when the peer closed the connection
+pub async fn read_event<R: tokio::io::AsyncBufRead + Unpin>(reader: &mut R) -> std::io::Result<Option<WyomingEvent>> {
+    let mut line = String::new();
+    if reader.read_line(&mut line).await? == 0 {
+        return Ok(None);
+    }
+    let header: EventHeader = serde_json::from_str(line.trim())?;
+
+    let mut data = header.data.unwrap_or(Value::Null);
+    if let Some(len) = header.data_length.filter(|&len| len > 0) {
+        let mut buf = vec![0u8; len];
+        reader.read_exact(&mut buf).await?;
+        let extra: Value = serde_json::from_slice(&buf)?;
+        // Extra data takes precedence over inline fields
+        match (&mut data, extra) {
+            (Value::Object(inline), Value::Object(extra)) => inline.extend(extra),
+            (data, extra) => *data = extra,
+        }
+    }
+
+    let mut payload = Vec::new();
+    if let Some(len) = header.payload_length.filter(|&len| len > 0) {
+        payload.resize(len, 0);
+        reader.read_exact(&mut payload).await?;
+    }
+
+    Ok(Some(WyomingEvent { event_type: header.event_type, data, payload }))
+}
+
+pub struct WyomingProvider {
+    address: String,
+    language: String,
+}
+
+impl WyomingProvider {
+    pub fn new(host: &str, port: u16, language: String) -> Self {
+        let address = format!("{}:{}", host, port);
+        log::info!("WyomingProvider initialized with server: {}", address);
+        Self { address, language }
+    }
+}
+
+#[async_trait]
+impl TranscriptionProvider for WyomingProvider {
+    async fn transcribe(&self, audio: AudioBuffer, sample_rate: u32) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        log::info!("Transcribing {} samples at {}Hz via Wyoming server {}", audio.len(), sample_rate, self.address);
+
+        let stream = tokio::time::timeout(
+            std::time::Duration::from_secs(CONNECT_TIMEOUT_SECS),
+            TcpStream::connect(&self.address),
+        )
+        .await
+        .map_err(|_| TranscriptionError::Timeout { provider: "Wyoming".to_string(), secs: CONNECT_TIMEOUT_SECS as f32 })?
+        .map_err(|e| TranscriptionError::Connection { endpoint: format!("Wyoming server at {}", self.address), details: e.to_string() })?;
+
+        let (read_half, mut write_half) = stream.into_split();
+        let mut reader = BufReader::new(read_half);
+
+        let format = json!({ "rate": sample_rate, "width": 2, "channels": 1 });
+
+        write_event(&mut write_half, &WyomingEvent::new("transcribe", json!({ "language": self.language }))).await?;
+        write_event(&mut write_half, &WyomingEvent::new("audio-start", format.clone())).await?;
+        for chunk in audio.chunks(CHUNK_SAMPLES) {
+            let event = WyomingEvent::new("audio-chunk", format.clone()).with_payload(pcm16_le_bytes(chunk));
+            write_event(&mut write_half, &event).await?;
+        }
+        write_event(&mut write_half, &WyomingEvent::new("audio-stop", Value::Null)).await?;
+
+        let transcript = tokio::time::timeout(std::time::Duration::from_secs(RESPONSE_TIMEOUT_SECS), async {
+            while let Some(event) = read_event(&mut reader).await? {
+                match event.event_type.as_str() {
+                    "transcript" => {
+                        let text = event.data.get("text").and_then(Value::as_str).unwrap_or_default();
+                        return Ok(text.trim().to_string());
+                    }
+                    "error" => {
+                        let message = event.data.get("text").and_then(Value::as_str).unwrap_or("unknown error");
+                        return Err(TranscriptionError::EngineFailed { engine: "Wyoming server".to_string(), details: message.to_string() }.into());
+                    }
+                    other => log::debug!("Ignoring Wyoming event: {}", other),
+                }
+            }
+            Err::<String, Box<dyn std::error::Error + Send + Sync>>("Wyoming server closed the connection without a transcript".into())
+        })
+        .await
+        .map_err(|_| TranscriptionError::Timeout { provider: "Wyoming".to_string(), secs: RESPONSE_TIMEOUT_SECS as f32 })??;
+
+        log::info!("Wyoming transcription complete: '{}' (len: {})", transcript, transcript.len());
+        Ok(transcript)
+    }
+
+    fn name(&self) -> &'static str {
+        "Wyoming"
+    }
+
+    fn supports_streaming(&self) -> bool {
+        false
+    }
+
+    async fn health_check(&self) -> HealthStatus {
+        lifecycle::probe_tcp(&self.address, "Wyoming server").await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    /// Fake Wyoming ASR server: checks the event sequence and answers with the
+    /// number of audio bytes it received. The transcript uses `data_length`
+    /// framing like current wyoming releases.
+    async fn spawn_fake_server() -> u16 {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let (read_half, mut write_half) = stream.into_split();
+            let mut reader = BufReader::new(read_half);
+
+            let transcribe = read_event(&mut reader).await.unwrap().unwrap();
+            assert_eq!(transcribe.event_type, "transcribe");
+            assert_eq!(transcribe.data["language"], "en");
+
+            let start = read_event(&mut reader).await.unwrap().unwrap();
+            assert_eq!(start.event_type, "audio-start");
+            assert_eq!(start.data["rate"], 16000);
+
+            let mut audio_bytes = 0;
+            loop {
+                let event = read_event(&mut reader).await.unwrap().unwrap();
+                match event.event_type.as_str() {
+                    "audio-chunk" => audio_bytes += event.payload.len(),
+                    "audio-stop" => break,
+                    other => panic!("unexpected event: {}", other),
+                }
+            }
+
+            let data = format!(r#"{{"text":" heard {} bytes "}}"#, audio_bytes);
+            let header = format!("{{\"type\":\"transcript\",\"data_length\":{}}}\n", data.len());
+            write_half.write_all(header.as_bytes()).await.unwrap();
+            write_half.write_all(data.as_bytes()).await.unwrap();
+        });
+
+        port
+    }
+
+    #[tokio::test]
+    async fn test_transcribe_against_fake_server() {
+        let port = spawn_fake_server().await;
+        let provider = WyomingProvider::new("127.0.0.1", port, "en".to_string());
+
+        let text = provider.transcribe(vec![0.25; 2500], 16000).await.unwrap();
+        assert_eq!(text, "heard 5000 bytes");
+    }
+
+    #[tokio::test]
+    async fn test_event_round_trip() {
+        let event = WyomingEvent::new("audio-chunk", json!({ "rate": 16000 })).with_payload(vec![1, 2, 3, 4]);
+        let mut buf = Vec::new();
+        write_event(&mut buf, &event).await.unwrap();
+
+        let mut reader = BufReader::new(buf.as_slice());
+        assert_eq!(read_event(&mut reader).await.unwrap(), Some(event));
+        assert_eq!(read_event(&mut reader).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_connection_refused_is_reported() {
+        // Bind and drop to get a port nothing is listening on
+        let port = TcpListener::bind("127.0.0.1:0").await.unwrap().local_addr().unwrap().port();
+        let provider = WyomingProvider::new("127.0.0.1", port, "en".to_string());
+
+        let err = provider.transcribe(vec![0.0; 160], 16000).await.unwrap_err();
+        assert!(err.to_string().contains("Failed to connect to Wyoming server"));
+    }
+}
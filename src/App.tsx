@@ -57,7 +57,7 @@ function App() {
   return (
     <div className={`app-container ${view === 'hud' ? 'hud-mode' : ''}`}>
       {view === 'settings' && <SettingsWindow />}
-      {view === 'hud' && <RecordingHUD isRecording={true} duration={duration} />}
+      {view === 'hud' && <RecordingHUD isRecording={isRecording} duration={duration} />}
     </div>
   );
 }
@@ -1,4 +1,20 @@
 import React, { useEffect, useState, useRef } from 'react';
+import { listen } from '@tauri-apps/api/event';
+
+// Mirrors errors::ErrorPayload in the backend
+interface PipelineError {
+    code: string;
+    category: string;
+    message: string;
+    hint: string;
+}
+
+// Mirrors transcription::TranscriptionProgress in the backend
+interface TranscriptionProgress {
+    provider: string;
+    percent: number;
+    elapsed_ms: number;
+}
 
 interface RecordingHUDProps {
     isRecording: boolean;
@@ -11,6 +27,61 @@ export const RecordingHUD: React.FC<RecordingHUDProps> = ({ isRecording }) => {
     const animationRef = useRef<number | undefined>(undefined);
     const rmsRef = useRef<number>(0);
     const targetRmsRef = useRef<number>(0);
+    const [interimText, setInterimText] = useState('');
+    const [queueDepth, setQueueDepth] = useState(0);
+    const [error, setError] = useState<PipelineError | null>(null);
+    const [progress, setProgress] = useState<TranscriptionProgress | null>(null);
+
+    // Percent done for long transcriptions; cleared when the job finishes
+    useEffect(() => {
+        const unlistenProgress = listen<TranscriptionProgress>('transcription-progress', (event) => setProgress(event.payload));
+        const unlistenDepth = listen<number>('queue-depth', () => setProgress(null));
+        const unlistenCancelled = listen('transcription-cancelled', () => setProgress(null));
+        return () => {
+            unlistenProgress.then((fn) => fn());
+            unlistenDepth.then((fn) => fn());
+            unlistenCancelled.then((fn) => fn());
+        };
+    }, []);
+
+    // Failures from any pipeline stage, shown briefly with a remediation hint
+    useEffect(() => {
+        let timer: ReturnType<typeof setTimeout> | undefined;
+        const unlistenError = listen<PipelineError>('pipeline-error', (event) => {
+            setError(event.payload);
+            clearTimeout(timer);
+            timer = setTimeout(() => setError(null), 5000);
+        });
+        const unlistenStart = listen('recording-start', () => setError(null));
+        return () => {
+            clearTimeout(timer);
+            unlistenError.then((fn) => fn());
+            unlistenStart.then((fn) => fn());
+        };
+    }, []);
+
+    // Earlier dictations still being transcribed
+    useEffect(() => {
+        const unlisten = listen<number>('queue-depth', (event) => setQueueDepth(event.payload));
+        return () => {
+            unlisten.then((fn) => fn());
+        };
+    }, []);
+
+    // Live text from streaming providers
+    useEffect(() => {
+        const unlistenInterim = listen<string>('transcription-interim', (event) => {
+            setInterimText(event.payload);
+        });
+        const unlistenStart = listen('recording-start', () => setInterimText(''));
+        const unlistenCancelled = listen('transcription-cancelled', () => setInterimText(''));
+
+        return () => {
+            unlistenInterim.then((fn) => fn());
+            unlistenStart.then((fn) => fn());
+            unlistenCancelled.then((fn) => fn());
+        };
+    }, []);
 
     // Smooth fade in/out
     useEffect(() => {
@@ -106,7 +177,8 @@ export const RecordingHUD: React.FC<RecordingHUDProps> = ({ isRecording }) => {
         };
     }, [isRecording]);
 
-    if (!visible) return null;
+    const processing = !isRecording && queueDepth > 0;
+    if (!visible && !error && !processing) return null;
 
     return (
         <div style={{
@@ -131,8 +203,8 @@ export const RecordingHUD: React.FC<RecordingHUDProps> = ({ isRecording }) => {
                     0 0 0 1px rgba(106, 227, 255, 0.1) inset,
                     0 0 40px rgba(106, 227, 255, 0.2)
                 `,
-                transform: isRecording ? 'translateY(0) scale(1)' : 'translateY(10px) scale(0.95)',
-                opacity: isRecording ? 1 : 0,
+                transform: isRecording || error || processing ? 'translateY(0) scale(1)' : 'translateY(10px) scale(0.95)',
+                opacity: isRecording || error || processing ? 1 : 0,
                 transition: 'all 0.3s cubic-bezier(0.34, 1.56, 0.64, 1)',
             }}>
                 <canvas 
@@ -140,9 +212,57 @@ export const RecordingHUD: React.FC<RecordingHUDProps> = ({ isRecording }) => {
                     style={{
                         width: '160px',
                         height: '40px',
-                        display: 'block',
+                        display: isRecording ? 'block' : 'none',
                     }}
                 />
+                {interimText && (
+                    <div style={{
+                        maxWidth: '240px',
+                        marginTop: '4px',
+                        fontSize: '11px',
+                        color: 'rgba(255, 255, 255, 0.8)',
+                        whiteSpace: 'nowrap',
+                        overflow: 'hidden',
+                        textOverflow: 'ellipsis',
+                    }}>
+                        {interimText}
+                    </div>
+                )}
+                {error && !isRecording && (
+                    <div style={{ maxWidth: '240px', fontSize: '11px', textAlign: 'center' }}>
+                        <div style={{ color: '#FF6A6A', fontWeight: 600 }}>{error.message}</div>
+                        <div style={{ marginTop: '2px', color: 'rgba(255, 255, 255, 0.7)' }}>{error.hint}</div>
+                    </div>
+                )}
+                {processing && !error && (
+                    <div style={{ width: '160px', fontSize: '11px', textAlign: 'center', color: 'rgba(255, 255, 255, 0.8)' }}>
+                        Transcribing{progress ? ` ${progress.percent}% · ${Math.round(progress.elapsed_ms / 1000)}s` : '…'}
+                        <div style={{
+                            marginTop: '4px',
+                            height: '3px',
+                            borderRadius: '2px',
+                            background: 'rgba(106, 227, 255, 0.2)',
+                            overflow: 'hidden',
+                        }}>
+                            <div style={{
+                                width: `${progress ? progress.percent : 0}%`,
+                                height: '100%',
+                                background: '#6AE3FF',
+                                transition: 'width 0.3s ease',
+                            }} />
+                        </div>
+                    </div>
+                )}
+                {queueDepth > 0 && (
+                    <div style={{
+                        marginTop: '4px',
+                        fontSize: '10px',
+                        textAlign: 'center',
+                        color: 'rgba(106, 227, 255, 0.8)',
+                    }}>
+                        {queueDepth} processing
+                    </div>
+                )}
             </div>
         </div>
     );
@@ -0,0 +1,51 @@
+import React, { useEffect, useState } from 'react';
+import { listen } from '@tauri-apps/api/event';
+
+type DiffOp = { op: 'equal' | 'delete' | 'insert'; text: string };
+
+interface Refinement {
+    draft: string;
+    refined: string;
+    diff: DiffOp[];
+}
+
+// Shown when a two-pass refinement couldn't replace the draft because it was edited
+export const RefinementPrompt: React.FC = () => {
+    const [refinement, setRefinement] = useState<Refinement | null>(null);
+
+    useEffect(() => {
+        const unlisten = listen<Refinement>('refinement-available', (event) => {
+            setRefinement(event.payload);
+        });
+        return () => {
+            unlisten.then((fn) => fn());
+        };
+    }, []);
+
+    if (!refinement) return null;
+
+    const copyRefined = async () => {
+        await navigator.clipboard.writeText(refinement.refined);
+        setRefinement(null);
+    };
+
+    return (
+        <div className="setting-card refinement-prompt">
+            <div className="card-header">
+                <h3>Refined transcript available</h3>
+            </div>
+            <div className="card-body">
+                <p className="input-hint">
+                    The draft was edited or the window changed, so it wasn't replaced automatically.
+                </p>
+                <p className="refinement-diff">
+                    {refinement.diff.map((part, index) => (
+                        <span key={index} className={`diff-${part.op}`}>{part.text} </span>
+                    ))}
+                </p>
+                <button className="download-button" onClick={copyRefined}>Copy refined text</button>
+                <button className="secondary-button" onClick={() => setRefinement(null)}>Dismiss</button>
+            </div>
+        </div>
+    );
+};
@@ -4,8 +4,183 @@ import { invoke } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
 import { check } from '@tauri-apps/plugin-updater';
 import { FlowGradientCursor } from './ui/flow-gradient-cursor';
+import { RefinementPrompt } from './RefinementPrompt';
 import '../styles.css';
 
+interface ChainEntry {
+    provider: string;
+    timeout_ms: number;
+}
+
+interface RoutingRule {
+    name: string;
+    min_duration_secs?: number | null;
+    max_duration_secs?: number | null;
+    languages?: string[];
+    power?: 'ac' | 'battery' | 'unknown' | null;
+    network?: boolean | null;
+    provider: string;
+    model?: string | null;
+    timeout_ms?: number;
+}
+
+const CHAIN_PROVIDERS = ['Whisper', 'Onnx', 'Cloud', 'Streaming', 'Wyoming', 'Command', 'SaveForLater', 'Mock'];
+
+// Mirrors errors::ErrorPayload in the backend
+interface ErrorPayload {
+    code: string;
+    category: string;
+    message: string;
+    hint: string;
+}
+
+// Agreement between the active and shadow providers (eval::ShadowReport)
+interface ShadowReport {
+    samples: number;
+    agreements: number;
+    shadow_failures: number;
+    agreement_rate: number;
+    mean_wer: number;
+    mean_primary_latency_ms: number;
+    mean_shadow_latency_ms: number;
+}
+
+// Health, capabilities and warm-up of one fallback chain step (transcription::ProviderReport)
+interface ProviderReport {
+    provider: string;
+    position: number;
+    capabilities: {
+        languages: { kind: 'any' | 'unknown' } | { kind: 'only'; codes: string[] };
+        streaming: boolean;
+        timestamps: boolean;
+        translation: boolean;
+        context: boolean;
+    };
+    health: {
+        state: 'ready' | 'degraded' | 'unavailable';
+        message: string;
+        error: ErrorPayload | null;
+    };
+    warm_up_ms: number | null;
+    warm_up_error: string | null;
+}
+
+function describeCapabilities({ capabilities }: ProviderReport): string {
+    const { languages } = capabilities;
+    const parts = [
+        languages.kind === 'any' ? 'All languages' : languages.kind === 'only' ? languages.codes.join(', ').toUpperCase() : 'Languages set by server',
+    ];
+    if (capabilities.streaming) parts.push('streaming');
+    if (capabilities.timestamps) parts.push('timestamps');
+    if (capabilities.translation) parts.push('translation');
+    if (capabilities.context) parts.push('uses context');
+    return parts.join(' · ');
+}
+
+// Cloud dictation waiting for the network (offline::OfflineJob)
+interface OfflineJob {
+    id: string;
+    provider: string;
+    created_at: string;
+    audio_secs: number;
+    attempts: number;
+    next_attempt_ms: number;
+    last_error: string | null;
+}
+
+// Downloadable model from the catalog manifest (models::CatalogEntry)
+interface CatalogModel {
+    id: string;
+    display_name: string;
+    provider: 'Whisper' | 'Onnx';
+    family: string;
+    languages: string[];
+    quantization: string | null;
+    size_mb: number;
+    ram_mb: number;
+    installed: boolean;
+}
+
+function formatMb(mb: number): string {
+    return mb >= 1000 ? `${(mb / 1000).toFixed(1)}GB` : `${mb}MB`;
+}
+
+function describeModel(model: CatalogModel): string {
+    const details = [`~${formatMb(model.size_mb)}`, `${formatMb(model.ram_mb)} RAM`];
+    if (model.languages.length > 0) details.push(`${model.languages.join(', ').toUpperCase()} only`);
+    if (model.installed) details.push('installed');
+    return `${model.display_name} (${details.join(', ')})`;
+}
+
+// Installed models with disk usage (models::manage::ModelInventory)
+interface InstalledModel {
+    id: string;
+    display_name: string;
+    provider: 'Whisper' | 'Onnx';
+    path: string;
+    size_bytes: number;
+    verification: 'verified' | 'unverified' | 'corrupt';
+}
+
+interface ModelInventory {
+    models_dir: string;
+    models: InstalledModel[];
+    total_bytes: number;
+    partial_bytes: number;
+}
+
+// Hardware tuning results (benchmark::tune::TuneReport)
+interface TuneTrial {
+    model: string;
+    threads: number;
+    rtf: number;
+    expected_latency_ms: number;
+    peak_memory_mb: number | null;
+    error: string | null;
+}
+
+interface TuneReport {
+    audio_secs: number;
+    logical_cores: number;
+    latency_target_ms: number;
+    trials: TuneTrial[];
+    skipped: string[];
+    recommendation: { model: string; threads: number; expected_latency_ms: number; meets_target: boolean } | null;
+}
+
+// Located whisper.cpp program (transcription::WhisperBinary)
+interface WhisperBinary {
+    path: string;
+    source: 'configured' | 'bundled' | 'path';
+    version: string | null;
+}
+
+// Where the app keeps its files (paths::StoragePaths)
+interface StoragePaths {
+    portable: boolean;
+    config_dir: string;
+    data_dir: string;
+    log_dir: string;
+    default_models_dir: string;
+}
+
+function formatBytes(bytes: number): string {
+    return formatMb(Math.round(bytes / 1_000_000));
+}
+
+// whisper.cpp decoding options; defaults come from the backend based on CPU cores
+interface WhisperParams {
+    threads: number;
+    beam_size: number;
+    best_of: number;
+    temperature: number;
+    temperature_inc: number;
+    max_segment_len: number;
+    no_speech_threshold: number;
+    suppress_non_speech: boolean;
+    min_speech_rms: number;
+}
+
 interface AppConfig {
     autostart: boolean;
     hotkey: string;
@@ -17,6 +192,31 @@ interface AppConfig {
     focus_delay_ms: number;
     enable_typing_fallback: boolean;
     whisper_model: string;
+    whisper: WhisperParams;
+    onnx_model: string;
+    streaming_endpoint: string;
+    wyoming_host: string;
+    wyoming_port: number;
+    command_template: string;
+    command_output: { mode: 'stdout' } | { mode: 'json_path' | 'regex'; pattern: string };
+    provider_chain: ChainEntry[];
+    routing_rules: RoutingRule[];
+    network_probe_address: string | null;
+    two_pass_enabled: boolean;
+    two_pass_draft_model: string;
+    two_pass_min_secs: number;
+    shadow_provider: string;
+    shadow_whisper_model: string;
+    context_enabled: boolean;
+    context_max_dictations: number;
+    context_idle_reset_secs: number;
+    offline_queue_enabled: boolean;
+    model_catalog_url: string;
+    latency_target_ms: number;
+    whisper_binary_path: string;
+    custom_models_dir: string;
+    queue_policy: 'queue' | 'drop' | 'merge';
+    max_queued_jobs: number;
 }
 
 type TabType = 'general' | 'hotkeys' | 'audio' | 'transcription' | 'advanced' | 'about';
@@ -34,13 +234,62 @@ export const SettingsWindow: React.FC = () => {
         focus_delay_ms: 100,
         enable_typing_fallback: true,
         whisper_model: 'base',
+        whisper: {
+            threads: 4,
+            beam_size: 5,
+            best_of: 5,
+            temperature: 0,
+            temperature_inc: 0.2,
+            max_segment_len: 0,
+            no_speech_threshold: 0.6,
+            suppress_non_speech: true,
+            min_speech_rms: 0.001,
+        },
+        onnx_model: 'moonshine-tiny',
+        streaming_endpoint: 'ws://127.0.0.1:8765/v1/stream',
+        wyoming_host: '127.0.0.1',
+        wyoming_port: 10300,
+        command_template: '',
+        command_output: { mode: 'stdout' },
+        provider_chain: [],
+        routing_rules: [],
+        network_probe_address: null,
+        two_pass_enabled: false,
+        two_pass_draft_model: 'tiny',
+        two_pass_min_secs: 6,
+        shadow_provider: '',
+        shadow_whisper_model: '',
+        context_enabled: false,
+        context_max_dictations: 3,
+        context_idle_reset_secs: 120,
+        offline_queue_enabled: true,
+        model_catalog_url: '',
+        latency_target_ms: 1500,
+        whisper_binary_path: '',
+        custom_models_dir: '',
+        queue_policy: 'queue',
+        max_queued_jobs: 3,
     });
     
     const [modelExists, setModelExists] = useState(false);
     const [modelsDir, setModelsDir] = useState('');
+    const [storagePaths, setStoragePaths] = useState<StoragePaths | null>(null);
     const [saveStatus, setSaveStatus] = useState<'idle' | 'saving' | 'saved'>('idle');
+    const [saveError, setSaveError] = useState<ErrorPayload | null>(null);
+    const [shadowReport, setShadowReport] = useState<ShadowReport | null>(null);
+    const [providerStatus, setProviderStatus] = useState<ProviderReport[]>([]);
+    const [checkingProviders, setCheckingProviders] = useState(false);
+    const [offlineJobs, setOfflineJobs] = useState<OfflineJob[]>([]);
+    const [catalog, setCatalog] = useState<CatalogModel[]>([]);
+    const [catalogStatus, setCatalogStatus] = useState<string | null>(null);
+    const [inventory, setInventory] = useState<ModelInventory | null>(null);
+    const [importPath, setImportPath] = useState('');
+    const [modelAction, setModelAction] = useState<{ busy: boolean; message: string | null }>({ busy: false, message: null });
+    const [tuning, setTuning] = useState<{ running: boolean; trials: TuneTrial[]; report: TuneReport | null; error: ErrorPayload | null }>({ running: false, trials: [], report: null, error: null });
+    const [whisperBinary, setWhisperBinary] = useState<string | null>(null);
+    const [restartRequired, setRestartRequired] = useState<string[]>([]);
     const [downloadProgress, setDownloadProgress] = useState<number | null>(null);
-    const [downloadError, setDownloadError] = useState<string | null>(null);
+    const [downloadError, setDownloadError] = useState<ErrorPayload | null>(null);
     
     // Update states
     const [updateAvailable, setUpdateAvailable] = useState(false);
@@ -57,52 +306,192 @@ export const SettingsWindow: React.FC = () => {
         invoke<string>('get_models_dir').then((dir) => {
             setModelsDir(dir);
         }).catch(err => console.error('Failed to get models dir:', err));
+
+        invoke<StoragePaths>('get_storage_paths').then(setStoragePaths)
+            .catch(err => console.error('Failed to get storage paths:', err));
         
+        invoke<CatalogModel[]>('get_model_catalog').then(setCatalog)
+            .catch(err => console.error('Failed to load model catalog:', err));
+
+        invoke<ModelInventory>('list_installed_models').then(setInventory)
+            .catch(err => console.error('Failed to list models:', err));
+
+        invoke<OfflineJob[]>('get_offline_queue').then(setOfflineJobs)
+            .catch(err => console.error('Failed to get offline queue:', err));
+
+        invoke<ProviderReport[]>('get_provider_status').then(setProviderStatus)
+            .catch(err => console.error('Failed to get provider status:', err));
+
         // Listen for download progress events
         const unlisten = listen<number>('download-progress', (event) => {
             setDownloadProgress(event.payload);
         });
+        // Launch-time check finishes after the window may already be open
+        const unlistenStatus = listen<ProviderReport[]>('provider-status', (event) => {
+            setProviderStatus(event.payload);
+        });
+        const unlistenOffline = listen('offline-dictation-delivered', () => {
+            invoke<OfflineJob[]>('get_offline_queue').then(setOfflineJobs);
+        });
+        const unlistenExpired = listen('offline-dictation-expired', () => {
+            invoke<OfflineJob[]>('get_offline_queue').then(setOfflineJobs);
+        });
+        const unlistenTuning = listen<TuneTrial>('tuning-trial', (event) => {
+            setTuning(prev => ({ ...prev, trials: [...prev.trials, event.payload] }));
+        });
+        // Settings such as the hotkey are only read at startup
+        const unlistenRestart = listen<string[]>('restart-required', (event) => {
+            setRestartRequired(event.payload);
+        });
+        // config.json edited outside the app; the backend has already applied it
+        const unlistenConfig = listen<AppConfig>('config-changed', (event) => {
+            setConfig(prev => ({ ...prev, ...event.payload }));
+        });
         
         // Check for updates on startup (silently)
         checkForUpdates();
         
         return () => {
             unlisten.then((fn) => fn());
+            unlistenStatus.then((fn) => fn());
+            unlistenOffline.then((fn) => fn());
+            unlistenExpired.then((fn) => fn());
+            unlistenConfig.then((fn) => fn());
+            unlistenRestart.then((fn) => fn());
+            unlistenTuning.then((fn) => fn());
         };
     }, []);
+
+    const recheckProviders = async () => {
+        setCheckingProviders(true);
+        try {
+            setProviderStatus(await invoke<ProviderReport[]>('check_providers'));
+        } catch (err) {
+            console.error('Failed to check providers:', err);
+        } finally {
+            setCheckingProviders(false);
+        }
+    };
     
+    // Local providers share the download flow; the model id decides which files are fetched
+    const isLocalProvider = config.provider === 'Whisper' || config.provider === 'Onnx';
+    const activeModel = config.provider === 'Onnx' ? config.onnx_model : config.whisper_model;
+
     useEffect(() => {
-        if (config.provider === 'Whisper') {
-            invoke<boolean>('check_model_exists', { modelName: config.whisper_model }).then((exists) => {
+        if (isLocalProvider) {
+            invoke<boolean>('check_model_exists', { modelName: activeModel }).then((exists) => {
                 setModelExists(exists);
             }).catch(err => console.error('Failed to check model:', err));
         }
-    }, [config.provider, config.whisper_model]);
+    }, [config.provider, activeModel]);
 
     const handleDownloadModel = async () => {
         try {
             setDownloadProgress(0);
             setDownloadError(null);
             
-            await invoke('download_model', { modelName: config.whisper_model });
+            await invoke('download_model', { modelName: activeModel });
             
             setDownloadProgress(null);
             setModelExists(true);
+            await refreshModels();
         } catch (error) {
             console.error('Failed to download model:', error);
-            setDownloadError(String(error));
+            setDownloadError(error as ErrorPayload);
             setDownloadProgress(null);
         }
     };
 
+    const refreshCatalog = async () => {
+        setCatalogStatus('Checking…');
+        try {
+            // The backend reads the URL from the saved config
+            await invoke('save_config', { config });
+            const models = await invoke<CatalogModel[]>('refresh_model_catalog');
+            setCatalog(models);
+            setCatalogStatus(`${models.length} models available`);
+        } catch (error) {
+            const payload = error as ErrorPayload;
+            setCatalogStatus(`${payload.message}. ${payload.hint}`);
+        }
+    };
+
+    const refreshModels = async () => {
+        setInventory(await invoke<ModelInventory>('list_installed_models'));
+        setCatalog(await invoke<CatalogModel[]>('get_model_catalog'));
+        if (isLocalProvider) {
+            setModelExists(await invoke<boolean>('check_model_exists', { modelName: activeModel }));
+        }
+    };
+
+    // Runs a model command, then reloads the inventory; errors arrive as ErrorPayload
+    const runModelAction = async (action: () => Promise<string>) => {
+        setModelAction({ busy: true, message: null });
+        try {
+            const message = await action();
+            setModelAction({ busy: false, message });
+        } catch (error) {
+            const payload = error as ErrorPayload;
+            setModelAction({ busy: false, message: `${payload.message}. ${payload.hint}` });
+        }
+        await refreshModels();
+    };
+
+    const checkWhisperBinary = async () => {
+        try {
+            const binary = await invoke<WhisperBinary>('check_whisper_binary', { path: config.whisper_binary_path });
+            setWhisperBinary(`Found ${binary.version ? `version ${binary.version}` : 'a compatible build'} (${binary.source}) at ${binary.path}`);
+        } catch (error) {
+            const payload = error as ErrorPayload;
+            setWhisperBinary(`${payload.message}. ${payload.hint}`);
+        }
+    };
+
+    const runTuning = async () => {
+        setTuning({ running: true, trials: [], report: null, error: null });
+        try {
+            const report = await invoke<TuneReport>('tune_models', { latencyTargetMs: config.latency_target_ms, apply: false });
+            setTuning(prev => ({ ...prev, running: false, report }));
+        } catch (error) {
+            setTuning(prev => ({ ...prev, running: false, error: error as ErrorPayload }));
+        }
+    };
+
+    const applyRecommendation = async (model: string, threads: number) => {
+        const tuned = { ...config, whisper_model: model, whisper: { ...config.whisper, threads } };
+        setConfig(tuned);
+        await invoke('save_config', { config: tuned });
+    };
+
+    const handleCancelDownload = () => {
+        invoke('cancel_download', { modelName: activeModel })
+            .catch(err => console.error('Failed to cancel download:', err));
+    };
+
+    const updateChainEntry = (index: number, entry: Partial<ChainEntry>) => {
+        setConfig({
+            ...config,
+            provider_chain: config.provider_chain.map((e, i) => (i === index ? { ...e, ...entry } : e)),
+        });
+    };
+
+    const updateWhisper = (params: Partial<WhisperParams>) => {
+        setConfig({ ...config, whisper: { ...config.whisper, ...params } });
+    };
+
     const handleSave = async () => {
         try {
             setSaveStatus('saving');
+            setSaveError(null);
             await invoke('save_config', { config });
             setSaveStatus('saved');
+            // The models folder may have moved
+            setModelsDir(await invoke<string>('get_models_dir'));
+            await refreshModels();
             setTimeout(() => setSaveStatus('idle'), 2000);
         } catch (error) {
             console.error('Failed to save config:', error);
+            setSaveError(error as ErrorPayload);
             setSaveStatus('idle');
         }
     };
@@ -235,6 +624,13 @@ export const SettingsWindow: React.FC = () => {
                 </div>
 
                 <div className="content-body">
+                    {saveError && (
+                        <div className="alert alert-warning">
+                            <div className="alert-title">⚠️ {saveError.message}</div>
+                            <p className="error-text">{saveError.hint}</p>
+                        </div>
+                    )}
+                    <RefinementPrompt />
                     {/* General Tab */}
                     {activeTab === 'general' && (
                         <div className="settings-grid">
@@ -357,6 +753,9 @@ export const SettingsWindow: React.FC = () => {
                                         <button className="hotkey-change-btn">Change</button>
                                     </div>
                                     <p className="input-hint">Click "Change" and press your desired key combination</p>
+                                    {restartRequired.includes('hotkey') && (
+                                        <p className="input-hint">Restart Vanta Dictate to use the new hotkey</p>
+                                    )}
                                 </div>
                             </div>
                         </div>
@@ -393,22 +792,102 @@ export const SettingsWindow: React.FC = () => {
                                         onChange={(e) => setConfig({ ...config, provider: e.target.value })}
                                     >
                                         <option value="Whisper">Whisper (Local, Offline) - Default</option>
+                                        <option value="Onnx">ONNX (Local, Offline, Moonshine)</option>
                                         <option value="Mock">Mock Provider (Testing Only)</option>
                                         <option value="Cloud">Cloud STT (Coming Soon)</option>
+                                        <option value="Streaming">Streaming Cloud (WebSocket)</option>
+                                        <option value="Wyoming">Wyoming Server (Self-Hosted)</option>
+                                        <option value="Command">External Command</option>
                                     </select>
 
-                                    {config.provider === 'Whisper' && (
+                                    {config.provider === 'Command' && (
+                                        <div className="whisper-config">
+                                            <label className="input-label">Command Template</label>
+                                            <input
+                                                type="text"
+                                                className="select-input"
+                                                placeholder="my-stt --in {wav} --lang {lang}"
+                                                value={config.command_template}
+                                                onChange={(e) => setConfig({ ...config, command_template: e.target.value })}
+                                            />
+                                            <label className="input-label">Output Parsing</label>
+                                            <select
+                                                className="select-input"
+                                                value={config.command_output.mode}
+                                                onChange={(e) => {
+                                                    const mode = e.target.value as 'stdout' | 'json_path' | 'regex';
+                                                    setConfig({
+                                                        ...config,
+                                                        command_output: mode === 'stdout' ? { mode } : { mode, pattern: '' },
+                                                    });
+                                                }}
+                                            >
+                                                <option value="stdout">Plain stdout</option>
+                                                <option value="json_path">JSON path</option>
+                                                <option value="regex">Regex</option>
+                                            </select>
+                                            {config.command_output.mode !== 'stdout' && (
+                                                <input
+                                                    type="text"
+                                                    className="select-input"
+                                                    placeholder={config.command_output.mode === 'json_path' ? 'segments.*.text' : '\\] (.+)'}
+                                                    value={config.command_output.pattern}
+                                                    onChange={(e) => setConfig({
+                                                        ...config,
+                                                        command_output: { mode: config.command_output.mode, pattern: e.target.value } as AppConfig['command_output'],
+                                                    })}
+                                                />
+                                            )}
+                                            <p className="input-hint">Placeholders: {'{wav}'} (16kHz mono WAV), {'{lang}'}, {'{sample_rate}'}</p>
+                                        </div>
+                                    )}
+
+                                    {config.provider === 'Wyoming' && (
+                                        <div className="whisper-config">
+                                            <label className="input-label">Server Host</label>
+                                            <input
+                                                type="text"
+                                                className="select-input"
+                                                value={config.wyoming_host}
+                                                onChange={(e) => setConfig({ ...config, wyoming_host: e.target.value })}
+                                            />
+                                            <label className="input-label">Server Port</label>
+                                            <input
+                                                type="number"
+                                                className="select-input"
+                                                value={config.wyoming_port}
+                                                onChange={(e) => setConfig({ ...config, wyoming_port: Number(e.target.value) })}
+                                            />
+                                            <p className="input-hint">Any Wyoming ASR service, e.g. wyoming-faster-whisper from Home Assistant.</p>
+                                        </div>
+                                    )}
+
+                                    {config.provider === 'Streaming' && (
+                                        <div className="whisper-config">
+                                            <label className="input-label">Streaming Endpoint</label>
+                                            <input
+                                                type="text"
+                                                className="select-input"
+                                                value={config.streaming_endpoint}
+                                                onChange={(e) => setConfig({ ...config, streaming_endpoint: e.target.value })}
+                                            />
+                                            <p className="input-hint">Audio is streamed while you speak; interim text appears in the overlay.</p>
+                                        </div>
+                                    )}
+
+                                    {isLocalProvider && (
                                         <div className="whisper-config">
                                             <label className="input-label">Model Size</label>
                                             <select
                                                 className="select-input"
-                                                value={config.whisper_model}
-                                                onChange={(e) => setConfig({ ...config, whisper_model: e.target.value })}
+                                                value={activeModel}
+                                                onChange={(e) => setConfig(config.provider === 'Onnx'
+                                                    ? { ...config, onnx_model: e.target.value }
+                                                    : { ...config, whisper_model: e.target.value })}
                                             >
-                                                <option value="tiny">Tiny (~75MB, fastest)</option>
-                                                <option value="base">Base (~142MB, recommended)</option>
-                                                <option value="small">Small (~466MB, better accuracy)</option>
-                                                <option value="medium">Medium (~1.5GB, best accuracy)</option>
+                                                {catalog.filter((model) => model.provider === config.provider).map((model) => (
+                                                    <option key={model.id} value={model.id}>{describeModel(model)}</option>
+                                                ))}
                                             </select>
 
                                             {!modelExists ? (
@@ -426,6 +905,9 @@ export const SettingsWindow: React.FC = () => {
                                                                     />
                                                                 </div>
                                                                 <p className="progress-text">Downloading... {downloadProgress}%</p>
+                                                                <button className="secondary-button" onClick={handleCancelDownload}>
+                                                                    Cancel
+                                                                </button>
                                                             </div>
                                                         ) : (
                                                             <button 
@@ -437,7 +919,7 @@ export const SettingsWindow: React.FC = () => {
                                                         )}
                                                         
                                                         {downloadError && (
-                                                            <p className="error-text">{downloadError}</p>
+                                                            <p className="error-text">{downloadError.message}. {downloadError.hint}</p>
                                                         )}
                                                         
                                                         <p className="input-hint">Model will be saved to: {modelsDir}</p>
@@ -460,6 +942,524 @@ export const SettingsWindow: React.FC = () => {
                                     )}
                                 </div>
                             </div>
+
+                            <div className="setting-card">
+                                <div className="card-header">
+                                    <Zap size={18} />
+                                    <h3>Two-Pass Dictation</h3>
+                                </div>
+                                <div className="card-body">
+                                    <label className="toggle-setting">
+                                        <div className="toggle-info">
+                                            <div className="toggle-label">Instant draft</div>
+                                            <div className="toggle-description">
+                                                Type a fast draft right away, then replace it with the refined transcript
+                                            </div>
+                                        </div>
+                                        <input
+                                            type="checkbox"
+                                            checked={config.two_pass_enabled}
+                                            onChange={(e) => setConfig({ ...config, two_pass_enabled: e.target.checked })}
+                                            className="toggle-input"
+                                        />
+                                    </label>
+                                    {config.two_pass_enabled && (
+                                        <div className="whisper-config">
+                                            <label className="input-label">Draft Whisper model</label>
+                                            <select
+                                                className="select-input"
+                                                value={config.two_pass_draft_model}
+                                                onChange={(e) => setConfig({ ...config, two_pass_draft_model: e.target.value })}
+                                            >
+                                                <option value="tiny">Tiny (fastest)</option>
+                                                <option value="base">Base</option>
+                                            </select>
+                                            <label className="input-label">Only for dictations longer than (seconds)</label>
+                                            <input
+                                                type="number"
+                                                className="select-input"
+                                                min={0}
+                                                value={config.two_pass_min_secs}
+                                                onChange={(e) => setConfig({ ...config, two_pass_min_secs: Number(e.target.value) })}
+                                            />
+                                        </div>
+                                    )}
+                                </div>
+                            </div>
+
+                            <div className="setting-card">
+                                <div className="card-header">
+                                    <FileText size={18} />
+                                    <h3>Dictation Context</h3>
+                                </div>
+                                <div className="card-body">
+                                    <label className="toggle-setting">
+                                        <div className="toggle-info">
+                                            <div className="toggle-label">Use previous dictations</div>
+                                            <div className="toggle-description">
+                                                Give the model what you just dictated into the same window, so names and capitalization stay consistent across presses
+                                            </div>
+                                        </div>
+                                        <input
+                                            type="checkbox"
+                                            checked={config.context_enabled}
+                                            onChange={(e) => setConfig({ ...config, context_enabled: e.target.checked })}
+                                            className="toggle-input"
+                                        />
+                                    </label>
+                                    {config.context_enabled && (
+                                        <div className="whisper-config">
+                                            <label className="input-label">Dictations to remember</label>
+                                            <input
+                                                type="number"
+                                                className="select-input"
+                                                min={1}
+                                                max={10}
+                                                value={config.context_max_dictations}
+                                                onChange={(e) => setConfig({ ...config, context_max_dictations: Number(e.target.value) })}
+                                            />
+                                            <label className="input-label">Forget after idle (seconds)</label>
+                                            <input
+                                                type="number"
+                                                className="select-input"
+                                                min={10}
+                                                value={config.context_idle_reset_secs}
+                                                onChange={(e) => setConfig({ ...config, context_idle_reset_secs: Number(e.target.value) })}
+                                            />
+                                            <p className="input-hint">
+                                                Context also resets when you dictate into a different window. Used by Whisper, and by external commands with a {'{prompt}'} placeholder.
+                                            </p>
+                                        </div>
+                                    )}
+                                </div>
+                            </div>
+
+                            <div className="setting-card">
+                                <div className="card-header">
+                                    <Cpu size={18} />
+                                    <h3>Whisper Decoding</h3>
+                                </div>
+                                <div className="card-body">
+                                    <div className="whisper-config">
+                                        <label className="input-label">CPU threads</label>
+                                        <input
+                                            type="number"
+                                            className="select-input"
+                                            min={1}
+                                            value={config.whisper.threads}
+                                            onChange={(e) => updateWhisper({ threads: Number(e.target.value) })}
+                                        />
+                                        <label className="input-label">Beam size (1 = greedy, fastest)</label>
+                                        <input
+                                            type="number"
+                                            className="select-input"
+                                            min={1}
+                                            max={16}
+                                            value={config.whisper.beam_size}
+                                            onChange={(e) => updateWhisper({ beam_size: Number(e.target.value) })}
+                                        />
+                                        <label className="input-label">Best of</label>
+                                        <input
+                                            type="number"
+                                            className="select-input"
+                                            min={1}
+                                            max={16}
+                                            value={config.whisper.best_of}
+                                            onChange={(e) => updateWhisper({ best_of: Number(e.target.value) })}
+                                        />
+                                        <label className="input-label">Temperature</label>
+                                        <input
+                                            type="number"
+                                            className="select-input"
+                                            min={0}
+                                            max={1}
+                                            step={0.1}
+                                            value={config.whisper.temperature}
+                                            onChange={(e) => updateWhisper({ temperature: Number(e.target.value) })}
+                                        />
+                                        <label className="input-label">Temperature fallback step (0 = no fallback)</label>
+                                        <input
+                                            type="number"
+                                            className="select-input"
+                                            min={0}
+                                            max={1}
+                                            step={0.1}
+                                            value={config.whisper.temperature_inc}
+                                            onChange={(e) => updateWhisper({ temperature_inc: Number(e.target.value) })}
+                                        />
+                                        <label className="input-label">Max segment length in characters (0 = no limit)</label>
+                                        <input
+                                            type="number"
+                                            className="select-input"
+                                            min={0}
+                                            value={config.whisper.max_segment_len}
+                                            onChange={(e) => updateWhisper({ max_segment_len: Number(e.target.value) })}
+                                        />
+                                        <label className="input-label">No-speech threshold</label>
+                                        <input
+                                            type="number"
+                                            className="select-input"
+                                            min={0}
+                                            max={1}
+                                            step={0.05}
+                                            value={config.whisper.no_speech_threshold}
+                                            onChange={(e) => updateWhisper({ no_speech_threshold: Number(e.target.value) })}
+                                        />
+                                        <label className="input-label">Silence level</label>
+                                        <input
+                                            type="number"
+                                            className="select-input"
+                                            min={0}
+                                            max={0.1}
+                                            step={0.0005}
+                                            value={config.whisper.min_speech_rms}
+                                            onChange={(e) => updateWhisper({ min_speech_rms: Number(e.target.value) })}
+                                        />
+                                        <p className="input-hint">Recordings quieter than this are discarded as silence; 0 turns the check off.</p>
+                                    </div>
+                                    <label className="toggle-setting">
+                                        <div className="toggle-info">
+                                            <div className="toggle-label">Suppress non-speech tokens</div>
+                                            <div className="toggle-description">
+                                                Keep music notes, sound effects and similar markers out of transcripts
+                                            </div>
+                                        </div>
+                                        <input
+                                            type="checkbox"
+                                            checked={config.whisper.suppress_non_speech}
+                                            onChange={(e) => updateWhisper({ suppress_non_speech: e.target.checked })}
+                                            className="toggle-input"
+                                        />
+                                    </label>
+                                </div>
+                            </div>
+
+                            <div className="setting-card">
+                                <div className="card-header">
+                                    <Cpu size={18} />
+                                    <h3>Find the Best Model for This Computer</h3>
+                                </div>
+                                <div className="card-body">
+                                    <p className="toggle-description">
+                                        Times a short clip on every installed Whisper model at a few thread counts and picks the most accurate one that is fast enough.
+                                    </p>
+                                    <div className="whisper-config">
+                                        <label className="input-label">Longest acceptable wait for a 5-second dictation (ms)</label>
+                                        <input
+                                            type="number"
+                                            className="select-input"
+                                            min={200}
+                                            step={100}
+                                            value={config.latency_target_ms}
+                                            onChange={(e) => setConfig({ ...config, latency_target_ms: Number(e.target.value) })}
+                                        />
+                                    </div>
+                                    <button className="secondary-button" onClick={runTuning} disabled={tuning.running}>
+                                        {tuning.running ? `Benchmarking… (${tuning.trials.length} runs)` : 'Run Benchmark'}
+                                    </button>
+                                    {tuning.error && <p className="toggle-description">{tuning.error.message}. {tuning.error.hint}</p>}
+                                    {tuning.trials.map((trial) => (
+                                        <p key={`${trial.model}-${trial.threads}`} className="input-hint">
+                                            {trial.model} × {trial.threads} threads: {trial.error
+                                                ? `failed (${trial.error})`
+                                                : `~${trial.expected_latency_ms} ms, RTF ${trial.rtf.toFixed(2)}${trial.peak_memory_mb !== null ? `, ${formatMb(trial.peak_memory_mb)} RAM` : ''}`}
+                                        </p>
+                                    ))}
+                                    {tuning.report && tuning.report.skipped.length > 0 && (
+                                        <p className="input-hint">Skipped {tuning.report.skipped.join(', ')}: smaller models were already too slow.</p>
+                                    )}
+                                    {tuning.report?.recommendation && (
+                                        <div className="whisper-config">
+                                            <p className="toggle-description">
+                                                {tuning.report.recommendation.meets_target
+                                                    ? `Recommended: ${tuning.report.recommendation.model} with ${tuning.report.recommendation.threads} threads (~${tuning.report.recommendation.expected_latency_ms} ms)`
+                                                    : `Nothing meets the target on ${tuning.report.logical_cores} cores. Fastest: ${tuning.report.recommendation.model} with ${tuning.report.recommendation.threads} threads (~${tuning.report.recommendation.expected_latency_ms} ms)`}
+                                            </p>
+                                            <button
+                                                className="secondary-button"
+                                                onClick={() => applyRecommendation(tuning.report!.recommendation!.model, tuning.report!.recommendation!.threads)}
+                                            >
+                                                Use This
+                                            </button>
+                                        </div>
+                                    )}
+                                </div>
+                            </div>
+
+                            <div className="setting-card">
+                                <div className="card-header">
+                                    <Download size={18} />
+                                    <h3>Installed Models</h3>
+                                </div>
+                                <div className="card-body">
+                                    {inventory && (
+                                        <p className="input-hint">
+                                            {formatBytes(inventory.total_bytes)} used in {inventory.models_dir}
+                                            {inventory.partial_bytes > 0 && <>, {formatBytes(inventory.partial_bytes)} in unfinished downloads</>}
+                                        </p>
+                                    )}
+                                    {inventory?.models.length === 0 && <p className="toggle-description">No models installed yet.</p>}
+                                    {inventory?.models.map((model) => (
+                                        <div key={model.id} className="whisper-config">
+                                            <label className="input-label">
+                                                {model.display_name} — {formatBytes(model.size_bytes)}
+                                                {' '}{model.verification === 'verified' ? '✅ Verified' : model.verification === 'corrupt' ? '❌ Corrupt' : '⚪ Not verified'}
+                                            </label>
+                                            <div>
+                                                <button
+                                                    className="secondary-button"
+                                                    disabled={modelAction.busy}
+                                                    onClick={() => runModelAction(async () => {
+                                                        const status = await invoke<string>('verify_model', { modelName: model.id });
+                                                        return `${model.display_name}: ${status}`;
+                                                    })}
+                                                >
+                                                    Verify
+                                                </button>
+                                                <button
+                                                    className="secondary-button"
+                                                    disabled={modelAction.busy}
+                                                    onClick={() => runModelAction(async () => {
+                                                        const freed = await invoke<number>('delete_model', { modelName: model.id });
+                                                        return `Deleted ${model.display_name}, freed ${formatBytes(freed)}`;
+                                                    })}
+                                                >
+                                                    Delete
+                                                </button>
+                                            </div>
+                                        </div>
+                                    ))}
+                                    <div className="whisper-config">
+                                        <label className="input-label">Import a model file</label>
+                                        <input
+                                            type="text"
+                                            className="select-input"
+                                            placeholder="Path to a ggml-*.bin file"
+                                            value={importPath}
+                                            onChange={(e) => setImportPath(e.target.value)}
+                                        />
+                                        <p className="input-hint">For offline machines: the file is checked against the catalog before it is copied.</p>
+                                    </div>
+                                    <button
+                                        className="secondary-button"
+                                        disabled={modelAction.busy || !importPath.trim()}
+                                        onClick={() => runModelAction(async () => {
+                                            const model = await invoke<CatalogModel>('import_model', { path: importPath.trim(), modelName: null });
+                                            setImportPath('');
+                                            return `Imported ${model.display_name}`;
+                                        })}
+                                    >
+                                        {modelAction.busy ? 'Working…' : 'Import'}
+                                    </button>
+                                    {modelAction.message && <p className="toggle-description">{modelAction.message}</p>}
+                                </div>
+                            </div>
+
+                            <div className="setting-card">
+                                <div className="card-header">
+                                    <Zap size={18} />
+                                    <h3>Provider Status</h3>
+                                </div>
+                                <div className="card-body">
+                                    {providerStatus.length === 0 ? (
+                                        <p className="toggle-description">Checking providers…</p>
+                                    ) : (
+                                        providerStatus.map((report) => (
+                                            <div key={report.position} className="whisper-config">
+                                                <label className="input-label">
+                                                    {report.position === 0 ? 'Primary' : `Fallback ${report.position}`}: {report.provider}
+                                                    {' '}— {report.health.state === 'ready' ? '✅ Ready' : report.health.state === 'degraded' ? '⚠️ Unverified' : '❌ Unavailable'}
+                                                </label>
+                                                <p className="input-hint">
+                                                    {report.health.message}
+                                                    {report.health.error && <> — {report.health.error.hint}</>}
+                                                </p>
+                                                <p className="input-hint">
+                                                    {describeCapabilities(report)}
+                                                    {report.warm_up_ms !== null && !report.warm_up_error && <> · warmed up in {report.warm_up_ms} ms</>}
+                                                    {report.warm_up_error && <> · warm-up failed: {report.warm_up_error}</>}
+                                                </p>
+                                            </div>
+                                        ))
+                                    )}
+                                    <button className="secondary-button" onClick={recheckProviders} disabled={checkingProviders}>
+                                        {checkingProviders ? 'Checking…' : 'Re-check'}
+                                    </button>
+                                </div>
+                            </div>
+
+                            <div className="setting-card">
+                                <div className="card-header">
+                                    <Shield size={18} />
+                                    <h3>Shadow Evaluation</h3>
+                                </div>
+                                <div className="card-body">
+                                    <p className="toggle-description">
+                                        Transcribe every dictation with a second provider in the background and log how often it agrees. What gets typed is unchanged.
+                                    </p>
+                                    <div className="whisper-config">
+                                        <label className="input-label">Shadow provider</label>
+                                        <select
+                                            className="select-input"
+                                            value={config.shadow_provider}
+                                            onChange={(e) => setConfig({ ...config, shadow_provider: e.target.value })}
+                                        >
+                                            <option value="">Off</option>
+                                            <option value="Whisper">Whisper (Local)</option>
+                                            <option value="Onnx">ONNX (Local, Moonshine)</option>
+                                            <option value="Wyoming">Wyoming Server</option>
+                                            <option value="Command">External Command</option>
+                                        </select>
+                                        {config.shadow_provider === 'Whisper' && (
+                                            <>
+                                                <label className="input-label">Shadow Whisper model</label>
+                                                <select
+                                                    className="select-input"
+                                                    value={config.shadow_whisper_model}
+                                                    onChange={(e) => setConfig({ ...config, shadow_whisper_model: e.target.value })}
+                                                >
+                                                    <option value="">Same as active model</option>
+                                                    {catalog.filter((model) => model.provider === 'Whisper').map((model) => (
+                                                        <option key={model.id} value={model.id}>{model.display_name}</option>
+                                                    ))}
+                                                </select>
+                                            </>
+                                        )}
+                                    </div>
+                                    <button
+                                        className="secondary-button"
+                                        onClick={() => invoke<ShadowReport>('get_shadow_report', { limit: null }).then(setShadowReport)}
+                                    >
+                                        Show agreement report
+                                    </button>
+                                    {shadowReport && (
+                                        shadowReport.samples === 0 ? (
+                                            <p className="toggle-description">No shadow comparisons recorded yet.</p>
+                                        ) : (
+                                            <p className="toggle-description">
+                                                Agreed on {shadowReport.agreements} of {shadowReport.samples} dictations
+                                                ({(shadowReport.agreement_rate * 100).toFixed(1)}%), mean WER {(shadowReport.mean_wer * 100).toFixed(1)}%,
+                                                {' '}{shadowReport.shadow_failures} shadow failures. Latency: {Math.round(shadowReport.mean_primary_latency_ms)} ms active
+                                                vs {Math.round(shadowReport.mean_shadow_latency_ms)} ms shadow.
+                                            </p>
+                                        )
+                                    )}
+                                </div>
+                            </div>
+
+                            <div className="setting-card">
+                                <div className="card-header">
+                                    <Power size={18} />
+                                    <h3>Offline Queue</h3>
+                                </div>
+                                <div className="card-body">
+                                    <label className="toggle-setting">
+                                        <div className="toggle-info">
+                                            <div className="toggle-label">Keep cloud dictations while offline</div>
+                                            <div className="toggle-description">
+                                                When the network is down, save the recording and transcribe it once the connection is back. The result goes to history and the clipboard.
+                                            </div>
+                                        </div>
+                                        <input
+                                            type="checkbox"
+                                            checked={config.offline_queue_enabled}
+                                            onChange={(e) => setConfig({ ...config, offline_queue_enabled: e.target.checked })}
+                                            className="toggle-input"
+                                        />
+                                    </label>
+                                    {offlineJobs.length > 0 && (
+                                        <p className="input-hint">
+                                            {offlineJobs.length} dictation{offlineJobs.length === 1 ? '' : 's'} waiting
+                                            ({offlineJobs.reduce((secs, job) => secs + job.audio_secs, 0).toFixed(0)}s of audio),
+                                            next retry {new Date(Math.min(...offlineJobs.map((job) => job.next_attempt_ms))).toLocaleTimeString()}.
+                                            {offlineJobs[0].last_error && <> Last error: {offlineJobs[0].last_error}</>}
+                                        </p>
+                                    )}
+                                </div>
+                            </div>
+
+                            <div className="setting-card">
+                                <div className="card-header">
+                                    <Cpu size={18} />
+                                    <h3>Processing Queue</h3>
+                                </div>
+                                <div className="card-body">
+                                    <p className="input-hint">
+                                        You can start a new dictation while earlier ones are still transcribing.
+                                        Text is always inserted in the order you spoke.
+                                    </p>
+                                    <div className="whisper-config">
+                                        <label className="input-label">When the queue is full</label>
+                                        <select
+                                            className="select-input"
+                                            value={config.queue_policy}
+                                            onChange={(e) => setConfig({ ...config, queue_policy: e.target.value as AppConfig['queue_policy'] })}
+                                        >
+                                            <option value="queue">Keep queuing</option>
+                                            <option value="drop">Drop new dictations</option>
+                                            <option value="merge">Merge into the last waiting dictation</option>
+                                        </select>
+                                        <label className="input-label">Queue size</label>
+                                        <input
+                                            type="number"
+                                            className="select-input"
+                                            min={1}
+                                            value={config.max_queued_jobs}
+                                            onChange={(e) => setConfig({ ...config, max_queued_jobs: Number(e.target.value) })}
+                                        />
+                                    </div>
+                                </div>
+                            </div>
+
+                            <div className="setting-card">
+                                <div className="card-header">
+                                    <Shield size={18} />
+                                    <h3>Fallback Chain</h3>
+                                </div>
+                                <div className="card-body">
+                                    <p className="input-hint">
+                                        Providers are tried in order. The next one runs on errors, timeouts or empty results.
+                                        Leave empty to use only the engine selected above.
+                                    </p>
+                                    {config.provider_chain.map((entry, index) => (
+                                        <div key={index} className="whisper-config">
+                                            <label className="input-label">Step {index + 1}</label>
+                                            <select
+                                                className="select-input"
+                                                value={entry.provider}
+                                                onChange={(e) => updateChainEntry(index, { provider: e.target.value })}
+                                            >
+                                                {CHAIN_PROVIDERS.map((p) => (
+                                                    <option key={p} value={p}>{p}</option>
+                                                ))}
+                                            </select>
+                                            <label className="input-label">Timeout (seconds)</label>
+                                            <input
+                                                type="number"
+                                                className="select-input"
+                                                min={1}
+                                                value={entry.timeout_ms / 1000}
+                                                onChange={(e) => updateChainEntry(index, { timeout_ms: Number(e.target.value) * 1000 })}
+                                            />
+                                            <button
+                                                className="download-button"
+                                                onClick={() => setConfig({ ...config, provider_chain: config.provider_chain.filter((_, i) => i !== index) })}
+                                            >
+                                                Remove
+                                            </button>
+                                        </div>
+                                    ))}
+                                    <button
+                                        className="download-button"
+                                        onClick={() => setConfig({
+                                            ...config,
+                                            provider_chain: [...config.provider_chain, { provider: 'Whisper', timeout_ms: 60000 }],
+                                        })}
+                                    >
+                                        Add Provider
+                                    </button>
+                                </div>
+                            </div>
                         </div>
                     )}
 
@@ -513,6 +1513,84 @@ export const SettingsWindow: React.FC = () => {
                                     </div>
                                 </div>
                             </div>
+
+                            <div className="setting-card">
+                                <div className="card-header">
+                                    <Download size={18} />
+                                    <h3>Model Catalog</h3>
+                                </div>
+                                <div className="card-body">
+                                    <div className="whisper-config">
+                                        <label className="input-label">Catalog URL</label>
+                                        <input
+                                            type="text"
+                                            className="select-input"
+                                            placeholder="Built-in catalog only"
+                                            value={config.model_catalog_url}
+                                            onChange={(e) => setConfig({ ...config, model_catalog_url: e.target.value })}
+                                        />
+                                        <p className="input-hint">A newer catalog adds models without updating the app.</p>
+                                    </div>
+                                    <button className="secondary-button" onClick={refreshCatalog} disabled={!config.model_catalog_url}>
+                                        Check for new models
+                                    </button>
+                                    {catalogStatus && <p className="toggle-description">{catalogStatus}</p>}
+                                </div>
+                            </div>
+
+                            <div className="setting-card">
+                                <div className="card-header">
+                                    <Cpu size={18} />
+                                    <h3>whisper.cpp Program</h3>
+                                </div>
+                                <div className="card-body">
+                                    <div className="whisper-config">
+                                        <label className="input-label">Program path</label>
+                                        <input
+                                            type="text"
+                                            className="select-input"
+                                            placeholder="Bundled copy, then PATH"
+                                            value={config.whisper_binary_path}
+                                            onChange={(e) => setConfig({ ...config, whisper_binary_path: e.target.value })}
+                                        />
+                                        <p className="input-hint">Path to whisper-cli (or the folder containing it). Requires whisper.cpp 1.6 or newer.</p>
+                                    </div>
+                                    <button className="secondary-button" onClick={checkWhisperBinary}>
+                                        Check
+                                    </button>
+                                    {whisperBinary && <p className="toggle-description">{whisperBinary}</p>}
+                                </div>
+                            </div>
+
+                            <div className="setting-card">
+                                <div className="card-header">
+                                    <FileText size={18} />
+                                    <h3>Storage</h3>
+                                </div>
+                                <div className="card-body">
+                                    {storagePaths && (
+                                        <p className="toggle-description">
+                                            {storagePaths.portable
+                                                ? 'Portable mode: all files are kept next to the app.'
+                                                : 'Installed mode. Put a file named "portable" next to the app to keep everything beside it.'}
+                                            <br />Settings: {storagePaths.config_dir}
+                                            <br />Data: {storagePaths.data_dir}
+                                            <br />Logs: {storagePaths.log_dir}
+                                        </p>
+                                    )}
+                                    <div className="whisper-config">
+                                        <label className="input-label">Models folder</label>
+                                        <input
+                                            type="text"
+                                            className="select-input"
+                                            placeholder={storagePaths?.default_models_dir ?? 'Default'}
+                                            value={config.custom_models_dir}
+                                            onChange={(e) => setConfig({ ...config, custom_models_dir: e.target.value })}
+                                        />
+                                        <p className="input-hint">Keep models on another drive. Existing models are not moved; copy them over or import them.</p>
+                                    </div>
+                                </div>
+                            </div>
                         </div>
                     )}
 